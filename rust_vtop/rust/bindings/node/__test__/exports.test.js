@@ -0,0 +1,55 @@
+const assert = require('node:assert/strict');
+const fs = require('node:fs');
+const path = require('node:path');
+const { test } = require('node:test');
+
+const binding = require('..');
+
+// index.d.ts is written by hand, so these checks keep it in step with
+// index.js and with the addon index.js wraps.
+const declarations = fs.readFileSync(path.join(__dirname, '..', 'index.d.ts'), 'utf8');
+
+function declaredClass(name) {
+    const body = declarations.match(new RegExp(`export class ${name} [^{]*\\{([\\s\\S]*?)\\n\\}`));
+    assert.ok(body, `index.d.ts declares ${name}`);
+    const members = { static: [], instance: [] };
+    for (const line of body[1].split('\n')) {
+        const match = line.match(/^\s+(static\s+)?(?:readonly\s+)?(\w+)\s*[(:]/);
+        if (match && match[2] !== 'constructor') {
+            members[match[1] ? 'static' : 'instance'].push(match[2]);
+        }
+    }
+    return members;
+}
+
+function methods(target) {
+    return Object.getOwnPropertyNames(target)
+        .filter((name) => !['constructor', 'length', 'name', 'prototype'].includes(name))
+        .sort();
+}
+
+function loadAddon() {
+    return require(process.env.VITAPMATE_NODE_BINARY || path.join(__dirname, '..', 'vitapmate_node.node'));
+}
+
+test('index.d.ts declares exactly the exported classes', () => {
+    const declared = [...declarations.matchAll(/^export class (\w+)/gm)].map((m) => m[1]).sort();
+    assert.deepEqual(Object.keys(binding).sort(), declared);
+});
+
+test('VtopSession matches its declaration', () => {
+    const declared = declaredClass('VtopSession');
+    assert.deepEqual(methods(binding.VtopSession.prototype), declared.instance.sort());
+    assert.deepEqual(methods(binding.VtopSession), declared.static.sort());
+});
+
+test('VtopSession wraps every method of the addon', () => {
+    const addon = loadAddon();
+    assert.deepEqual(methods(binding.VtopSession.prototype), methods(addon.VtopSession.prototype));
+});
+
+test('VtopError carries the declared fields', () => {
+    const declared = declaredClass('VtopError').instance.sort();
+    const error = new binding.VtopError({ message: 'x', retryable: false, authProblem: true });
+    assert.deepEqual(Object.keys(error).sort(), declared);
+});
@@ -0,0 +1,103 @@
+const assert = require('node:assert/strict');
+const http = require('node:http');
+const { test } = require('node:test');
+
+const { VtopSession, VtopError } = require('..');
+
+// A VTOP stand-in that answers every request with the given status.
+async function fakeVtop(status) {
+    const server = http.createServer((req, res) => {
+        res.writeHead(status, { 'content-type': 'text/html' });
+        res.end('<html><body>down for maintenance</body></html>');
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+    const { port } = server.address();
+    return { server, baseUrl: `http://127.0.0.1:${port}` };
+}
+
+test('new sessions are not authenticated', async () => {
+    const session = new VtopSession(' 21bce0001 ', 'secret', { baseUrl: 'http://127.0.0.1:9' });
+    assert.equal(session.username, '21BCE0001');
+    assert.equal(await session.isAuthenticated(), false);
+});
+
+test('fetching before login rejects with an auth problem', async () => {
+    const session = new VtopSession('21BCE0001', 'secret', { baseUrl: 'http://127.0.0.1:9' });
+    await assert.rejects(session.semesters(), (error) => {
+        assert.ok(error instanceof VtopError);
+        assert.equal(error.authProblem, true);
+        assert.equal(error.retryable, false);
+        assert.match(error.message, /log in/i);
+        return true;
+    });
+});
+
+test('a VTOP outage is reported as retryable with its status', async () => {
+    const { server, baseUrl } = await fakeVtop(503);
+    try {
+        const session = new VtopSession('21BCE0001', 'secret', { baseUrl, timeoutSeconds: 5 });
+        await assert.rejects(session.login(), (error) => {
+            assert.ok(error instanceof VtopError);
+            assert.equal(error.authProblem, false);
+            assert.equal(error.retryable, true);
+            assert.equal(error.status, 503);
+            return true;
+        });
+        assert.equal(await session.isAuthenticated(), false);
+    } finally {
+        server.close();
+    }
+});
+
+// A logged-in VTOP stand-in that holds every data request until `expected`
+// of them are in flight at once, or `patienceMs` passes, and reports the
+// most it saw together.
+async function barrierVtop(expected, patienceMs) {
+    let inFlight = 0;
+    let maxInFlight = 0;
+    let release;
+    const allArrived = new Promise((resolve) => {
+        release = resolve;
+    });
+    const server = http.createServer(async (req, res) => {
+        if (req.url === '/vtop/open/page') {
+            res.writeHead(200, { 'content-type': 'text/html' });
+            res.end('<input name="_csrf" value="t0k3n">');
+            return;
+        }
+        inFlight += 1;
+        maxInFlight = Math.max(maxInFlight, inFlight);
+        if (inFlight >= expected) {
+            release();
+        }
+        await Promise.race([allArrived, new Promise((resolve) => setTimeout(resolve, patienceMs))]);
+        inFlight -= 1;
+        res.writeHead(200, { 'content-type': 'text/html' });
+        res.end('<html><body></body></html>');
+    });
+    await new Promise((resolve) => server.listen(0, '127.0.0.1', resolve));
+    const { port } = server.address();
+    return {
+        server,
+        baseUrl: `http://127.0.0.1:${port}`,
+        maxInFlight: () => maxInFlight,
+    };
+}
+
+test('calls on one session overlap', async () => {
+    const { server, baseUrl, maxInFlight } = await barrierVtop(2, 5000);
+    try {
+        const session = new VtopSession('21BCE0001', 'secret', {
+            baseUrl,
+            cookie: 'SESSION=abc',
+            timeoutSeconds: 10,
+        });
+        await session.login();
+        // Whether the empty pages parse does not matter, only that neither
+        // request waited for the other to finish.
+        await Promise.allSettled([session.timetable('AP2024252'), session.marks('AP2024252')]);
+        assert.equal(maxInFlight(), 2);
+    } finally {
+        server.close();
+    }
+});
@@ -0,0 +1,159 @@
+// Field names follow the serde output of rust_lib_vitapmate's types.rs.
+// __test__/exports.test.js checks the classes against index.js and the addon.
+
+export interface SessionOptions {
+    /** Cookie from `VtopSession.cookie()` to resume an earlier session. */
+    cookie?: string;
+    baseUrl?: string;
+    timeoutSeconds?: number;
+}
+
+export class VtopError extends Error {
+    readonly name: 'VtopError';
+    readonly retryable: boolean;
+    readonly authProblem: boolean;
+    /** HTTP status VTOP answered with, when there was one. */
+    readonly status: number | null;
+}
+
+export interface SemesterInfo {
+    id: string;
+    name: string;
+}
+
+export interface SemesterData {
+    semesters: SemesterInfo[];
+    update_time: number;
+}
+
+export interface TimetableSlot {
+    serial: string;
+    day: string;
+    slot: string;
+    course_code: string;
+    course_type: string;
+    room_no: string;
+    block: string;
+    start_time: string;
+    end_time: string;
+    name: string;
+    is_lab: boolean;
+    faculty: string;
+}
+
+export interface TimetableData {
+    slots: TimetableSlot[];
+    semester_id: string;
+    update_time: number;
+}
+
+export interface AttendanceRecord {
+    serial: string;
+    category: string;
+    course_name: string;
+    course_code: string;
+    course_type: string;
+    faculty_detail: string;
+    classes_attended: string;
+    total_classes: string;
+    attendance_percentage: string;
+    attendence_fat_cat: string;
+    debar_status: string;
+    course_id: string;
+}
+
+export interface AttendanceData {
+    records: AttendanceRecord[];
+    semester_id: string;
+    update_time: number;
+}
+
+export interface FullAttendanceRecord {
+    serial: string;
+    date: string;
+    slot: string;
+    day_time: string;
+    status: string;
+    remark: string;
+}
+
+export interface FullAttendanceData {
+    records: FullAttendanceRecord[];
+    semester_id: string;
+    update_time: number;
+    course_id: string;
+    course_type: string;
+}
+
+export interface MarksRecordEach {
+    serial: string;
+    markstitle: string;
+    maxmarks: string;
+    weightage: string;
+    status: string;
+    scoredmark: string;
+    weightagemark: string;
+    remark: string;
+}
+
+export interface MarksRecord {
+    serial: string;
+    coursecode: string;
+    coursetitle: string;
+    coursetype: string;
+    faculity: string;
+    slot: string;
+    marks: MarksRecordEach[];
+}
+
+export interface MarksData {
+    records: MarksRecord[];
+    semester_id: string;
+    update_time: number;
+}
+
+export interface ExamScheduleRecord {
+    serial: string;
+    slot: string;
+    course_name: string;
+    course_code: string;
+    course_type: string;
+    course_id: string;
+    exam_date: string;
+    exam_session: string;
+    reporting_time: string;
+    exam_time: string;
+    venue: string;
+    seat_location: string;
+    seat_no: string;
+}
+
+export interface PerExamScheduleRecord {
+    records: ExamScheduleRecord[];
+    exam_type: string;
+}
+
+export interface ExamScheduleData {
+    exams: PerExamScheduleRecord[];
+    semester_id: string;
+    update_time: number;
+}
+
+/** One VTOP login, meant to be kept between requests. Fetches on a session run concurrently; `login()` waits for them. */
+export class VtopSession {
+    /** Throws `VtopError` when the configuration (vtop.toml, VTOP_* variables) is invalid. */
+    constructor(username: string, password: string, options?: SessionOptions);
+    /** Creates a session and logs in. */
+    static login(username: string, password: string, options?: SessionOptions): Promise<VtopSession>;
+    /** Upper-cased registration number. */
+    readonly username: string;
+    login(): Promise<void>;
+    isAuthenticated(): Promise<boolean>;
+    cookie(): Promise<string>;
+    semesters(): Promise<SemesterData>;
+    timetable(semesterId: string): Promise<TimetableData>;
+    attendance(semesterId: string): Promise<AttendanceData>;
+    fullAttendance(semesterId: string, courseId: string, courseType: string): Promise<FullAttendanceData>;
+    marks(semesterId: string): Promise<MarksData>;
+    examSchedule(semesterId: string): Promise<ExamScheduleData>;
+}
@@ -0,0 +1,104 @@
+const fs = require('node:fs');
+const path = require('node:path');
+
+function loadBinding() {
+    const candidates = [
+        process.env.VITAPMATE_NODE_BINARY,
+        path.join(__dirname, 'vitapmate_node.node'),
+    ].filter(Boolean);
+    for (const file of candidates) {
+        if (fs.existsSync(file)) {
+            return require(file);
+        }
+    }
+    throw new Error(
+        'vitapmate_node.node is missing; run `npm run build` in rust_vtop/rust/bindings/node',
+    );
+}
+
+const native = loadBinding();
+
+class VtopError extends Error {
+    constructor({ message, retryable, authProblem, status }) {
+        super(message);
+        this.name = 'VtopError';
+        this.retryable = retryable;
+        this.authProblem = authProblem;
+        this.status = status ?? null;
+    }
+}
+
+// The addon encodes VTOP failures as JSON in the error message.
+function rethrow(error) {
+    let payload;
+    try {
+        payload = JSON.parse(error.message);
+    } catch {
+        throw error;
+    }
+    if (!payload || payload.vtop !== true) {
+        throw error;
+    }
+    throw new VtopError(payload);
+}
+
+class VtopSession {
+    #inner;
+
+    constructor(username, password, options) {
+        try {
+            this.#inner = new native.VtopSession(username, password, options);
+        } catch (error) {
+            rethrow(error);
+        }
+    }
+
+    // Creates a session and logs in.
+    static async login(username, password, options) {
+        const session = new VtopSession(username, password, options);
+        await session.login();
+        return session;
+    }
+
+    get username() {
+        return this.#inner.username;
+    }
+
+    login() {
+        return this.#inner.login().catch(rethrow);
+    }
+
+    isAuthenticated() {
+        return this.#inner.isAuthenticated();
+    }
+
+    cookie() {
+        return this.#inner.cookie().catch(rethrow);
+    }
+
+    semesters() {
+        return this.#inner.semesters().catch(rethrow);
+    }
+
+    timetable(semesterId) {
+        return this.#inner.timetable(semesterId).catch(rethrow);
+    }
+
+    attendance(semesterId) {
+        return this.#inner.attendance(semesterId).catch(rethrow);
+    }
+
+    fullAttendance(semesterId, courseId, courseType) {
+        return this.#inner.fullAttendance(semesterId, courseId, courseType).catch(rethrow);
+    }
+
+    marks(semesterId) {
+        return this.#inner.marks(semesterId).catch(rethrow);
+    }
+
+    examSchedule(semesterId) {
+        return this.#inner.examSchedule(semesterId).catch(rethrow);
+    }
+}
+
+module.exports = { VtopSession, VtopError };
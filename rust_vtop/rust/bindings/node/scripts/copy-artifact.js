@@ -0,0 +1,24 @@
+// Copies the cargo build output next to index.js as `vitapmate_node.node`.
+const fs = require('node:fs');
+const path = require('node:path');
+
+const profile = process.argv[2] || 'release';
+const library = {
+    linux: 'libvitapmate_node.so',
+    darwin: 'libvitapmate_node.dylib',
+    win32: 'vitapmate_node.dll',
+}[process.platform];
+
+if (!library) {
+    console.error(`Unsupported platform: ${process.platform}`);
+    process.exit(1);
+}
+
+// The binding is a member of the rust_vtop/rust workspace, so cargo builds
+// into that workspace's target directory.
+const targetDir = process.env.CARGO_TARGET_DIR || path.resolve(__dirname, '../../../target');
+const source = path.join(targetDir, profile, library);
+const destination = path.resolve(__dirname, '../vitapmate_node.node');
+
+fs.copyFileSync(source, destination);
+console.log(`${source} -> ${destination}`);
@@ -0,0 +1,139 @@
+#[macro_use]
+extern crate napi_derive;
+
+use std::sync::Arc;
+
+use napi::{Error, Result, Status};
+use rust_lib_vitapmate::api::vtop::vtop_client::{VtopClient, VtopError};
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop_get_client as vtop;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+// Overrides applied on top of `VtopConfig::load()`, i.e. `vtop.toml` and the
+// `VTOP_*` environment variables.
+#[napi(object)]
+pub struct SessionOptions {
+    // Cookie from `VtopSession.cookie()` to resume an earlier session.
+    pub cookie: Option<String>,
+    pub base_url: Option<String>,
+    pub timeout_seconds: Option<u32>,
+}
+
+// One VTOP login. Keep it around between requests; fetches on the same
+// session run concurrently, a login waits for them.
+#[napi]
+pub struct VtopSession {
+    username: String,
+    client: Arc<RwLock<VtopClient>>,
+}
+
+#[napi]
+impl VtopSession {
+    #[napi(constructor)]
+    pub fn new(username: String, password: String, options: Option<SessionOptions>) -> Result<Self> {
+        let mut config = VtopConfig::load().map_err(failure)?;
+        let mut cookie = None;
+        if let Some(options) = options {
+            if let Some(base_url) = options.base_url {
+                config.base_url = base_url;
+            }
+            if let Some(timeout) = options.timeout_seconds {
+                config.timeout_seconds = timeout.into();
+            }
+            cookie = options.cookie;
+        }
+        let username = username.trim().to_uppercase();
+        let client = vtop::get_vtop_client_with_config(config, username.clone(), password, cookie)
+            .map_err(failure)?;
+        Ok(Self {
+            username,
+            client: Arc::new(RwLock::new(client)),
+        })
+    }
+
+    #[napi(getter)]
+    pub fn username(&self) -> String {
+        self.username.clone()
+    }
+
+    #[napi]
+    pub async fn login(&self) -> Result<()> {
+        let mut client = self.client.write().await;
+        vtop::vtop_client_login(&mut client).await.map_err(failure)
+    }
+
+    #[napi]
+    pub async fn is_authenticated(&self) -> bool {
+        let client = self.client.read().await;
+        vtop::fetch_is_auth(&client).await
+    }
+
+    // Session cookie for `SessionOptions.cookie`.
+    #[napi]
+    pub async fn cookie(&self) -> Result<String> {
+        let client = self.client.read().await;
+        let cookie = vtop::fetch_cookies(&client).await.map_err(failure)?;
+        Ok(String::from_utf8_lossy(&cookie).into_owned())
+    }
+
+    #[napi(ts_return_type = "Promise<SemesterData>")]
+    pub async fn semesters(&self) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_semesters(&client).await)
+    }
+
+    #[napi(ts_return_type = "Promise<TimetableData>")]
+    pub async fn timetable(&self, semester_id: String) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_timetable(&client, semester_id).await)
+    }
+
+    #[napi(ts_return_type = "Promise<AttendanceData>")]
+    pub async fn attendance(&self, semester_id: String) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_attendance(&client, semester_id).await)
+    }
+
+    #[napi(ts_return_type = "Promise<FullAttendanceData>")]
+    pub async fn full_attendance(
+        &self,
+        semester_id: String,
+        course_id: String,
+        course_type: String,
+    ) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_full_attendance(&client, semester_id, course_id, course_type, None).await)
+    }
+
+    #[napi(ts_return_type = "Promise<MarksData>")]
+    pub async fn marks(&self, semester_id: String) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_marks(&client, semester_id).await)
+    }
+
+    #[napi(ts_return_type = "Promise<ExamScheduleData>")]
+    pub async fn exam_schedule(&self, semester_id: String) -> Result<Value> {
+        let client = self.client.read().await;
+        to_js(vtop::fetch_exam_shedule(&client, semester_id).await)
+    }
+}
+
+fn to_js<T: Serialize>(result: std::result::Result<T, VtopError>) -> Result<Value> {
+    let data = result.map_err(failure)?;
+    serde_json::to_value(data).map_err(|e| Error::new(Status::GenericFailure, e.to_string()))
+}
+
+// N-API errors only carry a message, so the classification travels in it as
+// JSON; `index.js` turns it back into a `VtopError` with typed fields.
+fn failure(error: VtopError) -> Error {
+    let payload = serde_json::json!({
+        "vtop": true,
+        "message": error.user_message(),
+        "retryable": error.is_retryable(),
+        "authProblem": error.is_auth_problem(),
+        "status": error.status(),
+    });
+    Error::new(Status::GenericFailure, payload.to_string())
+}
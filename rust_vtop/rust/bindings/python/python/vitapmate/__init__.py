@@ -0,0 +1,187 @@
+"""Python bindings for the VTOP client in rust_lib_vitapmate.
+
+The bindings cover logging in, semesters, timetable, attendance, marks and the
+exam schedule, plus offline parsers for those pages. Course pages, digital
+assignments, fees, hostel forms, grades, curriculum, announcements, proctor
+details, biometric logs and feedback are there too when the extension is built
+with the ``unverified-endpoints`` feature (``UNVERIFIED_ENDPOINTS`` says
+whether it was); without it, their calls raise ``NotImplementedError``. The
+campus Wi-Fi portals are only available from Rust and the server for now.
+
+Tests run against a debug build of the extension::
+
+    cargo build -p vitapmate_python --features unverified-endpoints
+    python scripts/copy_artifact.py debug
+    PYTHONPATH=python python -m unittest discover -s tests
+"""
+
+from ._native import UNVERIFIED_ENDPOINTS, VtopError
+from .client import VtopClient
+from .models import (
+    LEAVE,
+    OUTING,
+    AllocationData,
+    Announcement,
+    AnnouncementsData,
+    ArrearsData,
+    AttendanceData,
+    AttendanceRecord,
+    BiometricDay,
+    BiometricLogData,
+    BiometricPunch,
+    CourseAllocation,
+    CourseGrade,
+    CourseMaterial,
+    CoursePageCourse,
+    CoursePageCourses,
+    CoursePageData,
+    CoursePageLecture,
+    CurriculumCategory,
+    CurriculumCourse,
+    CurriculumData,
+    DigitalAssignment,
+    DigitalAssignmentCourse,
+    DigitalAssignmentData,
+    DigitalAssignmentFailure,
+    DigitalAssignmentsReport,
+    DownloadedMaterial,
+    ExamScheduleData,
+    ExamScheduleRecord,
+    FacultyInfo,
+    FeeDue,
+    FeeDuesData,
+    FeePayment,
+    FeedbackForm,
+    FeedbackFormsData,
+    FeedbackQuestion,
+    FeedbackQuestionsData,
+    FeedbackResponse,
+    FeedbackSubmission,
+    FullAttendanceData,
+    FullAttendanceRecord,
+    HostelApplication,
+    HostelApplicationsData,
+    HostelRequest,
+    MarksData,
+    MarksRecord,
+    MarksRecordEach,
+    MaterialSyncFailure,
+    MaterialSyncReport,
+    PaymentHistoryData,
+    PerExamScheduleRecord,
+    ProctorDetails,
+    SemesterData,
+    SemesterGradesData,
+    SemesterInfo,
+    TimetableData,
+    TimetableSlot,
+)
+from .parsers import (
+    parse_allocation,
+    parse_arrears,
+    parse_attendance,
+    parse_biometric_log,
+    parse_course_page,
+    parse_course_page_courses,
+    parse_curriculum,
+    parse_digital_assignment_courses,
+    parse_digital_assignments,
+    parse_exam_schedule,
+    parse_fee_dues,
+    parse_feedback_forms,
+    parse_feedback_questions,
+    parse_full_attendance,
+    parse_hostel_applications,
+    parse_marks,
+    parse_messages,
+    parse_payment_history,
+    parse_semester_grades,
+    parse_semesters,
+    parse_spotlight,
+    parse_timetable,
+)
+
+__all__ = [
+    "AllocationData",
+    "Announcement",
+    "AnnouncementsData",
+    "ArrearsData",
+    "AttendanceData",
+    "AttendanceRecord",
+    "BiometricDay",
+    "BiometricLogData",
+    "BiometricPunch",
+    "CourseAllocation",
+    "CourseGrade",
+    "CourseMaterial",
+    "CoursePageCourse",
+    "CoursePageCourses",
+    "CoursePageData",
+    "CoursePageLecture",
+    "CurriculumCategory",
+    "CurriculumCourse",
+    "CurriculumData",
+    "DigitalAssignment",
+    "DigitalAssignmentCourse",
+    "DigitalAssignmentData",
+    "DigitalAssignmentFailure",
+    "DigitalAssignmentsReport",
+    "DownloadedMaterial",
+    "ExamScheduleData",
+    "ExamScheduleRecord",
+    "FacultyInfo",
+    "FeeDue",
+    "FeeDuesData",
+    "FeePayment",
+    "FeedbackForm",
+    "FeedbackFormsData",
+    "FeedbackQuestion",
+    "FeedbackQuestionsData",
+    "FeedbackResponse",
+    "FeedbackSubmission",
+    "FullAttendanceData",
+    "FullAttendanceRecord",
+    "HostelApplication",
+    "HostelApplicationsData",
+    "HostelRequest",
+    "LEAVE",
+    "MarksData",
+    "MarksRecord",
+    "MarksRecordEach",
+    "MaterialSyncFailure",
+    "MaterialSyncReport",
+    "OUTING",
+    "PaymentHistoryData",
+    "PerExamScheduleRecord",
+    "ProctorDetails",
+    "SemesterData",
+    "SemesterGradesData",
+    "SemesterInfo",
+    "TimetableData",
+    "TimetableSlot",
+    "UNVERIFIED_ENDPOINTS",
+    "VtopClient",
+    "VtopError",
+    "parse_allocation",
+    "parse_arrears",
+    "parse_attendance",
+    "parse_biometric_log",
+    "parse_course_page",
+    "parse_course_page_courses",
+    "parse_curriculum",
+    "parse_digital_assignment_courses",
+    "parse_digital_assignments",
+    "parse_exam_schedule",
+    "parse_fee_dues",
+    "parse_feedback_forms",
+    "parse_feedback_questions",
+    "parse_full_attendance",
+    "parse_hostel_applications",
+    "parse_marks",
+    "parse_messages",
+    "parse_payment_history",
+    "parse_semester_grades",
+    "parse_semesters",
+    "parse_spotlight",
+    "parse_timetable",
+]
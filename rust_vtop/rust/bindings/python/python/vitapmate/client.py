@@ -0,0 +1,229 @@
+from __future__ import annotations
+
+import dataclasses
+import json
+import os
+from typing import Any, Awaitable, Callable, List, Optional, Union
+
+from . import _native
+from .models import (
+    AllocationData,
+    AnnouncementsData,
+    ArrearsData,
+    AttendanceData,
+    BiometricLogData,
+    CourseMaterial,
+    CoursePageCourses,
+    CoursePageData,
+    CurriculumData,
+    DigitalAssignmentsReport,
+    DownloadedMaterial,
+    ExamScheduleData,
+    FeeDuesData,
+    FeedbackForm,
+    FeedbackFormsData,
+    FeedbackQuestionsData,
+    FeedbackResponse,
+    FeedbackSubmission,
+    FullAttendanceData,
+    HostelApplicationsData,
+    HostelRequest,
+    MarksData,
+    MaterialSyncReport,
+    PaymentHistoryData,
+    ProctorDetails,
+    SemesterData,
+    SemesterGradesData,
+    TimetableData,
+)
+
+FilePath = Union[str, "os.PathLike[str]"]
+
+
+def _json(model: Any) -> str:
+    if isinstance(model, list):
+        return json.dumps([dataclasses.asdict(m) for m in model])
+    return json.dumps(dataclasses.asdict(model))
+
+
+class VtopClient:
+    """One VTOP login. Every fetch is a coroutine; fetches on the same client
+    run concurrently, ``login`` waits for them.
+
+    Settings come from ``vtop.toml`` and the ``VTOP_*`` environment variables,
+    as for the server; the keyword arguments override them. Failed calls raise
+    ``vitapmate.VtopError``.
+    """
+
+    def __init__(
+        self,
+        username: str,
+        password: str,
+        *,
+        cookie: Optional[str] = None,
+        base_url: Optional[str] = None,
+        timeout_seconds: Optional[int] = None,
+    ) -> None:
+        self._native = _native.VtopClient(
+            username,
+            password,
+            cookie=cookie,
+            base_url=base_url,
+            timeout_seconds=timeout_seconds,
+        )
+
+    @property
+    def username(self) -> str:
+        return self._native.username
+
+    async def login(self) -> None:
+        await self._native.login()
+
+    async def is_authenticated(self) -> bool:
+        return await self._native.is_authenticated()
+
+    async def cookie(self) -> str:
+        """Session cookie, to pass as ``cookie=`` and skip the next login."""
+        return await self._native.cookie()
+
+    async def semesters(self) -> SemesterData:
+        return SemesterData.from_dict(await self._native.semesters())
+
+    async def timetable(self, semester_id: str) -> TimetableData:
+        return TimetableData.from_dict(await self._native.timetable(semester_id))
+
+    async def attendance(self, semester_id: str) -> AttendanceData:
+        return AttendanceData.from_dict(await self._native.attendance(semester_id))
+
+    async def full_attendance(
+        self, semester_id: str, course_id: str, course_type: str
+    ) -> FullAttendanceData:
+        return FullAttendanceData.from_dict(
+            await self._native.full_attendance(semester_id, course_id, course_type)
+        )
+
+    async def marks(self, semester_id: str) -> MarksData:
+        return MarksData.from_dict(await self._native.marks(semester_id))
+
+    async def exam_schedule(self, semester_id: str) -> ExamScheduleData:
+        return ExamScheduleData.from_dict(await self._native.exam_schedule(semester_id))
+
+    # The calls below need an extension built with the `unverified-endpoints`
+    # feature; see vitapmate.UNVERIFIED_ENDPOINTS.
+
+    def _unverified(self, name: str) -> Any:
+        if not _native.UNVERIFIED_ENDPOINTS:
+            raise NotImplementedError(
+                f"{name} needs vitapmate built with the unverified-endpoints feature"
+            )
+        return getattr(self._native, name)
+
+    async def course_page_courses(self, semester_id: str) -> CoursePageCourses:
+        call = self._unverified("course_page_courses")
+        return CoursePageCourses.from_dict(await call(semester_id))
+
+    async def course_page(
+        self, semester_id: str, class_id: str, faculty_id: str
+    ) -> CoursePageData:
+        call = self._unverified("course_page")
+        return CoursePageData.from_dict(await call(semester_id, class_id, faculty_id))
+
+    async def download_course_material(
+        self, material: CourseMaterial, file_path: FilePath
+    ) -> DownloadedMaterial:
+        """Saves one material to ``file_path``, replacing any file there."""
+        call = self._unverified("download_course_material")
+        return DownloadedMaterial(**await call(_json(material), os.fspath(file_path)))
+
+    async def sync_course_materials(
+        self, semester_id: str, directory: FilePath
+    ) -> MaterialSyncReport:
+        """Downloads new and changed materials of every course into
+        ``directory``; courses that fail are listed in ``failures``."""
+        call = self._unverified("sync_course_materials")
+        return MaterialSyncReport.from_dict(await call(semester_id, os.fspath(directory)))
+
+    async def digital_assignments(self, semester_id: str) -> DigitalAssignmentsReport:
+        """Courses whose assignments could not be loaded are listed in
+        ``failures``."""
+        call = self._unverified("digital_assignments")
+        return DigitalAssignmentsReport.from_dict(await call(semester_id))
+
+    async def upload_digital_assignment(
+        self,
+        class_id: str,
+        code: str,
+        file_path: FilePath,
+        otp: Callable[[str], Awaitable[Optional[str]]],
+    ) -> str:
+        """Uploads the file at ``file_path``. ``otp`` is awaited with VTOP's
+        prompt when it mails an OTP and returns the code, or None to give up."""
+        call = self._unverified("upload_digital_assignment")
+        return await call(class_id, code, os.fspath(file_path), otp)
+
+    async def fee_dues(self) -> FeeDuesData:
+        return FeeDuesData.from_dict(await self._unverified("fee_dues")())
+
+    async def payment_history(self) -> PaymentHistoryData:
+        return PaymentHistoryData.from_dict(await self._unverified("payment_history")())
+
+    async def fee_receipt(self, receipt_no: str) -> bytes:
+        """The receipt PDF of a ``FeePayment`` with ``has_receipt``."""
+        return await self._unverified("fee_receipt")(receipt_no)
+
+    async def hostel_applications(self, kind: str) -> HostelApplicationsData:
+        """``kind`` is ``vitapmate.OUTING`` or ``vitapmate.LEAVE``."""
+        call = self._unverified("hostel_applications")
+        return HostelApplicationsData.from_dict(await call(kind))
+
+    async def submit_hostel_request(self, request: HostelRequest) -> str:
+        """Raises ``VtopError`` without contacting VTOP when the request is
+        incomplete or its dates are out of order."""
+        return await self._unverified("submit_hostel_request")(_json(request))
+
+    async def cancel_hostel_application(self, kind: str, application_id: str) -> str:
+        call = self._unverified("cancel_hostel_application")
+        return await call(kind, application_id)
+
+    async def semester_grades(self, semester_id: str) -> SemesterGradesData:
+        call = self._unverified("semester_grades")
+        return SemesterGradesData.from_dict(await call(semester_id))
+
+    async def arrears(self) -> ArrearsData:
+        return ArrearsData.from_dict(await self._unverified("arrears")())
+
+    async def curriculum(self) -> CurriculumData:
+        return CurriculumData.from_dict(await self._unverified("curriculum")())
+
+    async def registration_allocation(self) -> AllocationData:
+        call = self._unverified("registration_allocation")
+        return AllocationData.from_dict(await call())
+
+    async def announcements(self) -> AnnouncementsData:
+        return AnnouncementsData.from_dict(await self._unverified("announcements")())
+
+    async def messages(self) -> AnnouncementsData:
+        return AnnouncementsData.from_dict(await self._unverified("messages")())
+
+    async def proctor_details(self) -> ProctorDetails:
+        return ProctorDetails.from_dict(await self._unverified("proctor_details")())
+
+    async def biometric_log(self, from_date: str, to_date: str) -> BiometricLogData:
+        call = self._unverified("biometric_log")
+        return BiometricLogData.from_dict(await call(from_date, to_date))
+
+    async def feedback_forms(self, semester_id: str) -> FeedbackFormsData:
+        call = self._unverified("feedback_forms")
+        return FeedbackFormsData.from_dict(await call(semester_id))
+
+    async def feedback_questions(
+        self, semester_id: str, form: FeedbackForm
+    ) -> FeedbackQuestionsData:
+        call = self._unverified("feedback_questions")
+        return FeedbackQuestionsData.from_dict(await call(semester_id, _json(form)))
+
+    async def submit_feedback(
+        self, form: FeedbackQuestionsData, responses: List[FeedbackResponse]
+    ) -> FeedbackSubmission:
+        call = self._unverified("submit_feedback")
+        return FeedbackSubmission.from_dict(await call(_json(form), _json(responses)))
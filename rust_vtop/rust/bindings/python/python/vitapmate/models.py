@@ -0,0 +1,766 @@
+"""Dataclasses for the models in rust_lib_vitapmate's types.rs.
+
+Field names match the Rust structs, so ``dataclasses.asdict`` gives back the
+same shape as the JSON the server and the debug binary print.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional
+
+Raw = Dict[str, Any]
+
+
+@dataclass(frozen=True)
+class SemesterInfo:
+    id: str
+    name: str
+
+
+@dataclass(frozen=True)
+class SemesterData:
+    semesters: List[SemesterInfo]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> SemesterData:
+        return cls(
+            semesters=[SemesterInfo(**s) for s in data["semesters"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class TimetableSlot:
+    serial: str
+    day: str
+    slot: str
+    course_code: str
+    course_type: str
+    room_no: str
+    block: str
+    start_time: str
+    end_time: str
+    name: str
+    is_lab: bool
+    faculty: str
+
+
+@dataclass(frozen=True)
+class TimetableData:
+    slots: List[TimetableSlot]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> TimetableData:
+        return cls(
+            slots=[TimetableSlot(**s) for s in data["slots"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class AttendanceRecord:
+    serial: str
+    category: str
+    course_name: str
+    course_code: str
+    course_type: str
+    faculty_detail: str
+    classes_attended: str
+    total_classes: str
+    attendance_percentage: str
+    attendence_fat_cat: str
+    debar_status: str
+    course_id: str
+
+
+@dataclass(frozen=True)
+class AttendanceData:
+    records: List[AttendanceRecord]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> AttendanceData:
+        return cls(
+            records=[AttendanceRecord(**r) for r in data["records"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FullAttendanceRecord:
+    serial: str
+    date: str
+    slot: str
+    day_time: str
+    status: str
+    remark: str
+
+
+@dataclass(frozen=True)
+class FullAttendanceData:
+    records: List[FullAttendanceRecord]
+    semester_id: str
+    update_time: int
+    course_id: str
+    course_type: str
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FullAttendanceData:
+        return cls(
+            records=[FullAttendanceRecord(**r) for r in data["records"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+            course_id=data["course_id"],
+            course_type=data["course_type"],
+        )
+
+
+@dataclass(frozen=True)
+class MarksRecordEach:
+    serial: str
+    markstitle: str
+    maxmarks: str
+    weightage: str
+    status: str
+    scoredmark: str
+    weightagemark: str
+    remark: str
+
+
+@dataclass(frozen=True)
+class MarksRecord:
+    serial: str
+    coursecode: str
+    coursetitle: str
+    coursetype: str
+    faculity: str
+    slot: str
+    marks: List[MarksRecordEach]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> MarksRecord:
+        fields = dict(data)
+        fields["marks"] = [MarksRecordEach(**m) for m in data["marks"]]
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class MarksData:
+    records: List[MarksRecord]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> MarksData:
+        return cls(
+            records=[MarksRecord.from_dict(r) for r in data["records"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class ExamScheduleRecord:
+    serial: str
+    slot: str
+    course_name: str
+    course_code: str
+    course_type: str
+    course_id: str
+    exam_date: str
+    exam_session: str
+    reporting_time: str
+    exam_time: str
+    venue: str
+    seat_location: str
+    seat_no: str
+
+
+@dataclass(frozen=True)
+class PerExamScheduleRecord:
+    records: List[ExamScheduleRecord]
+    exam_type: str
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> PerExamScheduleRecord:
+        return cls(
+            records=[ExamScheduleRecord(**r) for r in data["records"]],
+            exam_type=data["exam_type"],
+        )
+
+
+@dataclass(frozen=True)
+class ExamScheduleData:
+    exams: List[PerExamScheduleRecord]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> ExamScheduleData:
+        return cls(
+            exams=[PerExamScheduleRecord.from_dict(e) for e in data["exams"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+# The models below come from the endpoints behind the `unverified-endpoints`
+# feature; see vitapmate.UNVERIFIED_ENDPOINTS.
+
+
+@dataclass(frozen=True)
+class CoursePageCourse:
+    class_id: str
+    course_code: str
+    course_title: str
+    course_type: str
+    slot: str
+    faculty: str
+    faculty_id: str
+
+
+@dataclass(frozen=True)
+class CoursePageCourses:
+    courses: List[CoursePageCourse]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> CoursePageCourses:
+        return cls(
+            courses=[CoursePageCourse(**c) for c in data["courses"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class CourseMaterial:
+    title: str
+    path: str
+
+
+@dataclass(frozen=True)
+class CoursePageLecture:
+    serial: str
+    date: str
+    day: str
+    topic: str
+    materials: List[CourseMaterial]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> CoursePageLecture:
+        fields = dict(data)
+        fields["materials"] = [CourseMaterial(**m) for m in data["materials"]]
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class CoursePageData:
+    materials: List[CourseMaterial]
+    lectures: List[CoursePageLecture]
+    semester_id: str
+    class_id: str
+    faculty_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> CoursePageData:
+        return cls(
+            materials=[CourseMaterial(**m) for m in data["materials"]],
+            lectures=[CoursePageLecture.from_dict(l) for l in data["lectures"]],
+            semester_id=data["semester_id"],
+            class_id=data["class_id"],
+            faculty_id=data["faculty_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class DownloadedMaterial:
+    file_name: Optional[str]
+    bytes: int
+
+
+@dataclass(frozen=True)
+class MaterialSyncFailure:
+    course_code: str
+    title: str
+    error: str
+
+
+@dataclass(frozen=True)
+class MaterialSyncReport:
+    downloaded: List[str]
+    unchanged: int
+    failures: List[MaterialSyncFailure]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> MaterialSyncReport:
+        return cls(
+            downloaded=list(data["downloaded"]),
+            unchanged=data["unchanged"],
+            failures=[MaterialSyncFailure(**f) for f in data["failures"]],
+        )
+
+
+@dataclass(frozen=True)
+class DigitalAssignment:
+    serial: str
+    code: str
+    title: str
+    max_marks: str
+    weightage: str
+    due_date: str
+    question_paper: Optional[CourseMaterial]
+    uploaded: bool
+    last_updated: str
+    can_upload: bool
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> DigitalAssignment:
+        fields = dict(data)
+        if data["question_paper"] is not None:
+            fields["question_paper"] = CourseMaterial(**data["question_paper"])
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class DigitalAssignmentCourse:
+    class_id: str
+    course_code: str
+    course_title: str
+    course_type: str
+    faculty: str
+    assignments: List[DigitalAssignment]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> DigitalAssignmentCourse:
+        fields = dict(data)
+        fields["assignments"] = [DigitalAssignment.from_dict(a) for a in data["assignments"]]
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class DigitalAssignmentData:
+    courses: List[DigitalAssignmentCourse]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> DigitalAssignmentData:
+        return cls(
+            courses=[DigitalAssignmentCourse.from_dict(c) for c in data["courses"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class DigitalAssignmentFailure:
+    class_id: str
+    course_code: str
+    error: str
+
+
+@dataclass(frozen=True)
+class DigitalAssignmentsReport:
+    assignments: DigitalAssignmentData
+    failures: List[DigitalAssignmentFailure]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> DigitalAssignmentsReport:
+        return cls(
+            assignments=DigitalAssignmentData.from_dict(data["assignments"]),
+            failures=[DigitalAssignmentFailure(**f) for f in data["failures"]],
+        )
+
+
+@dataclass(frozen=True)
+class FeeDue:
+    serial: str
+    fee_head: str
+    term: str
+    amount: str
+    due_date: str
+    fine: str
+    status: str
+
+
+@dataclass(frozen=True)
+class FeeDuesData:
+    dues: List[FeeDue]
+    total_due: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FeeDuesData:
+        return cls(
+            dues=[FeeDue(**d) for d in data["dues"]],
+            total_due=data["total_due"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FeePayment:
+    serial: str
+    receipt_no: str
+    date: str
+    description: str
+    amount: str
+    mode: str
+    status: str
+    has_receipt: bool
+
+
+@dataclass(frozen=True)
+class PaymentHistoryData:
+    payments: List[FeePayment]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> PaymentHistoryData:
+        return cls(
+            payments=[FeePayment(**p) for p in data["payments"]],
+            update_time=data["update_time"],
+        )
+
+
+# HostelRequestKind is one of these two strings.
+OUTING = "Outing"
+LEAVE = "Leave"
+
+
+@dataclass(frozen=True)
+class HostelApplication:
+    kind: str
+    id: str
+    place: str
+    purpose: str
+    from_date: str
+    from_time: str
+    to_date: str
+    to_time: str
+    approver: str
+    status: str
+    can_cancel: bool
+
+
+@dataclass(frozen=True)
+class HostelApplicationsData:
+    applications: List[HostelApplication]
+    kind: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> HostelApplicationsData:
+        return cls(
+            applications=[HostelApplication(**a) for a in data["applications"]],
+            kind=data["kind"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class HostelRequest:
+    kind: str
+    place: str
+    purpose: str
+    from_date: str
+    from_time: str
+    to_date: str
+    to_time: str
+    contact_number: str
+
+
+@dataclass(frozen=True)
+class CourseGrade:
+    course_code: str
+    course_title: str
+    course_type: str
+    credits: float
+    grade: str
+    grade_points: Optional[float]
+    result_status: str
+    exam_month: str
+
+
+@dataclass(frozen=True)
+class SemesterGradesData:
+    grades: List[CourseGrade]
+    published_gpa: Optional[float]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> SemesterGradesData:
+        return cls(
+            grades=[CourseGrade(**g) for g in data["grades"]],
+            published_gpa=data["published_gpa"],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class ArrearsData:
+    records: List[CourseGrade]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> ArrearsData:
+        return cls(
+            records=[CourseGrade(**g) for g in data["records"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class CurriculumCategory:
+    code: str
+    name: str
+    credits_required: float
+    credits_earned: Optional[float]
+
+
+@dataclass(frozen=True)
+class CurriculumCourse:
+    category: str
+    course_code: str
+    course_title: str
+    course_type: str
+    credits: float
+
+
+@dataclass(frozen=True)
+class CurriculumData:
+    categories: List[CurriculumCategory]
+    courses: List[CurriculumCourse]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> CurriculumData:
+        return cls(
+            categories=[CurriculumCategory(**c) for c in data["categories"]],
+            courses=[CurriculumCourse(**c) for c in data["courses"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class CourseAllocation:
+    course_code: str
+    course_title: str
+    course_type: str
+    category: str
+    credits: float
+    status: str
+
+
+@dataclass(frozen=True)
+class AllocationData:
+    allocations: List[CourseAllocation]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> AllocationData:
+        return cls(
+            allocations=[CourseAllocation(**a) for a in data["allocations"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class Announcement:
+    id: str
+    category: str
+    title: str
+    body: str
+    attachments: List[CourseMaterial]
+    posted_date: str
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> Announcement:
+        fields = dict(data)
+        fields["attachments"] = [CourseMaterial(**m) for m in data["attachments"]]
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class AnnouncementsData:
+    announcements: List[Announcement]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> AnnouncementsData:
+        return cls(
+            announcements=[Announcement.from_dict(a) for a in data["announcements"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FacultyInfo:
+    name: str
+    emp_id: str
+    designation: str
+    school: str
+    cabin: str
+    email: str
+    phone: str
+    # Image bytes as VTOP serves them, usually JPEG.
+    photo: Optional[bytes]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FacultyInfo:
+        fields = dict(data)
+        if data["photo"] is not None:
+            fields["photo"] = bytes(data["photo"])
+        return cls(**fields)
+
+
+@dataclass(frozen=True)
+class ProctorDetails:
+    proctor: Optional[FacultyInfo]
+    class_advisor: Optional[FacultyInfo]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> ProctorDetails:
+        def faculty(info: Optional[Raw]) -> Optional[FacultyInfo]:
+            return None if info is None else FacultyInfo.from_dict(info)
+
+        return cls(
+            proctor=faculty(data["proctor"]),
+            class_advisor=faculty(data["class_advisor"]),
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class BiometricPunch:
+    time: str
+    location: str
+    direction: str
+
+
+@dataclass(frozen=True)
+class BiometricDay:
+    date: str
+    punches: List[BiometricPunch]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> BiometricDay:
+        return cls(
+            date=data["date"],
+            punches=[BiometricPunch(**p) for p in data["punches"]],
+        )
+
+
+@dataclass(frozen=True)
+class BiometricLogData:
+    days: List[BiometricDay]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> BiometricLogData:
+        return cls(
+            days=[BiometricDay.from_dict(d) for d in data["days"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FeedbackForm:
+    form_id: str
+    class_id: str
+    course_code: str
+    course_title: str
+    course_type: str
+    faculty: str
+    submitted: bool
+
+
+@dataclass(frozen=True)
+class FeedbackFormsData:
+    forms: List[FeedbackForm]
+    semester_id: str
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FeedbackFormsData:
+        return cls(
+            forms=[FeedbackForm(**f) for f in data["forms"]],
+            semester_id=data["semester_id"],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FeedbackQuestion:
+    id: str
+    text: str
+    # As serialized from Rust: ``{"Rating": {"min": 1, "max": 5}}``,
+    # ``{"Choice": {"options": [...]}}`` or ``"Text"``.
+    kind: Any
+    required: bool
+
+
+@dataclass(frozen=True)
+class FeedbackQuestionsData:
+    form_id: str
+    semester_id: str
+    questions: List[FeedbackQuestion]
+    update_time: int
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FeedbackQuestionsData:
+        return cls(
+            form_id=data["form_id"],
+            semester_id=data["semester_id"],
+            questions=[FeedbackQuestion(**q) for q in data["questions"]],
+            update_time=data["update_time"],
+        )
+
+
+@dataclass(frozen=True)
+class FeedbackResponse:
+    question_id: str
+    # ``{"Rating": n}``, ``{"Choice": value}`` or ``{"Text": text}``; use the
+    # constructors below.
+    answer: Raw
+
+    @classmethod
+    def rating(cls, question_id: str, value: int) -> FeedbackResponse:
+        return cls(question_id, {"Rating": value})
+
+    @classmethod
+    def choice(cls, question_id: str, value: str) -> FeedbackResponse:
+        return cls(question_id, {"Choice": value})
+
+    @classmethod
+    def text(cls, question_id: str, value: str) -> FeedbackResponse:
+        return cls(question_id, {"Text": value})
+
+
+@dataclass(frozen=True)
+class FeedbackSubmission:
+    message: str
+    # The forms still to fill in, or None when they could not be refetched.
+    outstanding: Optional[List[FeedbackForm]]
+
+    @classmethod
+    def from_dict(cls, data: Raw) -> FeedbackSubmission:
+        outstanding = data["outstanding"]
+        return cls(
+            message=data["message"],
+            outstanding=None if outstanding is None else [FeedbackForm(**f) for f in outstanding],
+        )
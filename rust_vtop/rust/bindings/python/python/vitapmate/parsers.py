@@ -0,0 +1,163 @@
+"""Parsers for pages saved from VTOP, for working offline.
+
+``source`` is either the page's HTML as a string or a path (``pathlib.Path``
+or any other ``os.PathLike``) to a saved copy. Plain strings are always
+treated as HTML.
+"""
+
+from __future__ import annotations
+
+import os
+from typing import Any, List, Union
+
+from . import _native
+from .models import (
+    AllocationData,
+    AnnouncementsData,
+    ArrearsData,
+    AttendanceData,
+    BiometricLogData,
+    CoursePageCourses,
+    CoursePageData,
+    CurriculumData,
+    DigitalAssignment,
+    DigitalAssignmentCourse,
+    ExamScheduleData,
+    FeeDuesData,
+    FeedbackFormsData,
+    FeedbackQuestionsData,
+    FullAttendanceData,
+    HostelApplicationsData,
+    MarksData,
+    PaymentHistoryData,
+    SemesterData,
+    SemesterGradesData,
+    TimetableData,
+)
+
+Source = Union[str, "os.PathLike[str]"]
+
+
+def _unverified(name: str) -> Any:
+    if not _native.UNVERIFIED_ENDPOINTS:
+        raise NotImplementedError(
+            f"{name} needs vitapmate built with the unverified-endpoints feature"
+        )
+    return getattr(_native, name)
+
+
+def _html(source: Source) -> str:
+    if isinstance(source, os.PathLike):
+        with open(source, encoding="utf-8", errors="replace") as page:
+            return page.read()
+    return source
+
+
+def parse_semesters(source: Source) -> SemesterData:
+    return SemesterData.from_dict(_native.parse_semesters(_html(source)))
+
+
+def parse_timetable(source: Source, semester_id: str) -> TimetableData:
+    return TimetableData.from_dict(_native.parse_timetable(_html(source), semester_id))
+
+
+def parse_attendance(source: Source, semester_id: str) -> AttendanceData:
+    return AttendanceData.from_dict(_native.parse_attendance(_html(source), semester_id))
+
+
+def parse_full_attendance(
+    source: Source, semester_id: str, course_id: str, course_type: str
+) -> FullAttendanceData:
+    return FullAttendanceData.from_dict(
+        _native.parse_full_attendance(_html(source), semester_id, course_id, course_type)
+    )
+
+
+def parse_marks(source: Source, semester_id: str) -> MarksData:
+    return MarksData.from_dict(_native.parse_marks(_html(source), semester_id))
+
+
+def parse_exam_schedule(source: Source, semester_id: str) -> ExamScheduleData:
+    return ExamScheduleData.from_dict(_native.parse_exam_schedule(_html(source), semester_id))
+
+
+
+# The parsers below need an extension built with the `unverified-endpoints`
+# feature; see vitapmate.UNVERIFIED_ENDPOINTS.
+
+
+def parse_course_page_courses(source: Source, semester_id: str) -> CoursePageCourses:
+    parse = _unverified("parse_course_page_courses")
+    return CoursePageCourses.from_dict(parse(_html(source), semester_id))
+
+
+def parse_course_page(
+    source: Source, semester_id: str, class_id: str, faculty_id: str
+) -> CoursePageData:
+    parse = _unverified("parse_course_page")
+    return CoursePageData.from_dict(parse(_html(source), semester_id, class_id, faculty_id))
+
+
+def parse_digital_assignment_courses(source: Source) -> List[DigitalAssignmentCourse]:
+    """The course list; each course's ``assignments`` is left empty."""
+    parse = _unverified("parse_digital_assignment_courses")
+    return [DigitalAssignmentCourse.from_dict(c) for c in parse(_html(source))]
+
+
+def parse_digital_assignments(source: Source) -> List[DigitalAssignment]:
+    parse = _unverified("parse_digital_assignments")
+    return [DigitalAssignment.from_dict(a) for a in parse(_html(source))]
+
+
+def parse_fee_dues(source: Source) -> FeeDuesData:
+    return FeeDuesData.from_dict(_unverified("parse_fee_dues")(_html(source)))
+
+
+def parse_payment_history(source: Source) -> PaymentHistoryData:
+    return PaymentHistoryData.from_dict(_unverified("parse_payment_history")(_html(source)))
+
+
+def parse_hostel_applications(source: Source, kind: str) -> HostelApplicationsData:
+    parse = _unverified("parse_hostel_applications")
+    return HostelApplicationsData.from_dict(parse(_html(source), kind))
+
+
+def parse_semester_grades(source: Source, semester_id: str) -> SemesterGradesData:
+    parse = _unverified("parse_semester_grades")
+    return SemesterGradesData.from_dict(parse(_html(source), semester_id))
+
+
+def parse_arrears(source: Source) -> ArrearsData:
+    return ArrearsData.from_dict(_unverified("parse_arrears")(_html(source)))
+
+
+def parse_curriculum(source: Source) -> CurriculumData:
+    return CurriculumData.from_dict(_unverified("parse_curriculum")(_html(source)))
+
+
+def parse_allocation(source: Source) -> AllocationData:
+    return AllocationData.from_dict(_unverified("parse_allocation")(_html(source)))
+
+
+def parse_spotlight(source: Source) -> AnnouncementsData:
+    return AnnouncementsData.from_dict(_unverified("parse_spotlight")(_html(source)))
+
+
+def parse_messages(source: Source) -> AnnouncementsData:
+    return AnnouncementsData.from_dict(_unverified("parse_messages")(_html(source)))
+
+
+def parse_biometric_log(source: Source) -> BiometricLogData:
+    return BiometricLogData.from_dict(_unverified("parse_biometric_log")(_html(source)))
+
+
+def parse_feedback_forms(source: Source, semester_id: str) -> FeedbackFormsData:
+    parse = _unverified("parse_feedback_forms")
+    return FeedbackFormsData.from_dict(parse(_html(source), semester_id))
+
+
+def parse_feedback_questions(
+    source: Source, semester_id: str, form_id: str
+) -> FeedbackQuestionsData:
+    parse = _unverified("parse_feedback_questions")
+    return FeedbackQuestionsData.from_dict(parse(_html(source), semester_id, form_id))
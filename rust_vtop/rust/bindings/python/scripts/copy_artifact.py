@@ -0,0 +1,23 @@
+"""Copies the cargo build output into python/vitapmate as the extension
+module, for running the tests without maturin (`maturin develop` does the
+same thing when it is available)."""
+
+import os
+import shutil
+import sys
+from pathlib import Path
+
+here = Path(__file__).resolve().parent
+profile = sys.argv[1] if len(sys.argv) > 1 else "release"
+library, extension = {
+    "linux": ("lib_native.so", "_native.abi3.so"),
+    "darwin": ("lib_native.dylib", "_native.abi3.so"),
+    "win32": ("_native.dll", "_native.pyd"),
+}[sys.platform]
+
+# The binding is a member of the rust_vtop/rust workspace.
+target = Path(os.environ.get("CARGO_TARGET_DIR", here.parents[2] / "target"))
+source = target / profile / library
+destination = here.parent / "python" / "vitapmate" / extension
+shutil.copyfile(source, destination)
+print(f"{source} -> {destination}")
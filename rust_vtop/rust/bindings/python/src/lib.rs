@@ -0,0 +1,734 @@
+use std::sync::Arc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+#[cfg(feature = "unverified-endpoints")]
+use futures::FutureExt;
+#[cfg(feature = "unverified-endpoints")]
+use pyo3::types::PyBytes;
+use pyo3::types::{PyDict, PyList};
+use pyo3_async_runtimes::tokio::future_into_py;
+#[cfg(feature = "unverified-endpoints")]
+use pyo3_async_runtimes::tokio::into_future;
+use rust_lib_vitapmate::api::vtop::paraser::{parseattn, parsemarks, parsesched, parsett};
+#[cfg(feature = "unverified-endpoints")]
+use rust_lib_vitapmate::api::vtop::paraser::{
+    parseannouncements, parsebiometric, parsecoursepage, parsecurriculum, parseda,
+    parsefeedback, parsefinance, parsegrades, parsehostel,
+};
+#[cfg(feature = "unverified-endpoints")]
+use rust_lib_vitapmate::api::vtop::types::HostelRequestKind;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopClient;
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop::vtop_errors::VtopError;
+use rust_lib_vitapmate::api::vtop_get_client as vtop;
+#[cfg(feature = "unverified-endpoints")]
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+mod errors {
+    pyo3::create_exception!(
+        vitapmate,
+        VtopError,
+        pyo3::exceptions::PyException,
+        "A failed VTOP call. `retryable`, `auth_problem` and `status` say what went wrong."
+    );
+}
+
+// The native half of `vitapmate.VtopClient`; every fetch resolves to plain
+// dicts that the Python side turns into dataclasses.
+#[pyclass(name = "VtopClient", module = "vitapmate._native")]
+struct NativeClient {
+    username: String,
+    client: Arc<RwLock<VtopClient>>,
+}
+
+#[pymethods]
+impl NativeClient {
+    // Settings come from `VtopConfig::load()`, i.e. `vtop.toml` and the
+    // `VTOP_*` environment variables, with the keyword arguments on top.
+    #[new]
+    #[pyo3(signature = (username, password, *, cookie=None, base_url=None, timeout_seconds=None))]
+    fn new(
+        username: String,
+        password: String,
+        cookie: Option<String>,
+        base_url: Option<String>,
+        timeout_seconds: Option<u64>,
+    ) -> PyResult<Self> {
+        let mut config = VtopConfig::load().map_err(failure)?;
+        if let Some(base_url) = base_url {
+            config.base_url = base_url;
+        }
+        if let Some(timeout) = timeout_seconds {
+            config.timeout_seconds = timeout;
+        }
+        let username = username.trim().to_uppercase();
+        let client = vtop::get_vtop_client_with_config(config, username.clone(), password, cookie)
+            .map_err(failure)?;
+        Ok(Self {
+            username,
+            client: Arc::new(RwLock::new(client)),
+        })
+    }
+
+    #[getter]
+    fn username(&self) -> String {
+        self.username.clone()
+    }
+
+    fn login<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let mut client = client.write().await;
+            vtop::vtop_client_login(&mut client).await.map_err(failure)
+        })
+    }
+
+    fn is_authenticated<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            Ok(vtop::fetch_is_auth(&client).await)
+        })
+    }
+
+    fn cookie<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            let cookie = vtop::fetch_cookies(&client).await.map_err(failure)?;
+            Ok(String::from_utf8_lossy(&cookie).into_owned())
+        })
+    }
+
+    fn semesters<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_semesters(&client).await.map_err(failure)?)
+        })
+    }
+
+    fn timetable<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_timetable(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    fn attendance<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_attendance(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    fn full_attendance<'py>(
+        &self,
+        py: Python<'py>,
+        semester_id: String,
+        course_id: String,
+        course_type: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(
+                vtop::fetch_full_attendance(&client, semester_id, course_id, course_type, None)
+                    .await
+                    .map_err(failure)?,
+            )
+        })
+    }
+
+    fn marks<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_marks(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    fn exam_schedule<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_exam_shedule(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn course_page_courses<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_course_page_courses(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn course_page<'py>(
+        &self,
+        py: Python<'py>,
+        semester_id: String,
+        class_id: String,
+        faculty_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(
+                vtop::fetch_course_page(&client, semester_id, class_id, faculty_id)
+                    .await
+                    .map_err(failure)?,
+            )
+        })
+    }
+
+    // `material` is a `CourseMaterial` as JSON.
+    #[cfg(feature = "unverified-endpoints")]
+    fn download_course_material<'py>(
+        &self,
+        py: Python<'py>,
+        material: String,
+        file_path: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let material = from_json(&material)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(
+                vtop::download_course_material(&client, material, file_path)
+                    .await
+                    .map_err(failure)?,
+            )
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn sync_course_materials<'py>(
+        &self,
+        py: Python<'py>,
+        semester_id: String,
+        directory: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            let report = vtop::sync_course_materials(&client, semester_id, directory, None)
+                .await
+                .map_err(failure)?;
+            let mut errors = vec![];
+            for failed in &report.failures {
+                errors.push(failed.error.user_message());
+            }
+            report_to_py(report, errors)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn digital_assignments<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            let report = vtop::fetch_digital_assignments(&client, semester_id, None)
+                .await
+                .map_err(failure)?;
+            let mut errors = vec![];
+            for failed in &report.failures {
+                errors.push(failed.error.user_message());
+            }
+            report_to_py(report, errors)
+        })
+    }
+
+    // `otp` is an async callable taking VTOP's prompt and returning the code,
+    // or None to give up.
+    #[cfg(feature = "unverified-endpoints")]
+    fn upload_digital_assignment<'py>(
+        &self,
+        py: Python<'py>,
+        class_id: String,
+        code: String,
+        file_path: String,
+        otp: PyObject,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        let otp = Arc::new(otp);
+        future_into_py(py, async move {
+            let client = client.read().await;
+            let ask = move |prompt: String| {
+                let otp = otp.clone();
+                async move {
+                    let answer = Python::with_gil(|py| {
+                        into_future(otp.bind(py).call1((prompt,))?)
+                    })
+                    .ok()?
+                    .await
+                    .ok()?;
+                    Python::with_gil(|py| answer.extract::<Option<String>>(py).ok().flatten())
+                }
+                .boxed()
+            };
+            vtop::upload_digital_assignment(&client, class_id, code, file_path, ask)
+                .await
+                .map_err(failure)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn fee_dues<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_fee_dues(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn payment_history<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_payment_history(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn fee_receipt<'py>(&self, py: Python<'py>, receipt_no: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            let receipt = vtop::fetch_fee_receipt(&client, receipt_no).await.map_err(failure)?;
+            Ok(Python::with_gil(|py| PyBytes::new(py, &receipt).unbind()))
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn hostel_applications<'py>(&self, py: Python<'py>, kind: String) -> PyResult<Bound<'py, PyAny>> {
+        let kind = hostel_kind(kind)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_hostel_applications(&client, kind).await.map_err(failure)?)
+        })
+    }
+
+    // `request` is a `HostelRequest` as JSON.
+    #[cfg(feature = "unverified-endpoints")]
+    fn submit_hostel_request<'py>(&self, py: Python<'py>, request: String) -> PyResult<Bound<'py, PyAny>> {
+        let request = from_json(&request)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            vtop::submit_hostel_request(&client, request).await.map_err(failure)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn cancel_hostel_application<'py>(
+        &self,
+        py: Python<'py>,
+        kind: String,
+        application_id: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let kind = hostel_kind(kind)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            vtop::cancel_hostel_application(&client, kind, application_id)
+                .await
+                .map_err(failure)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn semester_grades<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_semester_grades(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn arrears<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_arrears(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn curriculum<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_curriculum(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn registration_allocation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_registration_allocation(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn announcements<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_announcements(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn messages<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_messages(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn proctor_details<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_proctor_details(&client).await.map_err(failure)?)
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn biometric_log<'py>(
+        &self,
+        py: Python<'py>,
+        from_date: String,
+        to_date: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(
+                vtop::fetch_biometric_log(&client, from_date, to_date)
+                    .await
+                    .map_err(failure)?,
+            )
+        })
+    }
+
+    #[cfg(feature = "unverified-endpoints")]
+    fn feedback_forms<'py>(&self, py: Python<'py>, semester_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::fetch_feedback_forms(&client, semester_id).await.map_err(failure)?)
+        })
+    }
+
+    // `form` is a `FeedbackForm` as JSON.
+    #[cfg(feature = "unverified-endpoints")]
+    fn feedback_questions<'py>(
+        &self,
+        py: Python<'py>,
+        semester_id: String,
+        form: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let form = from_json(&form)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(
+                vtop::fetch_feedback_questions(&client, semester_id, form)
+                    .await
+                    .map_err(failure)?,
+            )
+        })
+    }
+
+    // `form` is the `FeedbackQuestionsData` answered and `responses` a list
+    // of `FeedbackResponse`, both as JSON.
+    #[cfg(feature = "unverified-endpoints")]
+    fn submit_feedback<'py>(
+        &self,
+        py: Python<'py>,
+        form: String,
+        responses: String,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let form = from_json(&form)?;
+        let responses = from_json(&responses)?;
+        let client = self.client.clone();
+        future_into_py(py, async move {
+            let client = client.read().await;
+            to_py(vtop::submit_feedback(&client, form, responses).await.map_err(failure)?)
+        })
+    }
+}
+
+// Parsers for pages saved from VTOP; no login needed.
+
+#[pyfunction]
+fn parse_semesters(html: String) -> PyResult<PyObject> {
+    to_py(parsett::parse_semid_timetable(html))
+}
+
+#[pyfunction]
+fn parse_timetable(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsett::parse_timetable(html, &semester_id))
+}
+
+#[pyfunction]
+fn parse_attendance(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parseattn::parse_attendance(html, semester_id))
+}
+
+#[pyfunction]
+fn parse_full_attendance(
+    html: String,
+    semester_id: String,
+    course_id: String,
+    course_type: String,
+) -> PyResult<PyObject> {
+    to_py(parseattn::parse_full_attendance(
+        html,
+        semester_id,
+        course_id,
+        course_type,
+    ))
+}
+
+#[pyfunction]
+fn parse_marks(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsemarks::parse_marks(html, semester_id))
+}
+
+#[pyfunction]
+fn parse_exam_schedule(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsesched::parse_schedule(html, semester_id))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_course_page_courses(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsecoursepage::parse_course_page_courses(html, semester_id))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_course_page(
+    html: String,
+    semester_id: String,
+    class_id: String,
+    faculty_id: String,
+) -> PyResult<PyObject> {
+    to_py(parsecoursepage::parse_course_page(
+        html,
+        semester_id,
+        class_id,
+        faculty_id,
+    ))
+}
+
+// The course list; each course's `assignments` is left empty.
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_digital_assignment_courses(html: String) -> PyResult<PyObject> {
+    to_py(parseda::parse_da_courses(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_digital_assignments(html: String) -> PyResult<PyObject> {
+    to_py(parseda::parse_assignments(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_fee_dues(html: String) -> PyResult<PyObject> {
+    to_py(parsefinance::parse_fee_dues(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_payment_history(html: String) -> PyResult<PyObject> {
+    to_py(parsefinance::parse_payment_history(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_hostel_applications(html: String, kind: String) -> PyResult<PyObject> {
+    to_py(parsehostel::parse_hostel_applications(html, hostel_kind(kind)?))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_semester_grades(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsegrades::parse_semester_grades(html, semester_id))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_arrears(html: String) -> PyResult<PyObject> {
+    to_py(parsegrades::parse_arrears(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_curriculum(html: String) -> PyResult<PyObject> {
+    to_py(parsecurriculum::parse_curriculum(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_allocation(html: String) -> PyResult<PyObject> {
+    to_py(parsecurriculum::parse_allocation(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_spotlight(html: String) -> PyResult<PyObject> {
+    to_py(parseannouncements::parse_spotlight(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_messages(html: String) -> PyResult<PyObject> {
+    to_py(parseannouncements::parse_messages(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_biometric_log(html: String) -> PyResult<PyObject> {
+    to_py(parsebiometric::parse_biometric_log(html))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_feedback_forms(html: String, semester_id: String) -> PyResult<PyObject> {
+    to_py(parsefeedback::parse_feedback_forms(html, semester_id))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+#[pyfunction]
+fn parse_feedback_questions(html: String, semester_id: String, form_id: String) -> PyResult<PyObject> {
+    to_py(parsefeedback::parse_feedback_questions(
+        html,
+        semester_id,
+        form_id,
+    ))
+}
+
+#[pymodule]
+fn _native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<NativeClient>()?;
+    m.add("VtopError", m.py().get_type::<errors::VtopError>())?;
+    m.add_function(wrap_pyfunction!(parse_semesters, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_timetable, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_attendance, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_full_attendance, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_marks, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_exam_schedule, m)?)?;
+    m.add("UNVERIFIED_ENDPOINTS", cfg!(feature = "unverified-endpoints"))?;
+    #[cfg(feature = "unverified-endpoints")]
+    {
+        m.add_function(wrap_pyfunction!(parse_course_page_courses, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_course_page, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_digital_assignment_courses, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_digital_assignments, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_fee_dues, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_payment_history, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_hostel_applications, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_semester_grades, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_arrears, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_curriculum, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_allocation, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_spotlight, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_messages, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_biometric_log, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_feedback_forms, m)?)?;
+        m.add_function(wrap_pyfunction!(parse_feedback_questions, m)?)?;
+    }
+    Ok(())
+}
+
+fn to_py<T: Serialize>(data: T) -> PyResult<PyObject> {
+    let value = serde_json::to_value(data).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Python::with_gil(|py| value_to_py(py, &value))
+}
+
+// Reports list a `VtopError` per failed course; Python gets each error's
+// message, in the order of `failures`.
+#[cfg(feature = "unverified-endpoints")]
+fn report_to_py<T: Serialize>(report: T, errors: Vec<String>) -> PyResult<PyObject> {
+    let mut value =
+        serde_json::to_value(report).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    if let Some(Value::Array(failures)) = value.get_mut("failures") {
+        for (failure, message) in failures.iter_mut().zip(errors) {
+            failure["error"] = Value::String(message);
+        }
+    }
+    Python::with_gil(|py| value_to_py(py, &value))
+}
+
+// Structured arguments cross over as JSON in the shape of the Rust models.
+#[cfg(feature = "unverified-endpoints")]
+fn from_json<T: DeserializeOwned>(json: &str) -> PyResult<T> {
+    serde_json::from_str(json).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[cfg(feature = "unverified-endpoints")]
+fn hostel_kind(kind: String) -> PyResult<HostelRequestKind> {
+    serde_json::from_value(Value::String(kind))
+        .map_err(|_| PyValueError::new_err("kind must be \"Outing\" or \"Leave\""))
+}
+
+fn value_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(match value {
+        Value::Null => py.None(),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any().unbind(),
+        Value::Number(n) => match n.as_u64() {
+            Some(n) => n.into_pyobject(py)?.into_any().unbind(),
+            None => match n.as_i64() {
+                Some(n) => n.into_pyobject(py)?.into_any().unbind(),
+                None => n.as_f64().unwrap_or_default().into_pyobject(py)?.into_any().unbind(),
+            },
+        },
+        Value::String(s) => s.into_pyobject(py)?.into_any().unbind(),
+        Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(value_to_py(py, item)?)?;
+            }
+            list.into_any().unbind()
+        }
+        Value::Object(fields) => {
+            let dict = PyDict::new(py);
+            for (key, item) in fields {
+                dict.set_item(key, value_to_py(py, item)?)?;
+            }
+            dict.into_any().unbind()
+        }
+    })
+}
+
+fn failure(error: VtopError) -> PyErr {
+    let err = errors::VtopError::new_err(error.user_message());
+    Python::with_gil(|py| {
+        let value = err.value(py);
+        let _ = value.setattr("retryable", error.is_retryable());
+        let _ = value.setattr("auth_problem", error.is_auth_problem());
+        let _ = value.setattr("status", error.status());
+    });
+    err
+}
@@ -0,0 +1,233 @@
+import asyncio
+import os
+import tempfile
+import threading
+import unittest
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs
+
+import vitapmate
+
+UNREACHABLE = "http://127.0.0.1:9"
+
+
+class Outage(BaseHTTPRequestHandler):
+    def do_GET(self):
+        self.send_response(503)
+        self.send_header("Content-Type", "text/html")
+        self.end_headers()
+        self.wfile.write(b"<html><body>down for maintenance</body></html>")
+
+    do_POST = do_GET
+
+    def log_message(self, *args):
+        pass
+
+
+class ClientTests(unittest.TestCase):
+    def test_new_clients_are_not_authenticated(self):
+        client = vitapmate.VtopClient(" 21bce0001 ", "secret", base_url=UNREACHABLE)
+        self.assertEqual(client.username, "21BCE0001")
+        self.assertFalse(asyncio.run(client.is_authenticated()))
+
+    def test_fetching_before_login_is_an_auth_problem(self):
+        client = vitapmate.VtopClient("21BCE0001", "secret", base_url=UNREACHABLE)
+        with self.assertRaises(vitapmate.VtopError) as raised:
+            asyncio.run(client.semesters())
+        self.assertTrue(raised.exception.auth_problem)
+        self.assertFalse(raised.exception.retryable)
+
+    def test_vtop_outage_is_retryable(self):
+        server = ThreadingHTTPServer(("127.0.0.1", 0), Outage)
+        threading.Thread(target=server.serve_forever, daemon=True).start()
+        try:
+            base_url = f"http://127.0.0.1:{server.server_address[1]}"
+            client = vitapmate.VtopClient("21BCE0001", "secret", base_url=base_url, timeout_seconds=5)
+            with self.assertRaises(vitapmate.VtopError) as raised:
+                asyncio.run(client.login())
+            self.assertTrue(raised.exception.retryable)
+            self.assertFalse(raised.exception.auth_problem)
+            self.assertEqual(raised.exception.status, 503)
+        finally:
+            server.shutdown()
+            server.server_close()
+
+    def test_calls_on_one_client_can_overlap(self):
+        client = vitapmate.VtopClient("21BCE0001", "secret", base_url=UNREACHABLE)
+
+        async def overlapping():
+            return await asyncio.gather(
+                client.timetable("AP2024252"),
+                client.marks("AP2024252"),
+                client.is_authenticated(),
+                return_exceptions=True,
+            )
+
+        timetable, marks, authenticated = asyncio.run(overlapping())
+        self.assertIsInstance(timetable, vitapmate.VtopError)
+        self.assertIsInstance(marks, vitapmate.VtopError)
+        self.assertFalse(authenticated)
+
+
+DA_COURSES = """<table>
+<tr><th>Sl.No.</th><th>Class Nbr</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Faculty</th><th></th></tr>
+<tr><td>1</td><td>AP2024252000123</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>Dr. A</td>
+<td><button onclick="javascript:myFunction('AP2024252000123');">Process</button></td></tr>
+<tr><td>2</td><td>AP2024252000456</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>Dr. B</td>
+<td><button onclick="javascript:myFunction('AP2024252000456');">Process</button></td></tr>
+</table>"""
+
+DA_ASSIGNMENTS = """<table>
+<tr><th>Sl.No.</th><th>Title</th><th>Max. Mark</th><th>Weightage %</th><th>Due Date</th><th>QP</th><th>Last Updated</th><th>Upload</th></tr>
+<tr><td>1</td><td>Assignment 1</td><td>10</td><td>10</td><td>15-Mar-2025</td>
+<td><a href="javascript:vtopDownload('examinations/doDownloadQuestion/Am01/AP2024252000123')">Download</a></td>
+<td>10-Mar-2025 11:02</td><td><button onclick="doDAUpload('Am01','AP2024252000123')">Upload</button></td></tr>
+</table>"""
+
+FEE_DUES = """<table>
+<tr><th>Sl.No.</th><th>Fee Head</th><th>Semester</th><th>Amount</th><th>Due Date</th><th>Fine</th><th>Status</th></tr>
+<tr><td>1</td><td>Tuition Fee</td><td>Winter Semester 2024-25</td><td>1,25,000.00</td><td>10-Jan-2025</td><td>0.00</td><td>Not Paid</td></tr>
+<tr><td colspan="6">Total</td><td>1,25,000.00</td></tr>
+</table>"""
+
+
+# Serves the login page and the pages of the endpoints behind the
+# `unverified-endpoints` feature; the second course's assignments fail.
+class FakeVtop(BaseHTTPRequestHandler):
+    def do_GET(self):
+        if self.path.startswith("/vtop/open/page"):
+            self.reply(200, b'<input name="_csrf" value="t0k3n">')
+        else:
+            self.reply(404, b"not found")
+
+    def do_POST(self):
+        body = self.rfile.read(int(self.headers.get("Content-Length", 0)))
+        form = parse_qs(body.decode("latin-1"))
+        path = self.path.split("?")[0]
+        if path == "/vtop/examinations/doDigitalAssignment":
+            self.reply(200, DA_COURSES.encode())
+        elif path == "/vtop/examinations/processDigitalAssignment":
+            if form.get("classId") == ["AP2024252000123"]:
+                self.reply(200, DA_ASSIGNMENTS.encode())
+            else:
+                self.reply(500, b"no such class")
+        elif path == "/vtop/examinations/doDAUpload":
+            self.reply(
+                200,
+                b'<span class="text-info">OTP sent to your registered e-mail</span>'
+                b'<form><input name="otp"></form>',
+            )
+        elif path == "/vtop/examinations/doDAUploadOtp":
+            if form.get("otp") == ["123456"]:
+                self.reply(200, b'<div class="alert">File uploaded successfully</div>')
+            else:
+                self.reply(200, b'<span class="text-danger">Invalid OTP.</span><input name="otp">')
+        elif path == "/vtop/finance/Payments":
+            self.reply(200, FEE_DUES.encode())
+        elif path == "/vtop/finance/getDownloadReceipt":
+            self.reply(200, b"%PDF-1.4 receipt", "application/pdf")
+        else:
+            self.reply(404, b"not found")
+
+    def reply(self, status, body, content_type="text/html"):
+        self.send_response(status)
+        self.send_header("Content-Type", content_type)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, *args):
+        pass
+
+
+@unittest.skipUnless(vitapmate.UNVERIFIED_ENDPOINTS, "built without unverified-endpoints")
+class UnverifiedEndpointTests(unittest.TestCase):
+    def setUp(self):
+        self.server = ThreadingHTTPServer(("127.0.0.1", 0), FakeVtop)
+        threading.Thread(target=self.server.serve_forever, daemon=True).start()
+        self.base_url = f"http://127.0.0.1:{self.server.server_address[1]}"
+
+    def tearDown(self):
+        self.server.shutdown()
+        self.server.server_close()
+
+    def client(self, username):
+        client = vitapmate.VtopClient(
+            username, "secret", cookie="SESSION=abc", base_url=self.base_url, timeout_seconds=5
+        )
+        asyncio.run(client.login())
+        return client
+
+    def test_assignments_report_failed_courses(self):
+        client = self.client("21BCE0501")
+        report = asyncio.run(client.digital_assignments("AP2024252"))
+        self.assertIsInstance(report, vitapmate.DigitalAssignmentsReport)
+        [course] = report.assignments.courses
+        self.assertEqual(course.course_code, "CSE3005")
+        self.assertEqual(
+            course.assignments[0].question_paper.path,
+            "examinations/doDownloadQuestion/Am01/AP2024252000123",
+        )
+        [failure] = report.failures
+        self.assertEqual(failure.course_code, "MAT2002")
+        self.assertIsInstance(failure.error, str)
+
+    def test_upload_asks_the_callback_for_the_otp(self):
+        client = self.client("21BCE0502")
+        prompts = []
+
+        async def otp(prompt):
+            prompts.append(prompt)
+            await asyncio.sleep(0)
+            return "123456"
+
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "answers.pdf")
+            with open(path, "wb") as answers:
+                answers.write(b"my answers")
+            message = asyncio.run(
+                client.upload_digital_assignment("AP2024252000123", "Am01", path, otp)
+            )
+        self.assertIn("uploaded successfully", message)
+        self.assertEqual(prompts, ["OTP sent to your registered e-mail"])
+
+    def test_giving_up_on_the_otp_fails_the_upload(self):
+        client = self.client("21BCE0503")
+
+        async def otp(prompt):
+            return None
+
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "answers.pdf")
+            with open(path, "wb") as answers:
+                answers.write(b"my answers")
+            with self.assertRaises(vitapmate.VtopError):
+                asyncio.run(
+                    client.upload_digital_assignment("AP2024252000123", "Am01", path, otp)
+                )
+
+    def test_fees_and_receipts(self):
+        client = self.client("21BCE0504")
+        dues = asyncio.run(client.fee_dues())
+        self.assertEqual([d.fee_head for d in dues.dues], ["Tuition Fee"])
+        self.assertEqual(dues.total_due, "1,25,000.00")
+        self.assertEqual(asyncio.run(client.fee_receipt("RC1001")), b"%PDF-1.4 receipt")
+
+    def test_invalid_hostel_requests_are_refused_before_sending(self):
+        client = self.client("21BCE0505")
+        request = vitapmate.HostelRequest(
+            kind=vitapmate.OUTING,
+            place="",
+            purpose="",
+            from_date="",
+            from_time="",
+            to_date="",
+            to_time="",
+            contact_number="",
+        )
+        with self.assertRaises(vitapmate.VtopError):
+            asyncio.run(client.submit_hostel_request(request))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,135 @@
+import dataclasses
+import tempfile
+import unittest
+from pathlib import Path
+
+import vitapmate
+
+SEMESTERS = """
+<select name="semesterSubId">
+  <option value="">-- Choose Semester --</option>
+  <option value="AP2024252"> Winter Semester 2024-25 </option>
+  <option value="AP2024251">Fall Semester 2024-25- AMR</option>
+</select>
+"""
+
+ATTENDANCE = """
+<table>
+  <tr><th>Sl.No.</th></tr>
+  <tr>
+    <td>1</td><td>Program Core</td><td>Compiler Design</td><td>CSE3005</td>
+    <td>Dr. A (SCOPE)</td><td>18</td><td>20</td><td>90</td><td>-</td><td>Permitted</td>
+    <td><a href="javascript:processViewAttendanceDetail('AP2024252','CSE3005','AP2024252000123','ETH')">View</a></td>
+  </tr>
+</table>
+"""
+
+FULL_ATTENDANCE = """
+<table>
+  <tr><th>a</th></tr><tr><th>b</th></tr><tr><th>c</th></tr>
+  <tr><td>1</td><td>02-Jan-2025</td><td>A1</td><td>THU 09:00</td><td>Present</td><td>-</td></tr>
+  <tr><td>2</td><td>03-Jan-2025</td><td>A1</td><td>FRI 09:00</td><td>Absent</td><td>-</td></tr>
+</table>
+"""
+
+
+class ParserTests(unittest.TestCase):
+    def test_semesters(self):
+        data = vitapmate.parse_semesters(SEMESTERS)
+        self.assertIsInstance(data, vitapmate.SemesterData)
+        self.assertEqual(
+            data.semesters,
+            [
+                vitapmate.SemesterInfo(id="AP2024252", name="Winter Semester 2024-25"),
+                vitapmate.SemesterInfo(id="AP2024251", name="Fall Semester 2024-25"),
+            ],
+        )
+
+    def test_attendance(self):
+        data = vitapmate.parse_attendance(ATTENDANCE, "AP2024252")
+        self.assertEqual(data.semester_id, "AP2024252")
+        [record] = data.records
+        self.assertIsInstance(record, vitapmate.AttendanceRecord)
+        self.assertEqual(record.course_code, "CSE3005")
+        self.assertEqual(record.attendance_percentage, "90")
+        self.assertEqual(record.course_id, "AP2024252000123")
+        self.assertEqual(record.course_type, "ETH")
+
+    def test_full_attendance_from_saved_file(self):
+        with tempfile.TemporaryDirectory() as directory:
+            page = Path(directory) / "attendance.html"
+            page.write_text(FULL_ATTENDANCE, encoding="utf-8")
+            data = vitapmate.parse_full_attendance(page, "AP2024252", "AP2024252000123", "ETH")
+        self.assertEqual([r.status for r in data.records], ["Present", "Absent"])
+        self.assertEqual(data.course_type, "ETH")
+
+    def test_models_convert_back_to_dicts(self):
+        data = vitapmate.parse_semesters(SEMESTERS)
+        raw = dataclasses.asdict(data)
+        self.assertEqual(raw["semesters"][0], {"id": "AP2024252", "name": "Winter Semester 2024-25"})
+        self.assertIsInstance(raw["update_time"], int)
+
+    def test_pages_without_data_parse_to_empty_models(self):
+        self.assertEqual(vitapmate.parse_marks("<html></html>", "AP2024252").records, [])
+        self.assertEqual(vitapmate.parse_exam_schedule("<html></html>", "AP2024252").exams, [])
+        self.assertEqual(vitapmate.parse_timetable("<html></html>", "AP2024252").slots, [])
+
+
+GRADE_VIEW = """<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>L</th><th>P</th><th>C</th><th>Grading Type</th><th>Grand Total</th><th>Grade</th><th>Result</th></tr>
+<tr><td>1</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>3</td><td>2</td><td>4</td><td>RG</td><td>81</td><td>A</td><td>PASS</td></tr>
+<tr><td>2</td><td>STS2001</td><td>Soft Skills</td><td>SS</td><td>0</td><td>0</td><td>1</td><td>PF</td><td>-</td><td>P</td><td>PASS</td></tr>
+</table>
+<p>CGPA : 8.80 GPA : 9.00</p>"""
+
+PAYMENTS = """<table>
+<tr><th>Sl.No.</th><th>Receipt No</th><th>Date</th><th>Description</th><th>Amount</th><th>Mode</th><th>Status</th><th></th></tr>
+<tr><td>1</td><td>RC1001</td><td>05-Jul-2024</td><td>Tuition Fee</td><td>1,25,000.00</td><td>Online</td><td>Success</td>
+<td><button onclick="doDownloadReceipt('RC1001','2024AP01')">Receipt</button></td></tr>
+<tr><td>2</td><td>-</td><td>06-Jul-2024</td><td>Hostel Fee</td><td>80,000.00</td><td>Online</td><td>Failed</td><td></td></tr>
+</table>"""
+
+
+@unittest.skipUnless(vitapmate.UNVERIFIED_ENDPOINTS, "built without unverified-endpoints")
+class UnverifiedParserTests(unittest.TestCase):
+    def test_semester_grades(self):
+        data = vitapmate.parse_semester_grades(GRADE_VIEW, "AP2024252")
+        self.assertIsInstance(data, vitapmate.SemesterGradesData)
+        compiler, soft_skills = data.grades
+        self.assertEqual(compiler.credits, 4.0)
+        self.assertEqual(compiler.grade, "A")
+        self.assertEqual(soft_skills.grade, "P")
+        self.assertAlmostEqual(data.published_gpa, 9.0)
+
+    def test_payment_history(self):
+        data = vitapmate.parse_payment_history(PAYMENTS)
+        self.assertEqual([p.receipt_no for p in data.payments], ["RC1001", "-"])
+        self.assertEqual([p.has_receipt for p in data.payments], [True, False])
+
+    def test_hostel_kind_must_be_outing_or_leave(self):
+        data = vitapmate.parse_hostel_applications("<html></html>", vitapmate.LEAVE)
+        self.assertEqual(data.kind, "Leave")
+        self.assertEqual(data.applications, [])
+        with self.assertRaises(ValueError):
+            vitapmate.parse_hostel_applications("<html></html>", "Vacation")
+
+    def test_feedback_responses_keep_the_rust_shape(self):
+        self.assertEqual(
+            dataclasses.asdict(vitapmate.FeedbackResponse.rating("q1", 4)),
+            {"question_id": "q1", "answer": {"Rating": 4}},
+        )
+        self.assertEqual(
+            dataclasses.asdict(vitapmate.FeedbackResponse.text("q2", "Good pace")),
+            {"question_id": "q2", "answer": {"Text": "Good pace"}},
+        )
+
+
+@unittest.skipIf(vitapmate.UNVERIFIED_ENDPOINTS, "built with unverified-endpoints")
+class WithoutUnverifiedEndpointsTests(unittest.TestCase):
+    def test_their_parsers_say_what_is_missing(self):
+        with self.assertRaisesRegex(NotImplementedError, "unverified-endpoints"):
+            vitapmate.parse_fee_dues("<html></html>")
+
+
+if __name__ == "__main__":
+    unittest.main()
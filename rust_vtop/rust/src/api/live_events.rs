@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+// How many (topic, kind, key) payloads `publish_if_changed` remembers before
+// forgetting the least recently used one.
+pub const DEFAULT_DEDUP_LIMIT: usize = 10_000;
+
+// Topics a client can subscribe to. Per-student topics carry the registration
+// number; `AccessTokens::authorize` decides who may subscribe to them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Topic {
+    FacultyCabin,
+    Sync(String),
+    Marks(String),
+    Attendance(String),
+    // Spotlight announcements and VTOP messages.
+    Announcements(String),
+}
+
+impl Topic {
+    // The student a topic belongs to; `None` for topics anyone may read.
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            Topic::FacultyCabin => None,
+            Topic::Sync(owner)
+            | Topic::Marks(owner)
+            | Topic::Attendance(owner)
+            | Topic::Announcements(owner) => Some(owner),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Topic> {
+        let value = value.trim();
+        if value == "faculty-cabin" {
+            return Some(Topic::FacultyCabin);
+        }
+        let (kind, owner) = value.split_once(':')?;
+        let owner = owner.trim().to_uppercase();
+        if owner.is_empty() {
+            return None;
+        }
+        match kind {
+            "sync" => Some(Topic::Sync(owner)),
+            "marks" => Some(Topic::Marks(owner)),
+            "attendance" => Some(Topic::Attendance(owner)),
+            "announcements" => Some(Topic::Announcements(owner)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Topic::FacultyCabin => write!(f, "faculty-cabin"),
+            Topic::Sync(owner) => write!(f, "sync:{}", owner),
+            Topic::Marks(owner) => write!(f, "marks:{}", owner),
+            Topic::Attendance(owner) => write!(f, "attendance:{}", owner),
+            Topic::Announcements(owner) => write!(f, "announcements:{}", owner),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveEvent {
+    pub id: u64,
+    pub topic: String,
+    pub kind: String,
+    pub data: Value,
+    pub timestamp: u64,
+}
+
+pub enum SubscriptionItem {
+    Event(LiveEvent),
+    // The subscriber missed events that are no longer buffered and should
+    // refetch its state instead of relying on the stream.
+    Resync,
+}
+
+type Slot = (String, String, String);
+
+struct HubState {
+    next_id: u64,
+    capacity: usize,
+    buffer: VecDeque<LiveEvent>,
+    // Payload and when it was last used, counted in `uses`.
+    last_payloads: HashMap<Slot, (Value, u64)>,
+    // The same slots by last use, oldest first, so eviction is a lookup.
+    by_last_use: BTreeMap<u64, Slot>,
+    uses: u64,
+    dedup_limit: usize,
+}
+
+impl HubState {
+    fn remember(&mut self, slot: Slot, data: Value) -> Option<Value> {
+        self.uses += 1;
+        self.by_last_use.insert(self.uses, slot.clone());
+        let previous = self.last_payloads.insert(slot, (data, self.uses));
+        match &previous {
+            Some((_, used)) => {
+                self.by_last_use.remove(used);
+            }
+            None if self.last_payloads.len() > self.dedup_limit => {
+                if let Some((_, oldest)) = self.by_last_use.pop_first() {
+                    self.last_payloads.remove(&oldest);
+                }
+            }
+            None => {}
+        }
+        previous.map(|(data, _)| data)
+    }
+}
+
+pub struct EventHub {
+    state: Mutex<HubState>,
+    sender: broadcast::Sender<LiveEvent>,
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_dedup_limit(capacity, DEFAULT_DEDUP_LIMIT)
+    }
+
+    pub fn with_dedup_limit(capacity: usize, dedup_limit: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            state: Mutex::new(HubState {
+                next_id: 1,
+                capacity,
+                buffer: VecDeque::with_capacity(capacity),
+                last_payloads: HashMap::new(),
+                by_last_use: BTreeMap::new(),
+                uses: 0,
+                dedup_limit: dedup_limit.max(1),
+            }),
+            sender,
+        }
+    }
+
+    pub fn publish(&self, topic: &Topic, kind: &str, data: Value) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        Self::push(&mut state, &self.sender, topic, kind, data)
+    }
+
+    // Publishes only when `data` differs from the last payload published for
+    // the same topic, kind and key. Returns the new event id if one was sent.
+    // Only the most recently used `dedup_limit` payloads are remembered, so a
+    // long-forgotten payload is published again.
+    pub fn publish_if_changed(
+        &self,
+        topic: &Topic,
+        kind: &str,
+        key: &str,
+        data: Value,
+    ) -> Option<u64> {
+        let mut state = self.state.lock().unwrap();
+        let slot = (topic.to_string(), kind.to_string(), key.to_string());
+        if state.remember(slot, data.clone()).as_ref() == Some(&data) {
+            return None;
+        }
+        Some(Self::push(&mut state, &self.sender, topic, kind, data))
+    }
+
+    // Stores `data` under the topic, kind and key without publishing it and
+    // returns what was stored before, for callers with their own notion of
+    // what changed. Shares `publish_if_changed`'s bounded store.
+    pub fn replace_last(&self, topic: &Topic, kind: &str, key: &str, data: Value) -> Option<Value> {
+        let mut state = self.state.lock().unwrap();
+        let slot = (topic.to_string(), kind.to_string(), key.to_string());
+        state.remember(slot, data)
+    }
+
+    pub fn remembered_payloads(&self) -> usize {
+        self.state.lock().unwrap().last_payloads.len()
+    }
+
+    pub fn subscribe(&self, topics: Vec<Topic>, last_event_id: Option<u64>) -> Subscription {
+        let topics: Vec<String> = topics.iter().map(|t| t.to_string()).collect();
+        // Holding the lock while subscribing guarantees nothing is published
+        // between the replay snapshot and the live receiver.
+        let state = self.state.lock().unwrap();
+        let receiver = self.sender.subscribe();
+        let mut replay = VecDeque::new();
+        let mut resync = false;
+        if let Some(after) = last_event_id {
+            let oldest = state.buffer.front().map(|e| e.id).unwrap_or(state.next_id);
+            // Either the buffer already dropped events the client has not seen,
+            // or the id comes from before a server restart.
+            resync = after + 1 < oldest || after >= state.next_id;
+            if !resync {
+                replay = state
+                    .buffer
+                    .iter()
+                    .filter(|e| e.id > after && topics.contains(&e.topic))
+                    .cloned()
+                    .collect();
+            }
+        }
+        Subscription {
+            topics,
+            replay,
+            receiver,
+            resync,
+        }
+    }
+
+    fn push(
+        state: &mut HubState,
+        sender: &broadcast::Sender<LiveEvent>,
+        topic: &Topic,
+        kind: &str,
+        data: Value,
+    ) -> u64 {
+        let event = LiveEvent {
+            id: state.next_id,
+            topic: topic.to_string(),
+            kind: kind.to_string(),
+            data,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::new(1, 0))
+                .as_secs(),
+        };
+        state.next_id += 1;
+        if state.buffer.len() == state.capacity {
+            state.buffer.pop_front();
+        }
+        state.buffer.push_back(event.clone());
+        // No receivers is not an error, the event stays in the replay buffer.
+        let _ = sender.send(event.clone());
+        event.id
+    }
+}
+
+pub struct Subscription {
+    topics: Vec<String>,
+    replay: VecDeque<LiveEvent>,
+    receiver: broadcast::Receiver<LiveEvent>,
+    resync: bool,
+}
+
+impl Subscription {
+    pub async fn next(&mut self) -> Option<SubscriptionItem> {
+        if self.resync {
+            self.resync = false;
+            return Some(SubscriptionItem::Resync);
+        }
+        if let Some(event) = self.replay.pop_front() {
+            return Some(SubscriptionItem::Event(event));
+        }
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) if self.topics.contains(&event.topic) => {
+                    return Some(SubscriptionItem::Event(event))
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(_)) => return Some(SubscriptionItem::Resync),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessDenied {
+    // No token, or one that is unknown or expired.
+    Unauthenticated,
+    // A valid token for a different student.
+    Forbidden,
+}
+
+// Tokens the server hands out at login. A token lets its holder subscribe to
+// its own student's topics and to public ones, until it expires.
+pub struct AccessTokens {
+    ttl: Duration,
+    random: SystemRandom,
+    tokens: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl AccessTokens {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            random: SystemRandom::new(),
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn issue(&self, owner: &str) -> String {
+        let mut bytes = [0u8; 32];
+        self.random
+            .fill(&mut bytes)
+            .expect("system random number generator failed");
+        let token = URL_SAFE_NO_PAD.encode(bytes);
+        let now = Instant::now();
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|_, (_, expires)| *expires > now);
+        tokens.insert(token.clone(), (owner.trim().to_uppercase(), now + self.ttl));
+        token
+    }
+
+    // The registration number a live token was issued for.
+    pub fn owner(&self, token: &str) -> Option<String> {
+        let tokens = self.tokens.lock().unwrap();
+        let (owner, expires) = tokens.get(token)?;
+        (*expires > Instant::now()).then(|| owner.clone())
+    }
+
+    pub fn authorize(&self, token: Option<&str>, topics: &[Topic]) -> Result<(), AccessDenied> {
+        let owner = token
+            .and_then(|token| self.owner(token))
+            .ok_or(AccessDenied::Unauthenticated)?;
+        if topics
+            .iter()
+            .filter_map(Topic::owner)
+            .all(|topic_owner| topic_owner == owner)
+        {
+            Ok(())
+        } else {
+            Err(AccessDenied::Forbidden)
+        }
+    }
+}
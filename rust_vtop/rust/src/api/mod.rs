@@ -1,3 +1,4 @@
+pub mod live_events;
 pub mod simple;
 pub mod vtop;
 pub mod vtop_get_client;
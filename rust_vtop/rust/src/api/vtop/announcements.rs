@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+
+use super::paraser::parseannouncements;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{Announcement, AnnouncementsData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::VtopResult;
+
+impl VtopClient {
+    // Spotlight notices from every category.
+    pub async fn get_announcements(&self) -> VtopResult<AnnouncementsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Announcements));
+        let text = self.post_page("spotlight/viewSpotlight", &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Announcements));
+        Ok(parseannouncements::parse_spotlight(text))
+    }
+
+    pub async fn get_messages(&self) -> VtopResult<AnnouncementsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Announcements));
+        let text = self
+            .post_page("academics/common/StudentMessages", &[])
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Announcements));
+        Ok(parseannouncements::parse_messages(text))
+    }
+}
+
+// Items of `current` whose id was not in `previous`, in page order. Edited
+// notices keep their VTOP id and so are not reported again.
+pub fn new_announcements(
+    previous: &AnnouncementsData,
+    current: &AnnouncementsData,
+) -> Vec<Announcement> {
+    let seen: HashSet<&str> = previous
+        .announcements
+        .iter()
+        .map(|a| a.id.as_str())
+        .collect();
+    current
+        .announcements
+        .iter()
+        .filter(|a| !seen.contains(a.id.as_str()))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,77 @@
+use serde::Serialize;
+
+use super::paraser::page_util::any_date;
+use super::paraser::parsebiometric;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{BiometricLogData, BiometricPunch, FullAttendanceData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::VtopResult;
+
+// A class marked absent on a day the student punched in.
+#[derive(Debug, Clone, Serialize)]
+pub struct AttendanceMismatch {
+    pub course_id: String,
+    pub course_type: String,
+    pub date: String,
+    pub slot: String,
+    pub day_time: String,
+    pub status: String,
+    pub punches: Vec<BiometricPunch>,
+}
+
+impl VtopClient {
+    // Punches between two dates, both as `05-Mar-2025`.
+    pub async fn get_biometric_log(
+        &self,
+        from_date: &str,
+        to_date: &str,
+    ) -> VtopResult<BiometricLogData> {
+        self.report(VtopProgress::Fetching(FetchKind::Biometric));
+        let text = self
+            .post_page(
+                "academics/common/BiometricInfo",
+                &[("fromDate", from_date), ("toDate", to_date)],
+            )
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Biometric));
+        Ok(parsebiometric::parse_biometric_log(text))
+    }
+}
+
+// Absent records in `attendance` whose day has punches in `log`, in the
+// order of `attendance`. Days are compared as dates since the attendance
+// pages and the biometric devices write them differently.
+pub fn cross_check(
+    log: &BiometricLogData,
+    attendance: &[FullAttendanceData],
+) -> Vec<AttendanceMismatch> {
+    let punched: Vec<_> = log
+        .days
+        .iter()
+        .filter(|day| !day.punches.is_empty())
+        .filter_map(|day| Some((any_date(&day.date)?, &day.punches)))
+        .collect();
+    let mut mismatches = vec![];
+    for course in attendance {
+        for record in &course.records {
+            if !record.status.to_ascii_lowercase().contains("absent") {
+                continue;
+            }
+            let Some(day) = any_date(&record.date) else {
+                continue;
+            };
+            if let Some((_, punches)) = punched.iter().find(|(d, _)| *d == day) {
+                mismatches.push(AttendanceMismatch {
+                    course_id: course.course_id.clone(),
+                    course_type: course.course_type.clone(),
+                    date: record.date.clone(),
+                    slot: record.slot.clone(),
+                    day_time: record.day_time.clone(),
+                    status: record.status.clone(),
+                    punches: punches.to_vec(),
+                });
+            }
+        }
+    }
+    mismatches
+}
@@ -0,0 +1,116 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::future::join_all;
+use serde::Serialize;
+
+use super::progress::{CancelToken, FetchKind};
+use super::types::{
+    AttendanceData, ExamScheduleData, FullAttendanceData, MarksData, TimetableData,
+};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{VtopError, VtopResult};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleFailure {
+    pub part: FetchKind,
+    // Set for `FullAttendance` failures.
+    pub course_id: Option<String>,
+    pub error: VtopError,
+}
+
+// Everything the app shows for one semester. A part that could not be
+// fetched is `None` and has an entry in `failures`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SemesterBundle {
+    pub semester_id: String,
+    pub timetable: Option<TimetableData>,
+    pub attendance: Option<AttendanceData>,
+    pub marks: Option<MarksData>,
+    pub exam_schedule: Option<ExamScheduleData>,
+    // One entry per course in `attendance`, when asked for.
+    pub full_attendance: Vec<FullAttendanceData>,
+    pub failures: Vec<BundleFailure>,
+    pub update_time: u64,
+}
+
+impl SemesterBundle {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn keep<T>(&mut self, part: FetchKind, result: VtopResult<T>) -> Option<T> {
+        match result {
+            Ok(data) => Some(data),
+            Err(error) => {
+                self.failures.push(BundleFailure {
+                    part,
+                    course_id: None,
+                    error,
+                });
+                None
+            }
+        }
+    }
+}
+
+impl VtopClient {
+    // Fetches the semester's pages concurrently on this session. The shared
+    // request limiter still caps how many reach VTOP at once. Once `cancel`
+    // fires, the parts still being fetched fail with `Cancelled` and the
+    // finished ones are kept.
+    pub async fn get_semester_bundle(
+        &self,
+        semester_id: &str,
+        include_full_attendance: bool,
+        cancel: &CancelToken,
+    ) -> SemesterBundle {
+        let (timetable, attendance, marks, exam_schedule) = tokio::join!(
+            cancel.run(self.get_timetable(semester_id)),
+            cancel.run(self.get_attendance(semester_id)),
+            cancel.run(self.get_marks(semester_id)),
+            cancel.run(self.get_exam_schedule(semester_id)),
+        );
+
+        let mut bundle = SemesterBundle {
+            semester_id: semester_id.to_string(),
+            timetable: None,
+            attendance: None,
+            marks: None,
+            exam_schedule: None,
+            full_attendance: vec![],
+            failures: vec![],
+            update_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::new(1, 0))
+                .as_secs(),
+        };
+        bundle.timetable = bundle.keep(FetchKind::Timetable, timetable);
+        bundle.attendance = bundle.keep(FetchKind::Attendance, attendance);
+        bundle.marks = bundle.keep(FetchKind::Marks, marks);
+        bundle.exam_schedule = bundle.keep(FetchKind::ExamSchedule, exam_schedule);
+
+        if include_full_attendance {
+            let courses: Vec<(String, String)> = bundle
+                .attendance
+                .iter()
+                .flat_map(|attendance| &attendance.records)
+                .map(|record| (record.course_id.clone(), record.course_type.clone()))
+                .collect();
+            let details = join_all(courses.iter().map(|(course_id, course_type)| {
+                cancel.run(self.get_full_attendance(semester_id, course_id, course_type))
+            }))
+            .await;
+            for ((course_id, _), result) in courses.into_iter().zip(details) {
+                match result {
+                    Ok(data) => bundle.full_attendance.push(data),
+                    Err(error) => bundle.failures.push(BundleFailure {
+                        part: FetchKind::FullAttendance,
+                        course_id: Some(course_id),
+                        error,
+                    }),
+                }
+            }
+        }
+        bundle
+    }
+}
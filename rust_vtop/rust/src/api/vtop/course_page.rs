@@ -0,0 +1,404 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use reqwest::header::CONTENT_DISPOSITION;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::paraser::parsecoursepage;
+use super::progress::{CancelToken, FetchKind, VtopProgress};
+use super::types::{CourseMaterial, CoursePageCourses, CoursePageData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{VtopError, VtopResult};
+
+// Kept in the sync folder; remembers which material went to which file.
+const MANIFEST: &str = ".vitapmate-materials.json";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadedMaterial {
+    // From VTOP's `Content-Disposition`, when it sends one.
+    pub file_name: Option<String>,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MaterialSyncFailure {
+    pub course_code: String,
+    // Empty when the course page itself could not be loaded.
+    pub title: String,
+    pub error: VtopError,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MaterialSyncReport {
+    // Files written by this sync, relative to the sync folder.
+    pub downloaded: Vec<String>,
+    pub unchanged: usize,
+    pub failures: Vec<MaterialSyncFailure>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, SyncedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SyncedFile {
+    file: String,
+    bytes: u64,
+}
+
+impl Manifest {
+    // A missing or unreadable manifest means everything is downloaded again.
+    async fn load(directory: &Path) -> Self {
+        tokio::fs::read(directory.join(MANIFEST))
+            .await
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    async fn save(&self, directory: &Path) -> VtopResult<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(storage)?;
+        tokio::fs::write(directory.join(MANIFEST), json)
+            .await
+            .map_err(storage)
+    }
+
+    // VTOP gives re-uploaded material a new path, so a file that is still
+    // on disk at its recorded size is up to date.
+    async fn is_current(&self, directory: &Path, material: &CourseMaterial) -> bool {
+        let Some(synced) = self.files.get(&material.path) else {
+            return false;
+        };
+        match tokio::fs::metadata(directory.join(&synced.file)).await {
+            Ok(metadata) => metadata.len() == synced.bytes,
+            Err(_) => false,
+        }
+    }
+}
+
+impl VtopClient {
+    pub async fn get_course_page_courses(
+        &self,
+        semester_id: &str,
+    ) -> VtopResult<CoursePageCourses> {
+        self.report(VtopProgress::Fetching(FetchKind::CoursePage));
+        let text = self
+            .post_page("getCourseForCoursePage", &[("semSubId", semester_id)])
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::CoursePage));
+        Ok(parsecoursepage::parse_course_page_courses(
+            text,
+            semester_id.to_string(),
+        ))
+    }
+
+    pub async fn get_course_page(
+        &self,
+        semester_id: &str,
+        class_id: &str,
+        faculty_id: &str,
+    ) -> VtopResult<CoursePageData> {
+        self.report(VtopProgress::Fetching(FetchKind::CoursePage));
+        let text = self
+            .post_page(
+                "processViewStudentCourseDetail",
+                &[
+                    ("semSubId", semester_id),
+                    ("classId", class_id),
+                    ("erpId", faculty_id),
+                ],
+            )
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::CoursePage));
+        Ok(parsecoursepage::parse_course_page(
+            text,
+            semester_id.to_string(),
+            class_id.to_string(),
+            faculty_id.to_string(),
+        ))
+    }
+
+    // Streams the material into `writer` chunk by chunk, so large slide decks
+    // are never held in memory.
+    pub async fn download_material<W: AsyncWrite + Unpin + ?Sized>(
+        &self,
+        material: &CourseMaterial,
+        writer: &mut W,
+    ) -> VtopResult<DownloadedMaterial> {
+        let (response, file_name) = self.open_material(material).await?;
+        let bytes = write_body(response, writer).await?;
+        Ok(DownloadedMaterial { file_name, bytes })
+    }
+
+    // The response, once its headers are in, and the file name it suggests.
+    async fn open_material(
+        &self,
+        material: &CourseMaterial,
+    ) -> VtopResult<(reqwest::Response, Option<String>)> {
+        let response = self.get_file(&material.path).await?;
+        let file_name = response
+            .headers()
+            .get(CONTENT_DISPOSITION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(disposition_file_name);
+        Ok((response, file_name))
+    }
+
+    // Mirrors every course page's material into `directory`, one folder per
+    // course. Material downloaded by an earlier sync is skipped; a material
+    // or course that fails is reported and the rest still sync.
+    // Once `cancel` fires, the download in progress is discarded, the
+    // manifest is saved with what finished and `Cancelled` is returned; the
+    // next sync picks up from there.
+    pub async fn sync_course_materials(
+        &self,
+        semester_id: &str,
+        directory: &Path,
+        cancel: &CancelToken,
+    ) -> VtopResult<MaterialSyncReport> {
+        tokio::fs::create_dir_all(directory)
+            .await
+            .map_err(storage)?;
+        let courses = cancel
+            .run(self.get_course_page_courses(semester_id))
+            .await?;
+        let mut manifest = Manifest::load(directory).await;
+        let mut taken: HashSet<PathBuf> = manifest
+            .files
+            .values()
+            .map(|synced| PathBuf::from(&synced.file))
+            .collect();
+        let mut report = MaterialSyncReport::default();
+
+        for course in &courses.courses {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let page = match cancel
+                .run(self.get_course_page(semester_id, &course.class_id, &course.faculty_id))
+                .await
+            {
+                Ok(page) => page,
+                Err(VtopError::Cancelled) => break,
+                Err(error) => {
+                    report.failures.push(MaterialSyncFailure {
+                        course_code: course.course_code.clone(),
+                        title: String::new(),
+                        error,
+                    });
+                    continue;
+                }
+            };
+            let folder = PathBuf::from(sanitize(&format!(
+                "{} {} {}",
+                course.course_code, course.course_type, course.course_title
+            )));
+            let materials = page
+                .materials
+                .iter()
+                .chain(page.lectures.iter().flat_map(|lecture| &lecture.materials));
+            for material in materials {
+                if cancel.is_cancelled() {
+                    break;
+                }
+                if manifest.is_current(directory, material).await {
+                    report.unchanged += 1;
+                    continue;
+                }
+                // A deleted or truncated file is replaced, under its old name
+                // unless the download now asks for another one.
+                let stale = manifest
+                    .files
+                    .get(&material.path)
+                    .map(|stale| PathBuf::from(&stale.file));
+                if let Some(stale) = &stale {
+                    taken.remove(stale);
+                }
+                match self
+                    .save_material(
+                        directory,
+                        &folder,
+                        material,
+                        &mut taken,
+                        stale.as_deref(),
+                        cancel,
+                    )
+                    .await
+                {
+                    Ok(synced) => {
+                        let superseded = stale.filter(|stale| *stale != Path::new(&synced.file));
+                        if let Some(superseded) = superseded {
+                            let _ = tokio::fs::remove_file(directory.join(superseded)).await;
+                        }
+                        report.downloaded.push(synced.file.clone());
+                        manifest.files.insert(material.path.clone(), synced);
+                    }
+                    Err(VtopError::Cancelled) => break,
+                    Err(error) => report.failures.push(MaterialSyncFailure {
+                        course_code: course.course_code.clone(),
+                        title: material.title.clone(),
+                        error,
+                    }),
+                }
+            }
+        }
+        manifest.save(directory).await?;
+        if cancel.is_cancelled() {
+            return Err(VtopError::Cancelled);
+        }
+        Ok(report)
+    }
+
+    // Downloads next to its final place first, so an interrupted download
+    // never leaves a truncated file under the material's name. `replacing`
+    // is the material's file from an earlier sync, which may be overwritten.
+    async fn save_material(
+        &self,
+        directory: &Path,
+        folder: &Path,
+        material: &CourseMaterial,
+        taken: &mut HashSet<PathBuf>,
+        replacing: Option<&Path>,
+        cancel: &CancelToken,
+    ) -> VtopResult<SyncedFile> {
+        let (response, downloaded) = cancel.run(self.open_material(material)).await?;
+        tokio::fs::create_dir_all(directory.join(folder))
+            .await
+            .map_err(storage)?;
+        let name = file_name(&material.title, downloaded.as_deref());
+        let relative = unique_path(taken, directory, folder, &name, replacing).await;
+        let target = directory.join(&relative);
+        let part = target.with_file_name(format!(
+            ".{}.part",
+            relative.file_name().unwrap_or_default().to_string_lossy()
+        ));
+
+        let written = async {
+            let mut file = tokio::fs::File::create(&part).await.map_err(storage)?;
+            cancel.run(write_body(response, &mut file)).await
+        }
+        .await;
+        let bytes = match written {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                let _ = tokio::fs::remove_file(&part).await;
+                taken.remove(&relative);
+                return Err(error);
+            }
+        };
+        tokio::fs::rename(&part, &target).await.map_err(storage)?;
+        Ok(SyncedFile {
+            file: relative.to_string_lossy().into_owned(),
+            bytes,
+        })
+    }
+}
+
+// Streams a download into `writer` and returns how many bytes it had.
+async fn write_body<W: AsyncWrite + Unpin + ?Sized>(
+    mut response: reqwest::Response,
+    writer: &mut W,
+) -> VtopResult<u64> {
+    let mut bytes = 0;
+    while let Some(chunk) = response.chunk().await.map_err(VtopError::network)? {
+        writer.write_all(&chunk).await.map_err(storage)?;
+        bytes += chunk.len() as u64;
+    }
+    writer.flush().await.map_err(storage)?;
+    Ok(bytes)
+}
+
+fn storage(error: impl std::fmt::Display) -> VtopError {
+    VtopError::StorageError(error.to_string())
+}
+
+// `attachment; filename="Unit 1.pdf"` or RFC 5987's `filename*=UTF-8''Unit%201.pdf`.
+fn disposition_file_name(header: &str) -> Option<String> {
+    let mut plain = None;
+    for part in header.split(';').map(str::trim) {
+        if let Some(encoded) = part.strip_prefix("filename*=") {
+            let encoded = encoded.rsplit('\'').next().unwrap_or(encoded);
+            if let Ok(name) = urlencoding::decode(encoded) {
+                return Some(name.into_owned()).filter(|n| !n.is_empty());
+            }
+        } else if let Some(name) = part.strip_prefix("filename=") {
+            plain = Some(name.trim_matches('"').to_string()).filter(|n| !n.is_empty());
+        }
+    }
+    plain
+}
+
+// The faculty's title reads better than VTOP's generated file names; the
+// extension comes from the download.
+fn file_name(title: &str, downloaded: Option<&str>) -> String {
+    let extension = downloaded
+        .and_then(|name| Path::new(name).extension())
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    let title = sanitize(title);
+    match (title.is_empty(), downloaded, extension) {
+        (true, Some(name), _) => sanitize(name),
+        (false, _, Some(extension))
+            if !title
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", extension)) =>
+        {
+            format!("{}.{}", title, extension)
+        }
+        (true, None, _) => "material".to_string(),
+        _ => title,
+    }
+}
+
+// The first name for `name` in `folder` that no other material of this sync
+// has claimed and that is not already on disk, bar the file being replaced.
+async fn unique_path(
+    taken: &mut HashSet<PathBuf>,
+    directory: &Path,
+    folder: &Path,
+    name: &str,
+    replacing: Option<&Path>,
+) -> PathBuf {
+    let name = Path::new(name);
+    let stem = name.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = name
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = folder.join(name);
+    let mut copy = 2;
+    loop {
+        let free = Some(candidate.as_path()) == replacing
+            || !tokio::fs::try_exists(directory.join(&candidate))
+                .await
+                .unwrap_or(false);
+        if free && taken.insert(candidate.clone()) {
+            return candidate;
+        }
+        candidate = folder.join(format!("{} ({}){}", stem, copy, extension));
+        copy += 1;
+    }
+}
+
+// Keeps names valid on every OS the app runs on.
+fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    cleaned
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches('.')
+        .chars()
+        .take(120)
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,157 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use super::gpa;
+use super::paraser::parsecurriculum;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{AllocationData, CourseGrade, CurriculumCourse, CurriculumData, TimetableData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::VtopResult;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryProgress {
+    pub code: String,
+    pub name: String,
+    pub credits_required: f32,
+    pub credits_earned: f32,
+    pub credits_registered: f32,
+    // Still to be registered for after this semester's courses.
+    pub credits_remaining: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CurriculumProgress {
+    pub categories: Vec<CategoryProgress>,
+    pub completed: Vec<CurriculumCourse>,
+    // In the current timetable and not passed yet.
+    pub registered: Vec<CurriculumCourse>,
+    // Courses the curriculum names that are neither passed nor registered.
+    pub pending: Vec<CurriculumCourse>,
+    pub credits_required: f32,
+    pub credits_earned: f32,
+}
+
+impl VtopClient {
+    pub async fn get_curriculum(&self) -> VtopResult<CurriculumData> {
+        self.report(VtopProgress::Fetching(FetchKind::Curriculum));
+        let text = self.post_page("academics/common/Curriculum", &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Curriculum));
+        Ok(parsecurriculum::parse_curriculum(text))
+    }
+
+    pub async fn get_registration_allocation(&self) -> VtopResult<AllocationData> {
+        self.report(VtopProgress::Fetching(FetchKind::Curriculum));
+        let text = self
+            .post_page("academics/common/StudentRegistrationAllocation", &[])
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Curriculum));
+        Ok(parsecurriculum::parse_allocation(text))
+    }
+}
+
+fn passed(grade: &CourseGrade) -> bool {
+    match gpa::grade_points(&grade.grade) {
+        Some(points) => points > 0.0,
+        // Pass/fail courses only say so in the result column.
+        None => grade.result_status.eq_ignore_ascii_case("pass"),
+    }
+}
+
+// Puts `grades` (every semester's plus arrears) and the current timetable
+// against the curriculum. Electives are not named in the curriculum, so
+// their category and credits come from the registration allocation. VTOP's
+// own earned credits win over the ones counted from `grades`, which may not
+// cover every semester.
+pub fn curriculum_progress(
+    curriculum: &CurriculumData,
+    allocation: &AllocationData,
+    grades: &[CourseGrade],
+    timetable: &TimetableData,
+) -> CurriculumProgress {
+    let mut catalog: HashMap<&str, CurriculumCourse> = HashMap::new();
+    for course in &curriculum.courses {
+        catalog.insert(&course.course_code, course.clone());
+    }
+    for allotted in &allocation.allocations {
+        catalog
+            .entry(&allotted.course_code)
+            .or_insert_with(|| CurriculumCourse {
+                category: allotted.category.clone(),
+                course_code: allotted.course_code.clone(),
+                course_title: allotted.course_title.clone(),
+                course_type: allotted.course_type.clone(),
+                credits: allotted.credits,
+            });
+    }
+    let course_of = |code: &str, fallback: Option<&CourseGrade>| {
+        catalog
+            .get(code)
+            .cloned()
+            .unwrap_or_else(|| CurriculumCourse {
+                category: String::new(),
+                course_code: code.to_string(),
+                course_title: fallback.map(|g| g.course_title.clone()).unwrap_or_default(),
+                course_type: fallback.map(|g| g.course_type.clone()).unwrap_or_default(),
+                credits: fallback.map(|g| g.credits).unwrap_or_default(),
+            })
+    };
+
+    let mut done = HashSet::new();
+    let mut completed = vec![];
+    for grade in grades.iter().filter(|grade| passed(grade)) {
+        if done.insert(grade.course_code.as_str()) {
+            completed.push(course_of(&grade.course_code, Some(grade)));
+        }
+    }
+    let mut registered = vec![];
+    let mut taking = HashSet::new();
+    for slot in &timetable.slots {
+        let code = slot.course_code.as_str();
+        if !code.is_empty() && !done.contains(code) && taking.insert(code) {
+            registered.push(course_of(code, None));
+        }
+    }
+    let pending: Vec<_> = curriculum
+        .courses
+        .iter()
+        .filter(|c| !done.contains(c.course_code.as_str()))
+        .filter(|c| !taking.contains(c.course_code.as_str()))
+        .cloned()
+        .collect();
+
+    let credits_in = |courses: &[CurriculumCourse], category: &str| -> f32 {
+        courses
+            .iter()
+            .filter(|c| c.category.eq_ignore_ascii_case(category))
+            .map(|c| c.credits)
+            .sum()
+    };
+    let categories: Vec<_> = curriculum
+        .categories
+        .iter()
+        .map(|category| {
+            let earned = category
+                .credits_earned
+                .unwrap_or_else(|| credits_in(&completed, &category.code));
+            let registered = credits_in(&registered, &category.code);
+            CategoryProgress {
+                code: category.code.clone(),
+                name: category.name.clone(),
+                credits_required: category.credits_required,
+                credits_earned: earned,
+                credits_registered: registered,
+                credits_remaining: (category.credits_required - earned - registered).max(0.0),
+            }
+        })
+        .collect();
+
+    CurriculumProgress {
+        credits_required: categories.iter().map(|c| c.credits_required).sum(),
+        credits_earned: categories.iter().map(|c| c.credits_earned).sum(),
+        categories,
+        completed,
+        registered,
+        pending,
+    }
+}
@@ -0,0 +1,88 @@
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+const FILE_PREFIX: &str = "vtop-";
+
+// Opt-in sink for pages VTOP returned when something went wrong, e.g. a
+// failed login. Pages should be passed through `redact::scrub_page` first.
+// Only the newest `max_files` captures are kept in `directory`; other files
+// there are left alone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugCaptureConfig {
+    pub directory: String,
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    20
+}
+
+impl DebugCaptureConfig {
+    pub fn new(directory: impl Into<String>, max_files: usize) -> Self {
+        Self {
+            directory: directory.into(),
+            max_files,
+        }
+    }
+
+    pub fn capture(&self, label: &str, page: &str) -> io::Result<PathBuf> {
+        let directory = PathBuf::from(&self.directory);
+        std::fs::create_dir_all(&directory)?;
+
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::new(1, 0))
+            .as_millis();
+        let label: String = label
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = directory.join(format!("{}{:015}-{}.html", FILE_PREFIX, millis, label));
+        std::fs::write(&path, page)?;
+
+        self.prune()?;
+        Ok(path)
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        let mut captures: Vec<PathBuf> = std::fs::read_dir(&self.directory)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(is_capture)
+                    .unwrap_or(false)
+            })
+            .collect();
+        // The zero-padded timestamp makes name order chronological.
+        captures.sort();
+        let excess = captures.len().saturating_sub(self.max_files);
+        for path in captures.into_iter().take(excess) {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+// Names written by `capture`: the prefix, a 15-digit timestamp, a dash and
+// the label.
+fn is_capture(name: &str) -> bool {
+    let Some(rest) = name
+        .strip_prefix(FILE_PREFIX)
+        .and_then(|rest| rest.strip_suffix(".html"))
+    else {
+        return false;
+    };
+    let (Some(stamp), Some(label)) = (rest.get(..15), rest.get(15..)) else {
+        return false;
+    };
+    stamp.bytes().all(|b| b.is_ascii_digit())
+        && label.starts_with('-')
+        && label[1..]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -0,0 +1,168 @@
+use std::future::Future;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::stream::{self, StreamExt};
+use reqwest::multipart::{Form, Part};
+use serde::Serialize;
+
+use super::paraser::parseda::{self, UploadReply};
+use super::progress::{FetchKind, VtopProgress};
+use super::types::DigitalAssignmentData;
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{VtopError, VtopResult};
+
+// Wrong OTPs VTOP accepts before the upload has to be started over.
+const MAX_OTP_ATTEMPTS: u32 = 3;
+// Course pages requested at once, so one listing cannot take every slot of
+// the shared request limiter.
+const MAX_COURSE_PAGES: usize = 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DigitalAssignmentFailure {
+    pub class_id: String,
+    pub course_code: String,
+    pub error: VtopError,
+}
+
+// The semester's assignments. A course whose page could not be loaded is
+// left out of `assignments` and listed in `failures`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigitalAssignmentsReport {
+    pub assignments: DigitalAssignmentData,
+    pub failures: Vec<DigitalAssignmentFailure>,
+}
+
+impl VtopClient {
+    // Lists the semester's courses, then loads every course's assignments,
+    // a few courses at a time. Only a failure to list the courses fails the
+    // call.
+    pub async fn get_digital_assignments(
+        &self,
+        semester_id: &str,
+    ) -> VtopResult<DigitalAssignmentsReport> {
+        self.report(VtopProgress::Fetching(FetchKind::DigitalAssignments));
+        let text = self
+            .post_page(
+                "examinations/doDigitalAssignment",
+                &[("semSubId", semester_id)],
+            )
+            .await?;
+        let courses = parseda::parse_da_courses(text);
+        // Owned ids keep the future `Send`, so it can be spawned.
+        let class_ids: Vec<String> = courses.iter().map(|c| c.class_id.clone()).collect();
+        let pages: Vec<_> = stream::iter(class_ids.into_iter().map(|class_id| async move {
+            self.post_page(
+                "examinations/processDigitalAssignment",
+                &[("classId", class_id.as_str())],
+            )
+            .await
+        }))
+        .buffered(MAX_COURSE_PAGES)
+        .collect()
+        .await;
+
+        self.report(VtopProgress::Parsing(FetchKind::DigitalAssignments));
+        let mut loaded = vec![];
+        let mut failures = vec![];
+        for (mut course, page) in courses.into_iter().zip(pages) {
+            match page {
+                Ok(page) => {
+                    course.assignments = parseda::parse_assignments(page);
+                    loaded.push(course);
+                }
+                Err(error) => failures.push(DigitalAssignmentFailure {
+                    class_id: course.class_id,
+                    course_code: course.course_code,
+                    error,
+                }),
+            }
+        }
+        Ok(DigitalAssignmentsReport {
+            assignments: DigitalAssignmentData {
+                courses: loaded,
+                semester_id: semester_id.to_string(),
+                update_time: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or(Duration::new(1, 0))
+                    .as_secs(),
+            },
+            failures,
+        })
+    }
+
+    // Uploads `content` as the answer to assignment `code` of `class_id` and
+    // returns VTOP's confirmation. When VTOP asks for the OTP it mailed, `otp`
+    // is called with VTOP's prompt (or the reason the last OTP was refused);
+    // returning `None` abandons the upload.
+    pub async fn upload_digital_assignment<F, Fut>(
+        &self,
+        class_id: &str,
+        code: &str,
+        file_name: &str,
+        content: Vec<u8>,
+        mut otp: F,
+    ) -> VtopResult<String>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Option<String>>,
+    {
+        let file = Part::bytes(content)
+            .file_name(file_name.to_string())
+            .mime_str(mime_type(file_name))
+            .map_err(|e| VtopError::ConfigurationError(e.to_string()))?;
+        let form = Form::new()
+            .text("classId", class_id.to_string())
+            .text("code", code.to_string())
+            .part("studDaUpload", file);
+        let mut reply = parseda::parse_upload_reply(
+            &self.post_multipart("examinations/doDAUpload", form).await?,
+        );
+
+        for _ in 0..MAX_OTP_ATTEMPTS {
+            let prompt = match reply {
+                UploadReply::Done(message) => return Ok(message),
+                UploadReply::Rejected(message) => return Err(VtopError::RequestRejected(message)),
+                UploadReply::NeedsOtp(prompt) => prompt,
+            };
+            let Some(code_from_mail) = otp(prompt).await else {
+                return Err(VtopError::Cancelled);
+            };
+            let page = self
+                .post_page(
+                    "examinations/doDAUploadOtp",
+                    &[
+                        ("classId", class_id),
+                        ("code", code),
+                        ("otp", code_from_mail.trim()),
+                    ],
+                )
+                .await?;
+            reply = parseda::parse_upload_reply(&page);
+        }
+        match reply {
+            UploadReply::Done(message) => Ok(message),
+            UploadReply::Rejected(message) | UploadReply::NeedsOtp(message) => {
+                Err(VtopError::RequestRejected(message))
+            }
+        }
+    }
+}
+
+// VTOP checks the declared type against the formats it allows.
+fn mime_type(file_name: &str) -> &'static str {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "zip" => "application/zip",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        _ => "application/octet-stream",
+    }
+}
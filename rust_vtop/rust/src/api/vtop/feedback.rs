@@ -0,0 +1,150 @@
+use serde::Serialize;
+
+use super::paraser::parsefeedback;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{
+    FeedbackAnswer, FeedbackForm, FeedbackFormsData, FeedbackQuestionKind, FeedbackQuestionsData,
+    FeedbackResponse,
+};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{FormError, VtopError, VtopResult};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedbackSubmission {
+    // VTOP's confirmation.
+    pub message: String,
+    // Forms of the semester still to be submitted afterwards, or `None`
+    // when they could not be fetched again. The feedback was saved either
+    // way.
+    pub outstanding: Option<Vec<FeedbackForm>>,
+}
+
+impl FeedbackFormsData {
+    pub fn pending(&self) -> Vec<FeedbackForm> {
+        self.forms
+            .iter()
+            .filter(|f| !f.submitted)
+            .cloned()
+            .collect()
+    }
+}
+
+impl FeedbackQuestionsData {
+    // Every response must name a question on the form, at most once, with
+    // an answer of the question's kind; every required question needs a
+    // non-blank answer.
+    pub fn validate(&self, responses: &[FeedbackResponse]) -> Result<(), FormError> {
+        let mut answered: Vec<&str> = vec![];
+        for response in responses {
+            let Some(question) = self.questions.iter().find(|q| q.id == response.question_id)
+            else {
+                return Err(FormError::UnknownQuestion(response.question_id.clone()));
+            };
+            let valid = match (&question.kind, &response.answer) {
+                (FeedbackQuestionKind::Rating { min, max }, FeedbackAnswer::Rating(rating)) => {
+                    (min..=max).contains(&rating)
+                }
+                (FeedbackQuestionKind::Choice { options }, FeedbackAnswer::Choice(choice)) => {
+                    options.contains(choice)
+                }
+                (FeedbackQuestionKind::Text, FeedbackAnswer::Text(_)) => true,
+                _ => false,
+            };
+            if !valid || answered.contains(&question.id.as_str()) {
+                return Err(FormError::InvalidAnswer(question.text.clone()));
+            }
+            if !matches!(&response.answer, FeedbackAnswer::Text(text) if text.trim().is_empty()) {
+                answered.push(&question.id);
+            }
+        }
+        match self
+            .questions
+            .iter()
+            .find(|q| q.required && !answered.contains(&q.id.as_str()))
+        {
+            Some(question) => Err(FormError::UnansweredQuestion(question.text.clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl VtopClient {
+    pub async fn get_feedback_forms(&self, semester_id: &str) -> VtopResult<FeedbackFormsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Feedback));
+        let text = self
+            .post_page(
+                "academics/common/StudentFeedback",
+                &[("semSubId", semester_id)],
+            )
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Feedback));
+        Ok(parsefeedback::parse_feedback_forms(
+            text,
+            semester_id.to_string(),
+        ))
+    }
+
+    pub async fn get_feedback_questions(
+        &self,
+        semester_id: &str,
+        form: &FeedbackForm,
+    ) -> VtopResult<FeedbackQuestionsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Feedback));
+        let text = self
+            .post_page(
+                "academics/common/getFeedbackQuestions",
+                &[
+                    ("semSubId", semester_id),
+                    ("formId", &form.form_id),
+                    ("classId", &form.class_id),
+                ],
+            )
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Feedback));
+        Ok(parsefeedback::parse_feedback_questions(
+            text,
+            semester_id.to_string(),
+            form.form_id.clone(),
+        ))
+    }
+
+    // Validates `responses` against `form` first; nothing is sent when that
+    // fails. Unanswered optional questions are left out of the submission.
+    pub async fn submit_feedback(
+        &self,
+        form: &FeedbackQuestionsData,
+        responses: &[FeedbackResponse],
+    ) -> VtopResult<FeedbackSubmission> {
+        form.validate(responses).map_err(VtopError::InvalidForm)?;
+        let answers: Vec<(&str, String)> = responses
+            .iter()
+            .map(|response| {
+                let value = match &response.answer {
+                    FeedbackAnswer::Rating(rating) => rating.to_string(),
+                    FeedbackAnswer::Choice(choice) => choice.clone(),
+                    FeedbackAnswer::Text(text) => text.trim().to_string(),
+                };
+                (response.question_id.as_str(), value)
+            })
+            .collect();
+        let mut fields = vec![
+            ("semSubId", form.semester_id.as_str()),
+            ("formId", form.form_id.as_str()),
+        ];
+        fields.extend(answers.iter().map(|(id, value)| (*id, value.as_str())));
+        let message = self
+            .post_form("academics/common/saveFeedback", &fields)
+            .await?;
+        let outstanding = match self.get_feedback_forms(&form.semester_id).await {
+            Ok(forms) => Some(forms.pending()),
+            Err(e) => {
+                tracing::warn!(error = %e, "feedback saved but the forms could not be refetched");
+                None
+            }
+        };
+        Ok(FeedbackSubmission {
+            message,
+            outstanding,
+        })
+    }
+}
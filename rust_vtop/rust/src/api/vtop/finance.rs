@@ -0,0 +1,39 @@
+use scraper::Html;
+
+use super::paraser::{page_util, parsefinance};
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{FeeDuesData, PaymentHistoryData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{VtopError, VtopResult};
+
+impl VtopClient {
+    pub async fn get_fee_dues(&self) -> VtopResult<FeeDuesData> {
+        self.report(VtopProgress::Fetching(FetchKind::Finance));
+        let text = self.post_page("finance/Payments", &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Finance));
+        Ok(parsefinance::parse_fee_dues(text))
+    }
+
+    pub async fn get_payment_history(&self) -> VtopResult<PaymentHistoryData> {
+        self.report(VtopProgress::Fetching(FetchKind::Finance));
+        let text = self.post_page("p2p/getReceiptsApplno", &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Finance));
+        Ok(parsefinance::parse_payment_history(text))
+    }
+
+    // The receipt PDF of a payment from `get_payment_history`. VTOP answers
+    // with an HTML page instead when it has no receipt for that number.
+    pub async fn get_fee_receipt(&self, receipt_no: &str) -> VtopResult<Vec<u8>> {
+        let response = self
+            .post_file("finance/getDownloadReceipt", &[("receiptNo", receipt_no)])
+            .await?;
+        let bytes = response.bytes().await.map_err(VtopError::network)?;
+        if !bytes.starts_with(b"%PDF") {
+            let page = String::from_utf8_lossy(&bytes);
+            return Err(VtopError::RequestRejected(page_util::page_message(
+                &Html::parse_document(&page),
+            )));
+        }
+        Ok(bytes.to_vec())
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::types::CourseGrade;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GpaSummary {
+    pub gpa: f32,
+    // Credits that went into `gpa`.
+    pub credits_counted: f32,
+    // Credits of courses with a passing grade.
+    pub credits_earned: f32,
+}
+
+// Points on VIT's 10 point scale. `None` for grades that carry no points,
+// such as `P` for pass/fail courses or `W` for withdrawn ones.
+pub fn grade_points(grade: &str) -> Option<f32> {
+    match grade.trim().to_ascii_uppercase().as_str() {
+        "S" | "O" => Some(10.0),
+        "A" => Some(9.0),
+        "B" => Some(8.0),
+        "C" => Some(7.0),
+        "D" => Some(6.0),
+        "E" => Some(5.0),
+        "F" | "N" | "N1" | "N2" | "N3" | "N4" => Some(0.0),
+        _ => None,
+    }
+}
+
+fn points_of(grade: &CourseGrade) -> Option<f32> {
+    grade_points(&grade.grade).or(grade.grade_points)
+}
+
+fn summarize<'a>(grades: impl IntoIterator<Item = &'a CourseGrade>) -> GpaSummary {
+    let mut weighted = 0.0;
+    let mut credits_counted = 0.0;
+    let mut credits_earned = 0.0;
+    for grade in grades {
+        let Some(points) = points_of(grade) else {
+            continue;
+        };
+        weighted += points * grade.credits;
+        credits_counted += grade.credits;
+        if points > 0.0 {
+            credits_earned += grade.credits;
+        }
+    }
+    GpaSummary {
+        gpa: if credits_counted > 0.0 {
+            weighted / credits_counted
+        } else {
+            0.0
+        },
+        credits_counted,
+        credits_earned,
+    }
+}
+
+// A semester's GPA. Failed courses count with zero points.
+pub fn semester_gpa(grades: &[CourseGrade]) -> GpaSummary {
+    summarize(grades)
+}
+
+// The CGPA over `grades` given oldest first, e.g. every semester's grades
+// followed by the arrears view. Only a course's latest attempt counts, and
+// only once it is passed, as in VIT's regulations.
+pub fn cgpa<'a>(grades: impl IntoIterator<Item = &'a CourseGrade>) -> GpaSummary {
+    let mut latest: HashMap<&str, &CourseGrade> = HashMap::new();
+    let mut order = vec![];
+    for grade in grades {
+        if points_of(grade).is_none() {
+            continue;
+        }
+        if latest.insert(&grade.course_code, grade).is_none() {
+            order.push(grade.course_code.as_str());
+        }
+    }
+    summarize(
+        order
+            .into_iter()
+            .map(|code| latest[code])
+            .filter(|grade| points_of(grade).is_some_and(|points| points > 0.0)),
+    )
+}
@@ -0,0 +1,32 @@
+use super::paraser::parsegrades;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{ArrearsData, SemesterGradesData};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::VtopResult;
+
+impl VtopClient {
+    pub async fn get_semester_grades(&self, semester_id: &str) -> VtopResult<SemesterGradesData> {
+        self.report(VtopProgress::Fetching(FetchKind::Grades));
+        let text = self
+            .post_page(
+                "examinations/examGradeView/doStudentGradeView",
+                &[("semesterSubId", semester_id)],
+            )
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Grades));
+        Ok(parsegrades::parse_semester_grades(
+            text,
+            semester_id.to_string(),
+        ))
+    }
+
+    // Re-exam and arrear results across all semesters.
+    pub async fn get_arrears(&self) -> VtopResult<ArrearsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Grades));
+        let text = self
+            .post_page("examinations/doStudentArrearView", &[])
+            .await?;
+        self.report(VtopProgress::Parsing(FetchKind::Grades));
+        Ok(parsegrades::parse_arrears(text))
+    }
+}
@@ -0,0 +1,110 @@
+use super::paraser::page_util::vtop_date;
+use super::paraser::parsehostel;
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{HostelApplicationsData, HostelRequest, HostelRequestKind};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::{FormError, VtopError, VtopResult};
+
+impl HostelRequestKind {
+    fn list_path(self) -> &'static str {
+        match self {
+            HostelRequestKind::Outing => "hostel/StudentGeneralOuting",
+            HostelRequestKind::Leave => "hostel/StudentLeaveRequest",
+        }
+    }
+
+    fn save_path(self) -> &'static str {
+        match self {
+            HostelRequestKind::Outing => "hostel/saveGeneralOutingForm",
+            HostelRequestKind::Leave => "hostel/saveLeaveRequestForm",
+        }
+    }
+
+    fn cancel_path(self) -> &'static str {
+        match self {
+            HostelRequestKind::Outing => "hostel/deleteGeneralOutingInfo",
+            HostelRequestKind::Leave => "hostel/deleteLeaveRequestInfo",
+        }
+    }
+}
+
+impl HostelRequest {
+    // The checks VTOP's form makes in the browser, so a bad request fails
+    // with a specific `FormError` instead of VTOP's generic alert.
+    pub fn validate(&self) -> Result<(), FormError> {
+        for (field, value) in [("place", &self.place), ("purpose", &self.purpose)] {
+            if value.trim().is_empty() {
+                return Err(FormError::MissingField(field.to_string()));
+            }
+        }
+        let phone = self.contact_number.trim();
+        if phone.len() != 10 || !phone.chars().all(|c| c.is_ascii_digit()) {
+            return Err(FormError::InvalidPhone(self.contact_number.clone()));
+        }
+        let from = (parse_date(&self.from_date)?, parse_time(&self.from_time)?);
+        let to = (parse_date(&self.to_date)?, parse_time(&self.to_time)?);
+        if self.kind == HostelRequestKind::Outing && from.0 != to.0 {
+            return Err(FormError::OutingSpansDays);
+        }
+        if to <= from {
+            return Err(FormError::EndsBeforeStart);
+        }
+        Ok(())
+    }
+}
+
+impl VtopClient {
+    pub async fn get_hostel_applications(
+        &self,
+        kind: HostelRequestKind,
+    ) -> VtopResult<HostelApplicationsData> {
+        self.report(VtopProgress::Fetching(FetchKind::Hostel));
+        let text = self.post_page(kind.list_path(), &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Hostel));
+        Ok(parsehostel::parse_hostel_applications(text, kind))
+    }
+
+    // Validates `request` first; nothing is sent when that fails. Returns
+    // VTOP's confirmation.
+    pub async fn submit_hostel_request(&self, request: &HostelRequest) -> VtopResult<String> {
+        request.validate().map_err(VtopError::InvalidForm)?;
+        self.post_form(
+            request.kind.save_path(),
+            &[
+                ("place", request.place.trim()),
+                ("purpose", request.purpose.trim()),
+                ("fromDate", request.from_date.trim()),
+                ("fromTime", request.from_time.trim()),
+                ("toDate", request.to_date.trim()),
+                ("toTime", request.to_time.trim()),
+                ("contactNumber", request.contact_number.trim()),
+            ],
+        )
+        .await
+    }
+
+    pub async fn cancel_hostel_application(
+        &self,
+        kind: HostelRequestKind,
+        application_id: &str,
+    ) -> VtopResult<String> {
+        self.post_form(kind.cancel_path(), &[("leaveId", application_id)])
+            .await
+    }
+}
+
+fn parse_date(date: &str) -> Result<(u32, u32, u32), FormError> {
+    vtop_date(date).ok_or_else(|| FormError::InvalidDate(date.to_string()))
+}
+
+// `18:30` -> minutes since midnight.
+fn parse_time(time: &str) -> Result<u32, FormError> {
+    let invalid = || FormError::InvalidTime(time.to_string());
+    let (hours, minutes) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
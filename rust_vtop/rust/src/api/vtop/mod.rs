@@ -1,5 +1,32 @@
+#[cfg(feature = "unverified-endpoints")]
+pub mod announcements;
+#[cfg(feature = "unverified-endpoints")]
+pub mod biometric;
+pub mod bundle;
+#[cfg(feature = "unverified-endpoints")]
+pub mod course_page;
+#[cfg(feature = "unverified-endpoints")]
+pub mod curriculum;
+pub mod debug_capture;
+#[cfg(feature = "unverified-endpoints")]
+pub mod digital_assignment;
+#[cfg(feature = "unverified-endpoints")]
+pub mod feedback;
+#[cfg(feature = "unverified-endpoints")]
+pub mod finance;
+pub mod gpa;
+#[cfg(feature = "unverified-endpoints")]
+pub mod grades;
+#[cfg(feature = "unverified-endpoints")]
+pub mod hostel;
 pub mod paraser;
+pub mod progress;
+#[cfg(feature = "unverified-endpoints")]
+pub mod proctor;
+pub mod redact;
+pub mod request_limiter;
 pub mod session_manager;
+pub mod tls_policy;
 pub mod types;
 pub mod vtop_client;
 pub mod vtop_config;
@@ -1,6 +1,28 @@
+pub mod page_util;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parseannouncements;
 pub mod parseattn;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsebiometric;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsecoursepage;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsecurriculum;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parseda;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsefeedback;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsefinance;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsegrades;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parsehostel;
 pub mod parsemarks;
+#[cfg(feature = "unverified-endpoints")]
+pub mod parseproctor;
 pub mod parsesched;
 pub mod parsett;
 pub mod wifi_portal;
 
+pub mod wifi_status;
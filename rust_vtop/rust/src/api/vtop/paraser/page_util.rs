@@ -0,0 +1,197 @@
+use scraper::{ElementRef, Html, Selector};
+
+use super::super::types::CourseMaterial;
+
+const MONTHS: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+
+// Text of a table cell with VTOP's layout whitespace removed.
+pub fn cell_text(cell: &ElementRef) -> String {
+    cell.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Arguments of the first `name(...)` call in an `onclick` or `javascript:`
+// link, e.g. `view('AP2024252', 'CSE3005')` -> ["AP2024252", "CSE3005"].
+pub fn js_call_args(script: &str, name: &str) -> Option<Vec<String>> {
+    let start = script.find(&format!("{}(", name))? + name.len() + 1;
+    let end = start + script[start..].find(')')?;
+    Some(
+        script[start..end]
+            .split(',')
+            .map(|arg| {
+                arg.trim()
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string()
+            })
+            .collect(),
+    )
+}
+
+pub fn script_of<'a>(element: &ElementRef<'a>) -> Option<&'a str> {
+    element
+        .value()
+        .attr("onclick")
+        .or_else(|| element.value().attr("href"))
+}
+
+// VTOP download links inside `element`.
+pub fn materials_in(element: &ElementRef) -> Vec<CourseMaterial> {
+    let links = Selector::parse("a, button").unwrap();
+    element
+        .select(&links)
+        .filter_map(|link| {
+            let path = material_path(script_of(&link)?)?;
+            let title = cell_text(&link);
+            Some(CourseMaterial {
+                title: if title.is_empty() {
+                    path.rsplit('/').next().unwrap_or_default().to_string()
+                } else {
+                    title
+                },
+                path,
+            })
+        })
+        .collect()
+}
+
+// Download links come as plain hrefs (`/vtop/downloadPdf/...`) or wrapped in
+// a script call (`javascript:vtopDownload('downloadPdf/...')`). Either way
+// the result is relative to `/vtop/`.
+pub fn material_path(script: &str) -> Option<String> {
+    let is_download = |part: &&str| part.to_ascii_lowercase().contains("download");
+    let target = if script.contains(['\'', '"']) {
+        // Quoted arguments sit at the odd positions.
+        script
+            .split(['\'', '"'])
+            .skip(1)
+            .step_by(2)
+            .find(is_download)?
+    } else {
+        Some(script).filter(is_download)?
+    };
+    if target.contains("://") {
+        return None;
+    }
+    let path = target.trim().trim_start_matches('/');
+    let path = path.strip_prefix("vtop/").unwrap_or(path);
+    Some(path.to_string()).filter(|p| !p.is_empty())
+}
+
+// The notice VTOP shows after a form submission, or the start of the page's
+// text when it has none.
+pub fn page_message(document: &Html) -> String {
+    let messages =
+        Selector::parse(".alert, .text-danger, .text-success, .text-info, #message").unwrap();
+    document
+        .select(&messages)
+        .map(|element| cell_text(&element))
+        .find(|text| !text.is_empty())
+        .unwrap_or_else(|| {
+            let text = cell_text(&document.root_element());
+            text.chars().take(200).collect()
+        })
+}
+
+// Whether a notice returned for a form reports success. VTOP styles its
+// notices with the Bootstrap alert classes; unstyled ones go by wording,
+// where "Unsuccessful" and "not successful" are failures.
+pub fn reports_success(document: &Html, message: &str) -> bool {
+    let styled = |selector: &str| {
+        let selector = Selector::parse(selector).unwrap();
+        document
+            .select(&selector)
+            .any(|element| !cell_text(&element).is_empty())
+    };
+    if styled(".text-danger, .alert-danger, .alert-warning") {
+        return false;
+    }
+    if styled(".text-success, .alert-success") {
+        return true;
+    }
+    let message = message.to_ascii_lowercase();
+    message.contains("success")
+        && !message.contains("unsuccess")
+        && !message.contains("not success")
+}
+
+// VTOP's `05-Mar-2025` -> (2025, 3, 5), which orders like the date.
+pub fn vtop_date(date: &str) -> Option<(u32, u32, u32)> {
+    let (day, month, year) = date_parts(date.trim(), '-')?;
+    let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))? as u32 + 1;
+    checked_date(year.parse().ok()?, month, day.parse().ok()?)
+}
+
+// Like `vtop_date`, but also reads what biometric devices write:
+// `2025-03-05` and `05/03/2025`, which is day first, so `05/03/2025` is
+// 5 March. A time after the date is ignored.
+pub fn any_date(date: &str) -> Option<(u32, u32, u32)> {
+    let date = date.split_whitespace().next()?;
+    if let Some(day) = vtop_date(date) {
+        return Some(day);
+    }
+    let (first, month, last) = date_parts(date, '-').or_else(|| date_parts(date, '/'))?;
+    let (year, day) = if first.len() == 4 {
+        (first, last)
+    } else {
+        (last, first)
+    };
+    checked_date(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
+
+fn date_parts(date: &str, separator: char) -> Option<(&str, &str, &str)> {
+    let mut parts = date.split(separator);
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(first), Some(middle), Some(last), None) => Some((first, middle, last)),
+        _ => None,
+    }
+}
+
+fn checked_date(year: u32, month: u32, day: u32) -> Option<(u32, u32, u32)> {
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    (year >= 2000 && (1..=days).contains(&day)).then_some((year, month, day))
+}
+
+// Every `<table>` as its header texts and the texts of its data rows.
+pub fn tables(document: &Html) -> Vec<(Vec<String>, Vec<Vec<String>>)> {
+    let tables = Selector::parse("table").unwrap();
+    let rows = Selector::parse("tr").unwrap();
+    let headers = Selector::parse("th").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    document
+        .select(&tables)
+        .map(|table| {
+            let mut header = vec![];
+            let mut data = vec![];
+            for row in table.select(&rows) {
+                let th: Vec<_> = row.select(&headers).map(|c| cell_text(&c)).collect();
+                if !th.is_empty() {
+                    header = th;
+                    continue;
+                }
+                let td: Vec<_> = row.select(&cells).map(|c| cell_text(&c)).collect();
+                if !td.is_empty() {
+                    data.push(td);
+                }
+            }
+            (header, data)
+        })
+        .collect()
+}
+
+// Index of the first header, lowercased, that `wanted` accepts.
+pub fn column(header: &[String], wanted: impl Fn(&str) -> bool) -> Option<usize> {
+    header.iter().position(|h| wanted(&h.to_ascii_lowercase()))
+}
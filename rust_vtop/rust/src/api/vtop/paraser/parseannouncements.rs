@@ -0,0 +1,169 @@
+use scraper::{ElementRef, Html, Selector};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{cell_text, column, js_call_args, materials_in, script_of};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+// First `05-Mar-2025` or `05/03/2025` style word in `text`.
+fn find_date(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric()))
+        .find(|word| {
+            let parts: Vec<_> = word.split(['-', '/']).collect();
+            parts.len() == 3
+                && parts[0].len() <= 2
+                && parts[0].chars().all(|c| c.is_ascii_digit())
+                && parts[2].len() == 4
+                && parts[2].chars().all(|c| c.is_ascii_digit())
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+// The element's own id, or the first argument of a non-download script
+// call such as `viewSpotlight('1201')`.
+fn vtop_id(element: &ElementRef) -> Option<String> {
+    let value = element.value();
+    if let Some(id) = value.attr("data-id").or_else(|| value.attr("id")) {
+        return Some(id.to_string());
+    }
+    let scripted = Selector::parse("[onclick], a[href^='javascript']").unwrap();
+    std::iter::once(*element)
+        .chain(element.select(&scripted))
+        .filter_map(|e| script_of(&e))
+        .filter(|script| !script.to_ascii_lowercase().contains("download"))
+        .find_map(|script| {
+            let call = script.split('(').next()?;
+            let name = call.rsplit([' ', ':', ';']).next()?;
+            js_call_args(script, name)?.into_iter().next()
+        })
+        .filter(|id| !id.is_empty())
+}
+
+fn fallback_id(category: &str, posted_date: &str, title: &str) -> String {
+    format!("{}|{}|{}", category, posted_date, title)
+}
+
+// Spotlight is a card per category (Academics, Examinations, ...) with a
+// list of notices. A notice's title is its bold text and its body the
+// paragraphs under it; download links become attachments.
+pub fn parse_spotlight(html: String) -> AnnouncementsData {
+    let document = Html::parse_document(&html);
+    let cards = Selector::parse(".card").unwrap();
+    let headers = Selector::parse(".card-header, .card-title, h4, h5").unwrap();
+    let items = Selector::parse("li").unwrap();
+    let titles = Selector::parse("b, strong, .title, h6").unwrap();
+    let paragraphs = Selector::parse("p, .body").unwrap();
+    let dates = Selector::parse("small, time, .date").unwrap();
+
+    let mut sections: Vec<_> = document.select(&cards).collect();
+    if sections.is_empty() {
+        sections.push(document.root_element());
+    }
+    let mut announcements = vec![];
+    for section in sections {
+        let category = section
+            .select(&headers)
+            .next()
+            .map(|header| cell_text(&header))
+            .unwrap_or_default();
+        for item in section.select(&items) {
+            let text = cell_text(&item);
+            if text.is_empty() {
+                continue;
+            }
+            let title = item
+                .select(&titles)
+                .map(|t| cell_text(&t))
+                .find(|t| !t.is_empty())
+                .unwrap_or_else(|| text.clone());
+            let body = item
+                .select(&paragraphs)
+                .map(|p| cell_text(&p))
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            // The body may mention other dates, so a marked-up date wins.
+            let posted_date = item
+                .select(&dates)
+                .map(|d| find_date(&cell_text(&d)))
+                .find(|d| !d.is_empty())
+                .unwrap_or_else(|| find_date(&text));
+            announcements.push(Announcement {
+                id: vtop_id(&item).unwrap_or_else(|| fallback_id(&category, &posted_date, &title)),
+                category: category.clone(),
+                title,
+                body,
+                attachments: materials_in(&item),
+                posted_date,
+            });
+        }
+    }
+    AnnouncementsData {
+        announcements,
+        update_time: now(),
+    }
+}
+
+// The messages page is a table; columns are found by header since faculty
+// and class-group messages use slightly different ones.
+pub fn parse_messages(html: String) -> AnnouncementsData {
+    let document = Html::parse_document(&html);
+    let tables = Selector::parse("table").unwrap();
+    let rows = Selector::parse("tr").unwrap();
+    let headers = Selector::parse("th").unwrap();
+    let cells = Selector::parse("td").unwrap();
+
+    let mut announcements = vec![];
+    for table in document.select(&tables) {
+        let mut header = vec![];
+        for row in table.select(&rows) {
+            let th: Vec<_> = row.select(&headers).map(|c| cell_text(&c)).collect();
+            if !th.is_empty() {
+                header = th;
+                continue;
+            }
+            let Some(title) = column(&header, |h| h.contains("subject") || h.contains("title"))
+            else {
+                continue;
+            };
+            let cells: Vec<_> = row.select(&cells).collect();
+            let text = |index: Option<usize>| {
+                index
+                    .and_then(|i| cells.get(i))
+                    .map(|cell| cell_text(cell))
+                    .unwrap_or_default()
+            };
+            let title = text(Some(title));
+            if title.is_empty() {
+                continue;
+            }
+            let category = text(column(&header, |h| {
+                h.contains("category") || h.contains("from") || h.contains("faculty")
+            }));
+            let body = text(column(&header, |h| {
+                h.contains("message") || h.contains("description") || h.contains("content")
+            }));
+            let posted_date = find_date(&text(column(&header, |h| h.contains("date"))));
+            announcements.push(Announcement {
+                id: vtop_id(&row).unwrap_or_else(|| fallback_id(&category, &posted_date, &title)),
+                category,
+                title,
+                body,
+                attachments: materials_in(&row),
+                posted_date,
+            });
+        }
+    }
+    AnnouncementsData {
+        announcements,
+        update_time: now(),
+    }
+}
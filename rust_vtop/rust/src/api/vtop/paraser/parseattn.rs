@@ -22,11 +22,11 @@ pub fn parse_attendance(html: String, sem: String) -> AttendanceData {
             let mut outer_count = 0;
 
             let mut next = || {
-                if !(cells.len() > max + 1) && outer_count == 8 {
+                if (cells.len() <= max + 1) && outer_count == 8 {
                     outer_count += 1;
                     return "-".to_string();
                 }
-                let v = cells[index_vec].clone();
+                let v = cells[index_vec];
                 outer_count += 1;
                 index_vec += 1;
                 v.text()
@@ -0,0 +1,54 @@
+use scraper::Html;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{column, tables};
+
+// One row per punch with the date, time, device location and, on newer
+// devices, the direction. Rows are grouped into days in the order they
+// first appear.
+pub fn parse_biometric_log(html: String) -> BiometricLogData {
+    let document = Html::parse_document(&html);
+    let mut days: Vec<BiometricDay> = vec![];
+    for (header, rows) in tables(&document) {
+        let (Some(date), Some(time)) = (
+            column(&header, |h| h.contains("date")),
+            column(&header, |h| h.contains("time")),
+        ) else {
+            continue;
+        };
+        let location = column(&header, |h| {
+            h.contains("location") || h.contains("device") || h.contains("gate")
+        });
+        let direction = column(&header, |h| {
+            h.contains("in/out") || h.contains("direction") || h.contains("type")
+        });
+        for row in rows {
+            let text =
+                |index: Option<usize>| index.and_then(|i| row.get(i)).cloned().unwrap_or_default();
+            let (date, time) = (text(Some(date)), text(Some(time)));
+            if date.is_empty() || time.is_empty() {
+                continue;
+            }
+            let punch = BiometricPunch {
+                time,
+                location: text(location),
+                direction: text(direction).to_ascii_uppercase(),
+            };
+            match days.iter_mut().find(|day| day.date == date) {
+                Some(day) => day.punches.push(punch),
+                None => days.push(BiometricDay {
+                    date,
+                    punches: vec![punch],
+                }),
+            }
+        }
+    }
+    BiometricLogData {
+        days,
+        update_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::new(1, 0))
+            .as_secs(),
+    }
+}
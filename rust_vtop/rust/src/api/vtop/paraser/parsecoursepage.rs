@@ -0,0 +1,101 @@
+use scraper::{Html, Selector};
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{cell_text, js_call_args, materials_in, script_of};
+
+const VIEW_DETAIL: &str = "processViewStudentCourseDetail";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+// Registered classes, one row each, ending in a "View" button that calls
+// `processViewStudentCourseDetail(semSubId, classId, erpId)`.
+pub fn parse_course_page_courses(html: String, sem: String) -> CoursePageCourses {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick], a[href]").unwrap();
+
+    let mut courses = vec![];
+    for row in document.select(&rows) {
+        let cells: Vec<_> = row.select(&cells).collect();
+        if cells.len() < 7 {
+            continue;
+        }
+        let Some(args) = row
+            .select(&buttons)
+            .filter_map(|button| script_of(&button))
+            .find_map(|script| js_call_args(script, VIEW_DETAIL))
+        else {
+            continue;
+        };
+        courses.push(CoursePageCourse {
+            class_id: args.get(1).cloned().unwrap_or_else(|| cell_text(&cells[4])),
+            faculty_id: args.get(2).cloned().unwrap_or_default(),
+            course_code: cell_text(&cells[1]),
+            course_title: cell_text(&cells[2]),
+            course_type: cell_text(&cells[3]),
+            slot: cell_text(&cells[5]),
+            faculty: cell_text(&cells[6]),
+        });
+    }
+    CoursePageCourses {
+        courses,
+        semester_id: sem,
+        update_time: now(),
+    }
+}
+
+// The lecture plan lists serial, date, day and topic followed by that
+// lecture's material links. Download links elsewhere on the page are
+// course-wide material such as the syllabus or reference books.
+pub fn parse_course_page(
+    html: String,
+    sem: String,
+    class_id: String,
+    faculty_id: String,
+) -> CoursePageData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+
+    let mut seen = HashSet::new();
+    let mut lectures = vec![];
+    for row in document.select(&rows) {
+        let cells: Vec<_> = row.select(&cells).collect();
+        if cells.len() < 4 || cell_text(&cells[0]).parse::<u32>().is_err() {
+            continue;
+        }
+        let materials: Vec<_> = cells[4..]
+            .iter()
+            .flat_map(|cell| materials_in(cell))
+            .filter(|material| seen.insert(material.path.clone()))
+            .collect();
+        lectures.push(CoursePageLecture {
+            serial: cell_text(&cells[0]),
+            date: cell_text(&cells[1]),
+            day: cell_text(&cells[2]),
+            topic: cell_text(&cells[3]),
+            materials,
+        });
+    }
+    let materials = materials_in(&document.root_element())
+        .into_iter()
+        .filter(|material| seen.insert(material.path.clone()))
+        .collect();
+
+    CoursePageData {
+        materials,
+        lectures,
+        semester_id: sem,
+        class_id,
+        faculty_id,
+        update_time: now(),
+    }
+}
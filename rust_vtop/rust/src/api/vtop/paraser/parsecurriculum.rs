@@ -0,0 +1,94 @@
+use scraper::Html;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{column, tables};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+fn number(text: Option<&String>) -> Option<f32> {
+    text.and_then(|t| t.trim().parse().ok())
+}
+
+fn text(row: &[String], index: Option<usize>) -> String {
+    index.and_then(|i| row.get(i)).cloned().unwrap_or_default()
+}
+
+// The curriculum page has a category summary (category, description,
+// credits required and earned) and the list of the programme's courses.
+pub fn parse_curriculum(html: String) -> CurriculumData {
+    let document = Html::parse_document(&html);
+    let mut categories = vec![];
+    let mut courses = vec![];
+    for (header, rows) in tables(&document) {
+        let required = column(&header, |h| h.contains("required"));
+        let code = column(&header, |h| h.contains("code"));
+        let category = column(&header, |h| h.contains("category"));
+        if let (Some(required), Some(category)) = (required, category) {
+            let name = column(&header, |h| h.contains("description") || h.contains("name"));
+            let earned = column(&header, |h| h.contains("earned"));
+            categories.extend(rows.iter().filter_map(|row| {
+                Some(CurriculumCategory {
+                    code: text(row, Some(category)),
+                    name: text(row, name),
+                    credits_required: number(row.get(required))?,
+                    credits_earned: earned.and_then(|i| number(row.get(i))),
+                })
+            }));
+        } else if let Some(code) = code {
+            let title = column(&header, |h| h.contains("title"));
+            let course_type = column(&header, |h| h.contains("type"));
+            let credits = column(&header, |h| h.starts_with("credit") || h == "c");
+            courses.extend(rows.iter().filter_map(|row| {
+                let course_code = text(row, Some(code));
+                (!course_code.is_empty()).then(|| CurriculumCourse {
+                    category: text(row, category),
+                    course_code,
+                    course_title: text(row, title),
+                    course_type: text(row, course_type),
+                    credits: credits.and_then(|i| number(row.get(i))).unwrap_or_default(),
+                })
+            }));
+        }
+    }
+    CurriculumData {
+        categories,
+        courses,
+        update_time: now(),
+    }
+}
+
+pub fn parse_allocation(html: String) -> AllocationData {
+    let document = Html::parse_document(&html);
+    let mut allocations = vec![];
+    for (header, rows) in tables(&document) {
+        let Some(code) = column(&header, |h| h.contains("code")) else {
+            continue;
+        };
+        let title = column(&header, |h| h.contains("title"));
+        let course_type = column(&header, |h| h.contains("type"));
+        let category = column(&header, |h| h.contains("category"));
+        let credits = column(&header, |h| h.starts_with("credit") || h == "c");
+        let status = column(&header, |h| h.contains("status"));
+        allocations.extend(rows.iter().filter_map(|row| {
+            let course_code = text(row, Some(code));
+            (!course_code.is_empty()).then(|| CourseAllocation {
+                course_code,
+                course_title: text(row, title),
+                course_type: text(row, course_type),
+                category: text(row, category),
+                credits: credits.and_then(|i| number(row.get(i))).unwrap_or_default(),
+                status: text(row, status),
+            })
+        }));
+    }
+    AllocationData {
+        allocations,
+        update_time: now(),
+    }
+}
@@ -0,0 +1,112 @@
+use scraper::{Html, Selector};
+
+use super::super::types::*;
+use super::page_util::{cell_text, js_call_args, materials_in, page_message, script_of};
+
+const UPLOAD: &str = "doDAUpload";
+
+// Courses with digital assignments. Each row's button carries the class id
+// as the first argument of its `onclick` call. Assignments are filled in by
+// `parse_assignments`.
+pub fn parse_da_courses(html: String) -> Vec<DigitalAssignmentCourse> {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick]").unwrap();
+
+    document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 6 {
+                return None;
+            }
+            let onclick = row.select(&buttons).find_map(|b| script_of(&b))?;
+            let class_id = onclick
+                .split(['\'', '"'])
+                .nth(1)
+                .map(str::to_string)
+                .unwrap_or_else(|| cell_text(&cells[1]));
+            Some(DigitalAssignmentCourse {
+                class_id,
+                course_code: cell_text(&cells[2]),
+                course_title: cell_text(&cells[3]),
+                course_type: cell_text(&cells[4]),
+                faculty: cell_text(&cells[5]),
+                assignments: vec![],
+            })
+        })
+        .collect()
+}
+
+// Columns: serial, title, max marks, weightage, due date, question paper,
+// last updated and, while uploads are open, an upload button.
+pub fn parse_assignments(html: String) -> Vec<DigitalAssignment> {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick], a[href]").unwrap();
+
+    document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 7 || cell_text(&cells[0]).parse::<u32>().is_err() {
+                return None;
+            }
+            let upload = row
+                .select(&buttons)
+                .filter_map(|button| script_of(&button))
+                .find_map(|script| js_call_args(script, UPLOAD));
+            let question_paper = materials_in(&cells[5]).into_iter().next();
+            let code = upload
+                .as_ref()
+                .and_then(|args| args.first().cloned())
+                .or_else(|| question_paper.as_ref().and_then(|q| code_in_path(&q.path)))
+                .unwrap_or_default();
+            let last_updated = cell_text(&cells[6]);
+            Some(DigitalAssignment {
+                serial: cell_text(&cells[0]),
+                code,
+                title: cell_text(&cells[1]),
+                max_marks: cell_text(&cells[2]),
+                weightage: cell_text(&cells[3]),
+                due_date: cell_text(&cells[4]),
+                question_paper,
+                uploaded: !last_updated.is_empty() && last_updated != "-",
+                last_updated,
+                can_upload: upload.is_some(),
+            })
+        })
+        .collect()
+}
+
+// `examinations/doDownloadQuestion/Am01/AP2024252000123` -> `Am01`.
+fn code_in_path(path: &str) -> Option<String> {
+    let mut segments = path.split('/').rev();
+    segments.next()?;
+    segments.next().map(str::to_string)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadReply {
+    Done(String),
+    // VTOP mailed an OTP and shows a form for it.
+    NeedsOtp(String),
+    Rejected(String),
+}
+
+pub fn parse_upload_reply(html: &str) -> UploadReply {
+    let document = Html::parse_document(html);
+    let otp_input = Selector::parse("input[name=otp], input[name=otpEmail]").unwrap();
+
+    let message = page_message(&document);
+    let lower = message.to_ascii_lowercase();
+    if document.select(&otp_input).next().is_some() {
+        UploadReply::NeedsOtp(message)
+    } else if lower.contains("success") {
+        UploadReply::Done(message)
+    } else {
+        UploadReply::Rejected(message)
+    }
+}
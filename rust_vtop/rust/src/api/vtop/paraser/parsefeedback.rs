@@ -0,0 +1,157 @@
+use scraper::{ElementRef, Html, Selector};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{cell_text, script_of};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+// Columns: serial, course code, title, type, faculty and status. Pending
+// forms have a button whose `onclick` carries the form id and class id.
+pub fn parse_feedback_forms(html: String, semester_id: String) -> FeedbackFormsData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick], a[href^='javascript']").unwrap();
+
+    let forms = document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 6 || cell_text(&cells[0]).parse::<u32>().is_err() {
+                return None;
+            }
+            let args: Vec<String> = row
+                .select(&buttons)
+                .filter_map(|button| script_of(&button))
+                .find(|script| script.contains(['\'', '"']))
+                .map(|script| {
+                    // Quoted arguments sit at the odd positions.
+                    script
+                        .split(['\'', '"'])
+                        .skip(1)
+                        .step_by(2)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let status = cell_text(&cells[5]).to_ascii_lowercase();
+            Some(FeedbackForm {
+                form_id: args.first().cloned().unwrap_or_default(),
+                class_id: args.get(1).cloned().unwrap_or_default(),
+                course_code: cell_text(&cells[1]),
+                course_title: cell_text(&cells[2]),
+                course_type: cell_text(&cells[3]),
+                faculty: cell_text(&cells[4]),
+                submitted: (status.contains("submitted") || status.contains("completed"))
+                    && !status.contains("not"),
+            })
+        })
+        .collect();
+    FeedbackFormsData {
+        forms,
+        semester_id,
+        update_time: now(),
+    }
+}
+
+fn question_kind(inputs: &[ElementRef]) -> FeedbackQuestionKind {
+    let options = Selector::parse("option").unwrap();
+    let first = inputs[0].value();
+    if first.name() == "select" {
+        let options = inputs[0]
+            .select(&options)
+            .filter_map(|option| option.value().attr("value"))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .collect();
+        return FeedbackQuestionKind::Choice { options };
+    }
+    if first
+        .attr("type")
+        .unwrap_or("text")
+        .eq_ignore_ascii_case("radio")
+    {
+        let values: Vec<_> = inputs
+            .iter()
+            .filter_map(|input| input.value().attr("value"))
+            .map(str::trim)
+            .collect();
+        let scale: Option<Vec<u32>> = values.iter().map(|v| v.parse().ok()).collect();
+        return match scale {
+            Some(scale) if !scale.is_empty() => FeedbackQuestionKind::Rating {
+                min: *scale.iter().min().unwrap(),
+                max: *scale.iter().max().unwrap(),
+            },
+            _ => FeedbackQuestionKind::Choice {
+                options: values.into_iter().map(str::to_string).collect(),
+            },
+        };
+    }
+    FeedbackQuestionKind::Text
+}
+
+// Each question is a table row: its text in the first cell and radio
+// buttons, a select or a text box named after the question. A row may
+// hold several questions sharing its text.
+pub fn parse_feedback_questions(
+    html: String,
+    semester_id: String,
+    form_id: String,
+) -> FeedbackQuestionsData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td, th").unwrap();
+    let fields =
+        Selector::parse("input[name]:not([type=hidden]), select[name], textarea[name]").unwrap();
+
+    let mut questions: Vec<FeedbackQuestion> = vec![];
+    for row in document.select(&rows) {
+        let inputs: Vec<_> = row.select(&fields).collect();
+        if inputs.is_empty() {
+            continue;
+        }
+        let text = row
+            .select(&cells)
+            .map(|cell| cell_text(&cell))
+            .find(|text| !text.is_empty())
+            .unwrap_or_default();
+        let mut names: Vec<&str> = vec![];
+        for input in &inputs {
+            let name = input.value().attr("name").unwrap_or_default();
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        for name in names {
+            if questions.iter().any(|q| q.id == name) {
+                continue;
+            }
+            let group: Vec<_> = inputs
+                .iter()
+                .filter(|input| input.value().attr("name") == Some(name))
+                .copied()
+                .collect();
+            questions.push(FeedbackQuestion {
+                id: name.to_string(),
+                text: text.clone(),
+                kind: question_kind(&group),
+                required: group
+                    .iter()
+                    .any(|input| input.value().attr("required").is_some()),
+            });
+        }
+    }
+    FeedbackQuestionsData {
+        form_id,
+        semester_id,
+        questions,
+        update_time: now(),
+    }
+}
@@ -0,0 +1,94 @@
+use scraper::{Html, Selector};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{cell_text, js_call_args, script_of};
+
+const DOWNLOAD_RECEIPT: &str = "doDownloadReceipt";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+// Columns: serial, fee head, academic year or semester, amount, due date,
+// fine and status, closed by a "Total" row.
+pub fn parse_fee_dues(html: String) -> FeeDuesData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+
+    let mut dues = vec![];
+    let mut total_due = String::new();
+    for row in document.select(&rows) {
+        let cells: Vec<_> = row.select(&cells).collect();
+        let Some(first) = cells.first().map(cell_text) else {
+            continue;
+        };
+        if first.to_ascii_lowercase().starts_with("total") {
+            total_due = cells.last().map(cell_text).unwrap_or_default();
+            continue;
+        }
+        if cells.len() < 7 || first.parse::<u32>().is_err() {
+            continue;
+        }
+        dues.push(FeeDue {
+            serial: first,
+            fee_head: cell_text(&cells[1]),
+            term: cell_text(&cells[2]),
+            amount: cell_text(&cells[3]),
+            due_date: cell_text(&cells[4]),
+            fine: cell_text(&cells[5]),
+            status: cell_text(&cells[6]),
+        });
+    }
+    FeeDuesData {
+        dues,
+        total_due,
+        update_time: now(),
+    }
+}
+
+// Columns: serial, receipt number, date, description, amount, payment mode,
+// status and a receipt button when VTOP has one.
+pub fn parse_payment_history(html: String) -> PaymentHistoryData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick], a[href]").unwrap();
+
+    let payments = document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 7 || cell_text(&cells[0]).parse::<u32>().is_err() {
+                return None;
+            }
+            let receipt = row
+                .select(&buttons)
+                .filter_map(|button| script_of(&button))
+                .find_map(|script| js_call_args(script, DOWNLOAD_RECEIPT));
+            let listed = cell_text(&cells[1]);
+            Some(FeePayment {
+                serial: cell_text(&cells[0]),
+                receipt_no: receipt
+                    .as_ref()
+                    .and_then(|args| args.first().cloned())
+                    .filter(|no| !no.is_empty())
+                    .unwrap_or(listed),
+                date: cell_text(&cells[2]),
+                description: cell_text(&cells[3]),
+                amount: cell_text(&cells[4]),
+                mode: cell_text(&cells[5]),
+                status: cell_text(&cells[6]),
+                has_receipt: receipt.is_some(),
+            })
+        })
+        .collect();
+    PaymentHistoryData {
+        payments,
+        update_time: now(),
+    }
+}
@@ -0,0 +1,132 @@
+use scraper::{ElementRef, Html, Selector};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::cell_text;
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::new(1, 0))
+        .as_secs()
+}
+
+// Both grade pages change their column set between semesters (L/T/P/J
+// credit splits, grand total, grading type), so columns are found by their
+// header instead of position.
+struct Columns {
+    code: usize,
+    title: Option<usize>,
+    course_type: Option<usize>,
+    credits: Option<usize>,
+    grade: usize,
+    grade_points: Option<usize>,
+    result: Option<usize>,
+    exam_month: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Option<Self> {
+        let find = |wanted: &dyn Fn(&str) -> bool| {
+            header.iter().position(|h| wanted(&h.to_ascii_lowercase()))
+        };
+        Some(Self {
+            code: find(&|h| h.contains("code"))?,
+            title: find(&|h| h.contains("title") || h.contains("course name")),
+            course_type: find(&|h| h.contains("type") && !h.contains("grading")),
+            // `C` or `Credits`, not the L/T/P/J splits.
+            credits: find(&|h| h.starts_with("credit") || h == "c"),
+            grade: find(&|h| h.contains("grade") && !h.contains("point") && !h.contains("type"))?,
+            grade_points: find(&|h| h.contains("point")),
+            result: find(&|h| h.contains("result") || h.contains("status")),
+            exam_month: find(&|h| h.contains("month") || h.contains("exam held")),
+        })
+    }
+
+    fn read(&self, cells: &[String]) -> Option<CourseGrade> {
+        let get = |index: Option<usize>| {
+            index
+                .and_then(|i| cells.get(i))
+                .cloned()
+                .unwrap_or_default()
+        };
+        let code = get(Some(self.code));
+        let grade = get(Some(self.grade));
+        if code.is_empty() || grade.is_empty() {
+            return None;
+        }
+        Some(CourseGrade {
+            course_code: code,
+            course_title: get(self.title),
+            course_type: get(self.course_type),
+            credits: number(&get(self.credits)).unwrap_or_default(),
+            grade,
+            grade_points: number(&get(self.grade_points)),
+            result_status: get(self.result),
+            exam_month: get(self.exam_month),
+        })
+    }
+}
+
+fn number(text: &str) -> Option<f32> {
+    text.trim().parse().ok()
+}
+
+fn grade_rows(document: &Html) -> Vec<CourseGrade> {
+    let rows = Selector::parse("tr").unwrap();
+    let header_cells = Selector::parse("th, td").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let texts = |row: ElementRef, selector: &Selector| -> Vec<String> {
+        row.select(selector).map(|cell| cell_text(&cell)).collect()
+    };
+
+    let mut columns = None;
+    let mut grades = vec![];
+    for row in document.select(&rows) {
+        if row.select(&Selector::parse("th").unwrap()).next().is_some() {
+            columns = Columns::from_header(&texts(row, &header_cells)).or(columns);
+            continue;
+        }
+        let Some(columns) = &columns else {
+            continue;
+        };
+        let cells = texts(row, &cells);
+        if cells
+            .first()
+            .is_some_and(|serial| serial.parse::<u32>().is_ok())
+        {
+            grades.extend(columns.read(&cells));
+        }
+    }
+    grades
+}
+
+pub fn parse_semester_grades(html: String, sem: String) -> SemesterGradesData {
+    let document = Html::parse_document(&html);
+    let grades = grade_rows(&document);
+    // Printed as "GPA : 8.52" below the table, sometimes next to the CGPA.
+    let text = cell_text(&document.root_element());
+    let published_gpa = text
+        .match_indices("GPA")
+        .find(|(start, _)| !text[..*start].ends_with('C'))
+        .map(|(start, _)| &text[start + 3..])
+        .and_then(|rest| {
+            rest.trim_start_matches([' ', ':'])
+                .split_whitespace()
+                .next()
+                .and_then(number)
+        });
+    SemesterGradesData {
+        grades,
+        published_gpa,
+        semester_id: sem,
+        update_time: now(),
+    }
+}
+
+pub fn parse_arrears(html: String) -> ArrearsData {
+    ArrearsData {
+        records: grade_rows(&Html::parse_document(&html)),
+        update_time: now(),
+    }
+}
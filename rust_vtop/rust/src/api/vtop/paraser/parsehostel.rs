@@ -0,0 +1,49 @@
+use scraper::{Html, Selector};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::super::types::*;
+use super::page_util::{cell_text, script_of};
+
+// Columns: serial, application id, place, purpose, from date and time, to
+// date and time, approver and status, plus a cancel button while pending.
+pub fn parse_hostel_applications(html: String, kind: HostelRequestKind) -> HostelApplicationsData {
+    let document = Html::parse_document(&html);
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td").unwrap();
+    let buttons = Selector::parse("[onclick]").unwrap();
+
+    let applications = document
+        .select(&rows)
+        .filter_map(|row| {
+            let cells: Vec<_> = row.select(&cells).collect();
+            if cells.len() < 10 || cell_text(&cells[0]).parse::<u32>().is_err() {
+                return None;
+            }
+            let can_cancel = row
+                .select(&buttons)
+                .filter_map(|button| script_of(&button))
+                .any(|script| script.to_ascii_lowercase().contains("delete"));
+            Some(HostelApplication {
+                kind,
+                id: cell_text(&cells[1]),
+                place: cell_text(&cells[2]),
+                purpose: cell_text(&cells[3]),
+                from_date: cell_text(&cells[4]),
+                from_time: cell_text(&cells[5]),
+                to_date: cell_text(&cells[6]),
+                to_time: cell_text(&cells[7]),
+                approver: cell_text(&cells[8]),
+                status: cell_text(&cells[9]),
+                can_cancel,
+            })
+        })
+        .collect();
+    HostelApplicationsData {
+        applications,
+        kind,
+        update_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::new(1, 0))
+            .as_secs(),
+    }
+}
@@ -20,8 +20,7 @@ pub fn parse_marks(html: String, sem: String) -> MarksData {
         el.map(|e| e.text().collect::<Vec<_>>().join(""))
             .unwrap_or_default()
             .trim()
-            .replace('\t', "")
-            .replace('\n', "")
+            .replace(['\t', '\n'], "")
     }
 
     let mut bmarks = false;
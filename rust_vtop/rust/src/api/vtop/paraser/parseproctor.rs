@@ -0,0 +1,126 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use scraper::{ElementRef, Html, Selector};
+
+use super::super::types::FacultyInfo;
+use super::page_util::cell_text;
+
+pub struct ParsedFaculty {
+    pub info: FacultyInfo,
+    // Where to fetch the photo from when it is not inlined in the page.
+    pub photo_path: Option<String>,
+}
+
+pub struct ParsedProctorPage {
+    pub proctor: Option<ParsedFaculty>,
+    pub class_advisor: Option<ParsedFaculty>,
+}
+
+// Text of the caption or the element just before `table`, which is where
+// VTOP names the section ("Proctor Details", "Class Advisor").
+fn heading(table: &ElementRef) -> String {
+    let caption = Selector::parse("caption").unwrap();
+    if let Some(caption) = table.select(&caption).next() {
+        return cell_text(&caption);
+    }
+    table
+        .prev_siblings()
+        .find_map(ElementRef::wrap)
+        .map(|element| cell_text(&element))
+        .unwrap_or_default()
+}
+
+fn photo(table: &ElementRef) -> (Option<Vec<u8>>, Option<String>) {
+    let images = Selector::parse("img[src]").unwrap();
+    let Some(src) = table
+        .select(&images)
+        .filter_map(|img| img.value().attr("src"))
+        .map(str::trim)
+        .find(|src| !src.is_empty())
+    else {
+        return (None, None);
+    };
+    if let Some(data) = src.strip_prefix("data:") {
+        let encoded = data.split_once(";base64,").map(|(_, encoded)| encoded);
+        let bytes = encoded.and_then(|e| STANDARD.decode(e.trim()).ok());
+        return (bytes.filter(|b| !b.is_empty()), None);
+    }
+    if src.contains("://") {
+        return (None, None);
+    }
+    let path = src.trim_start_matches('/');
+    let path = path.strip_prefix("vtop/").unwrap_or(path);
+    (None, Some(path.to_string()))
+}
+
+// A label/value table. Labels vary between "Faculty Name" and "Proctor
+// Name" and so on, so only the distinguishing word is matched.
+fn parse_faculty(table: &ElementRef) -> Option<(bool, ParsedFaculty)> {
+    let rows = Selector::parse("tr").unwrap();
+    let cells = Selector::parse("td, th").unwrap();
+    let mut info = FacultyInfo {
+        name: String::new(),
+        emp_id: String::new(),
+        designation: String::new(),
+        school: String::new(),
+        cabin: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        photo: None,
+    };
+    let mut advisor = heading(table).to_ascii_lowercase().contains("advis");
+    for row in table.select(&rows) {
+        let texts: Vec<_> = row.select(&cells).map(|c| cell_text(&c)).collect();
+        let [label, value, ..] = texts.as_slice() else {
+            continue;
+        };
+        let label = label.to_ascii_lowercase();
+        advisor |= label.contains("advis");
+        let field = if label.contains("school") {
+            &mut info.school
+        } else if label.contains("designation") {
+            &mut info.designation
+        } else if label.contains("cabin") || label.contains("room") {
+            &mut info.cabin
+        } else if label.contains("mail") {
+            &mut info.email
+        } else if label.contains("mobile") || label.contains("phone") {
+            &mut info.phone
+        } else if label.contains("id") || label.contains("emp") {
+            &mut info.emp_id
+        } else if label.contains("name") {
+            &mut info.name
+        } else {
+            continue;
+        };
+        if field.is_empty() {
+            *field = value.clone();
+        }
+    }
+    if info.name.is_empty() {
+        return None;
+    }
+    let (bytes, photo_path) = photo(table);
+    info.photo = bytes;
+    Some((advisor, ParsedFaculty { info, photo_path }))
+}
+
+pub fn parse_proctor_page(html: String) -> ParsedProctorPage {
+    let document = Html::parse_document(&html);
+    let tables = Selector::parse("table").unwrap();
+    let mut page = ParsedProctorPage {
+        proctor: None,
+        class_advisor: None,
+    };
+    for table in document.select(&tables) {
+        let Some((advisor, faculty)) = parse_faculty(&table) else {
+            continue;
+        };
+        let slot = if advisor {
+            &mut page.class_advisor
+        } else {
+            &mut page.proctor
+        };
+        slot.get_or_insert(faculty);
+    }
+    page
+}
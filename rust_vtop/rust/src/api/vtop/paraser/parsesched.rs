@@ -122,7 +122,7 @@ pub fn parse_schedule(html: String, sem: String) -> ExamScheduleData {
         }
     }
     ExamScheduleData {
-        exams: exams,
+        exams,
         semester_id: sem,
         update_time: SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -60,13 +60,9 @@ pub fn parse_timetable(html: String, sem: &str) -> TimetableData {
                         .replace("\t", "")
                         .replace("\n", "");
                     if islab {
-                        if !facultyname_lab_code.contains_key(&code) {
-                            facultyname_lab_code.insert(code, faculty_name);
-                        }
+                        facultyname_lab_code.entry(code).or_insert(faculty_name);
                     } else {
-                        if !facultyname_code.contains_key(&code) {
-                            facultyname_code.insert(code, faculty_name);
-                        }
+                        facultyname_code.entry(code).or_insert(faculty_name);
                     }
                 }
             }
@@ -173,7 +169,7 @@ pub fn parse_timetable(html: String, sem: &str) -> TimetableData {
                                         .get(&code)
                                         .unwrap_or(&"".to_string())
                                         .to_string(),
-                                    is_lab: is_lab,
+                                    is_lab,
                                     faculty: if is_lab {
                                         facultyname_lab_code
                                             .get(&code)
@@ -1,39 +1,114 @@
-use std::time::Duration;
-
+use reqwest::Url;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
 
-pub async fn find_captivative_portal() -> String {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(2))
-        .danger_accept_invalid_certs(true)
-        .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
-
-    let res = client
-        .get("http://connectivitycheck.gstatic.com/generate_204")
-        .send()
-        .await;
-    match res {
-        Ok(k) => {
-            if k.status().as_u16() == 204 {
-                return "IE".into();
-            }
-
-            let text = k.text().await.unwrap_or("".into());
-            let document = Html::parse_document(&text);
-            let selector: Selector = Selector::parse("form[id=portal_url]").unwrap();
-
-            for element in document.select(&selector) {
-                if let Some(action) = element.value().attr("action") {
-                    return action.to_string();
-                }
-            }
-        }
-        Err(_) => {
-            return "NE".into();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PortalVendor {
+    Fortinet,
+    Sophos,
+    Unknown,
+}
+
+const FORTINET_FINGERPRINTS: [&str; 5] = [
+    "fgtauth",
+    "fortigate",
+    "fortinet",
+    "name=\"magic\"",
+    "keepalive?",
+];
+const SOPHOS_FINGERPRINTS: [&str; 5] = [
+    "sophos",
+    "cyberoam",
+    "httpclient.html",
+    "login.xml",
+    "producttype",
+];
+
+// Guesses the portal vendor from a URL on the portal and the page it served.
+pub fn fingerprint(url: &Url, page: &str) -> PortalVendor {
+    let url = url.as_str().to_lowercase();
+    let page = page.to_lowercase();
+    let matches = |fingerprints: &[&str]| {
+        fingerprints
+            .iter()
+            .any(|f| url.contains(f) || page.contains(f))
+    };
+    if matches(&FORTINET_FINGERPRINTS) {
+        PortalVendor::Fortinet
+    } else if matches(&SOPHOS_FINGERPRINTS) || url.contains(":8090") {
+        PortalVendor::Sophos
+    } else {
+        PortalVendor::Unknown
+    }
+}
+
+// Where a portal page sends the browser next: a meta refresh, a script
+// assignment to `location`, or the FortiGate `portal_url` form.
+pub fn next_hop(page: &str, base: &Url) -> Option<Url> {
+    let document = Html::parse_document(page);
+
+    let meta = Selector::parse("meta[http-equiv]").unwrap();
+    let refresh = document
+        .select(&meta)
+        .filter(|element| {
+            element
+                .value()
+                .attr("http-equiv")
+                .map(|v| v.eq_ignore_ascii_case("refresh"))
+                .unwrap_or(false)
+        })
+        .find_map(|element| element.value().attr("content"))
+        .and_then(|content| {
+            let lower = content.to_lowercase();
+            let start = lower.find("url=")? + "url=".len();
+            Some(
+                content[start..]
+                    .trim()
+                    .trim_matches(|c| c == '\'' || c == '"')
+                    .to_string(),
+            )
+        });
+    if let Some(target) = refresh {
+        return base.join(&target).ok();
+    }
+
+    let script = Selector::parse("script").unwrap();
+    for element in document.select(&script) {
+        let code = element.text().collect::<String>();
+        if let Some(target) = script_location(&code) {
+            return base.join(&target).ok();
         }
     }
 
-    "".into()
+    let form = Selector::parse("form[id=portal_url]").unwrap();
+    document
+        .select(&form)
+        .find_map(|element| element.value().attr("action"))
+        .and_then(|action| base.join(action).ok())
 }
 
+// Finds `location = "..."`, `location.href = "..."` or
+// `location.replace("...")` in a script.
+fn script_location(code: &str) -> Option<String> {
+    let mut rest = code;
+    while let Some(index) = rest.find("location") {
+        rest = &rest[index + "location".len()..];
+        let after = rest
+            .trim_start_matches(".href")
+            .trim_start_matches(".replace")
+            .trim_start_matches(".assign")
+            .trim_start();
+        let Some(after) = after.strip_prefix('=').or_else(|| after.strip_prefix('(')) else {
+            continue;
+        };
+        let after = after.trim_start();
+        let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            continue;
+        };
+        let value = &after[1..];
+        if let Some(end) = value.find(quote) {
+            return Some(value[..end].to_string());
+        }
+    }
+    None
+}
@@ -0,0 +1,123 @@
+use super::super::wifi::{ActiveSession, WifiSessionStatus};
+
+// Names each field is looked up under, in order; the first one present
+// wins and a field with none of them is left empty.
+const USERNAME_TAGS: [&str; 2] = ["username", "user"];
+const QUOTA_TAGS: [&str; 4] = ["remainingquota", "remaining_quota", "quota", "datatransfer"];
+const SESSION_TIME_TAGS: [&str; 4] = ["sessiontime", "session_time", "livetime", "usedtime"];
+const DEVICE_COUNT_TAGS: [&str; 4] = [
+    "loggedinusers",
+    "concurrentlogins",
+    "activesessions",
+    "devices",
+];
+const SESSION_BLOCK_TAGS: [&str; 2] = ["session", "activesession"];
+
+// Parses a `login.xml` or `live` reply. `logged_in` reflects the reply
+// itself: an `<ack>ack</ack>` keepalive answer or a `LIVE` status.
+pub fn parse_session_status(xml: &str) -> WifiSessionStatus {
+    let message = xml_text(xml, "message").filter(|m| !m.is_empty());
+    let status = xml_text(xml, "status").unwrap_or_default();
+    let ack = xml_text(xml, "ack").unwrap_or_default();
+    let lower = message.as_deref().unwrap_or_default().to_ascii_lowercase();
+
+    let sessions: Vec<ActiveSession> = SESSION_BLOCK_TAGS
+        .iter()
+        .flat_map(|tag| xml_blocks(xml, tag))
+        .map(|block| ActiveSession {
+            ip: first_of(&block, &["ip", "ipaddress", "ip_address"]),
+            mac: first_of(&block, &["mac", "macaddress", "mac_address"]),
+            login_time: first_of(&block, &["logintime", "starttime", "login_time"]),
+        })
+        .collect();
+    let remaining_quota = first_of(xml, &QUOTA_TAGS);
+
+    WifiSessionStatus {
+        logged_in: ack.eq_ignore_ascii_case("ack")
+            || status.eq_ignore_ascii_case("live")
+            || lower.contains("you are signed in"),
+        username: first_of(xml, &USERNAME_TAGS).or_else(|| signed_in_as(&lower, &message)),
+        remaining_quota_bytes: remaining_quota.as_deref().and_then(parse_bytes),
+        remaining_quota,
+        session_seconds: first_of(xml, &SESSION_TIME_TAGS)
+            .as_deref()
+            .and_then(parse_seconds),
+        active_devices: first_of(xml, &DEVICE_COUNT_TAGS)
+            .and_then(|count| count.parse().ok())
+            .or(Some(sessions.len() as u32).filter(|count| *count > 0)),
+        limit_reached: lower.contains("limit reached")
+            || (lower.contains("limit") && lower.contains("maximum")),
+        active_sessions: sessions,
+        message,
+    }
+}
+
+// "You are signed in as 21BCE0001" -> "21BCE0001".
+fn signed_in_as(lower: &str, message: &Option<String>) -> Option<String> {
+    let start = lower.find("signed in as")? + "signed in as".len();
+    let name = message.as_deref()?[start..].trim();
+    Some(name.to_string()).filter(|n| !n.is_empty())
+}
+
+fn first_of(xml: &str, tags: &[&str]) -> Option<String> {
+    tags.iter()
+        .find_map(|tag| xml_text(xml, tag))
+        .filter(|v| !v.is_empty())
+}
+
+// "1.5 GB", "512MB", "2048 KB" or a bare number of megabytes, which is
+// what the portal reports when it leaves the unit out.
+fn parse_bytes(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..split].parse().ok()?;
+    let multiplier = match value[split..].trim().to_ascii_uppercase().as_str() {
+        "" | "MB" | "M" => 1024.0 * 1024.0,
+        "B" => 1.0,
+        "KB" | "K" => 1024.0,
+        "GB" | "G" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+// "01:02:03", "02:03" or plain seconds.
+fn parse_seconds(value: &str) -> Option<u64> {
+    value.trim().split(':').try_fold(0u64, |total, part| {
+        total
+            .checked_mul(60)?
+            .checked_add(part.trim().parse::<u64>().ok()?)
+    })
+}
+
+// Text of the first `<tag>` element, with any CDATA wrapper removed.
+pub fn xml_text(xml: &str, tag: &str) -> Option<String> {
+    xml_blocks(xml, tag).into_iter().next().map(|value| {
+        let value = value.trim();
+        value
+            .strip_prefix("<![CDATA[")
+            .and_then(|v| v.strip_suffix("]]>"))
+            .unwrap_or(value)
+            .trim()
+            .to_string()
+    })
+}
+
+// Contents of every `<tag>...</tag>` element, matched case-insensitively.
+fn xml_blocks(xml: &str, tag: &str) -> Vec<String> {
+    let lower = xml.to_ascii_lowercase();
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut blocks = vec![];
+    let mut from = 0;
+    while let Some(start) = lower[from..].find(&open).map(|i| from + i + open.len()) {
+        let Some(end) = lower[start..].find(&close).map(|i| start + i) else {
+            break;
+        };
+        blocks.push(xml[start..end].to_string());
+        from = end + close.len();
+    }
+    blocks
+}
@@ -0,0 +1,66 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::paraser::parseproctor::{self, ParsedFaculty};
+use super::progress::{FetchKind, VtopProgress};
+use super::types::{FacultyInfo, ProctorDetails};
+use super::vtop_client::VtopClient;
+use super::vtop_errors::VtopResult;
+
+impl VtopClient {
+    // The student's proctor, and class advisor when VTOP lists one.
+    pub async fn get_proctor_details(&self) -> VtopResult<ProctorDetails> {
+        self.proctor_details(true).await
+    }
+
+    // Like `get_proctor_details` without the extra requests for photos;
+    // only photos inlined in the page are kept.
+    pub async fn get_proctor_contacts(&self) -> VtopResult<ProctorDetails> {
+        self.proctor_details(false).await
+    }
+
+    async fn proctor_details(&self, photos: bool) -> VtopResult<ProctorDetails> {
+        self.report(VtopProgress::Fetching(FetchKind::Proctor));
+        let text = self.post_page("proctor/viewProctorDetails", &[]).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Proctor));
+        let page = parseproctor::parse_proctor_page(text);
+        Ok(ProctorDetails {
+            proctor: self.with_photo(page.proctor, photos).await?,
+            class_advisor: self.with_photo(page.class_advisor, photos).await?,
+            update_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::new(1, 0))
+                .as_secs(),
+        })
+    }
+
+    // A photo that fails to download leaves `photo` empty rather than
+    // losing the contact details; only a lost session is passed on.
+    async fn with_photo(
+        &self,
+        faculty: Option<ParsedFaculty>,
+        download: bool,
+    ) -> VtopResult<Option<FacultyInfo>> {
+        let Some(ParsedFaculty {
+            mut info,
+            photo_path,
+        }) = faculty
+        else {
+            return Ok(None);
+        };
+        if let Some(path) = photo_path.filter(|_| download) {
+            match self.get_file(&path).await {
+                Ok(response) => {
+                    info.photo = response
+                        .bytes()
+                        .await
+                        .ok()
+                        .map(|b| b.to_vec())
+                        .filter(|b| !b.is_empty());
+                }
+                Err(error) if error.is_auth_problem() => return Err(error),
+                Err(error) => tracing::debug!(error = %error, "proctor photo not downloaded"),
+            }
+        }
+        Ok(Some(info))
+    }
+}
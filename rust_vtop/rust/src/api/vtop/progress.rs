@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+use super::types::{
+    AttendanceData, ExamScheduleData, FullAttendanceData, MarksData, SemesterData, TimetableData,
+};
+use super::vtop_errors::{VtopError, VtopResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FetchKind {
+    Semesters,
+    Timetable,
+    Attendance,
+    FullAttendance,
+    Marks,
+    ExamSchedule,
+    CoursePage,
+    DigitalAssignments,
+    Finance,
+    Hostel,
+    Grades,
+    Curriculum,
+    Announcements,
+    Proctor,
+    Biometric,
+    Feedback,
+}
+
+// Steps of a VTOP call, in the order they happen. Login attempts count from
+// 1 and start over whenever the captcha was solved wrongly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum VtopProgress {
+    // Checking whether a saved cookie is still logged in.
+    RestoringSession,
+    LoadingLoginPage { attempt: u32 },
+    SolvingCaptcha { attempt: u32 },
+    SubmittingLogin { attempt: u32 },
+    LoggedIn,
+    Fetching(FetchKind),
+    Parsing(FetchKind),
+    Cancelled,
+    Failed { message: String },
+}
+
+// Items of the `*_stream` calls: progress, then the fetched data.
+#[derive(Debug, Clone, Serialize)]
+pub enum VtopEvent {
+    Progress(VtopProgress),
+    Semesters(SemesterData),
+    Timetable(TimetableData),
+    Attendance(AttendanceData),
+    FullAttendance(FullAttendanceData),
+    Marks(MarksData),
+    ExamSchedule(ExamScheduleData),
+}
+
+pub type ProgressFn = Arc<dyn Fn(VtopProgress) + Send + Sync>;
+
+tokio::task_local! {
+    static PROGRESS: ProgressFn;
+}
+
+// Runs `call` with the steps of every client call it makes going to
+// `progress`. Calls running at the same time, even on one client, each
+// report to their own callback.
+pub async fn with_progress<F: Future>(progress: ProgressFn, call: F) -> F::Output {
+    PROGRESS.scope(progress, call).await
+}
+
+pub(crate) fn report(step: VtopProgress) {
+    let _ = PROGRESS.try_with(|progress| progress(step));
+}
+
+// Shared between the caller and the calls it may want to abandon. Once
+// cancelled it stays cancelled; use a new token for the next call.
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    cancelled: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        let (cancelled, _) = watch::channel(false);
+        Self {
+            cancelled: Arc::new(cancelled),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+
+    pub async fn cancelled(&self) {
+        let mut receiver = self.cancelled.subscribe();
+        // The sender lives as long as `self`, so this only returns once set.
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+
+    // Runs `future` unless the token fires first. The future is dropped at
+    // its next await point, so no request is left running.
+    pub async fn run<T>(&self, future: impl Future<Output = VtopResult<T>>) -> VtopResult<T> {
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(VtopError::Cancelled),
+            result = future => result,
+        }
+    }
+}
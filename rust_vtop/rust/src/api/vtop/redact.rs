@@ -0,0 +1,134 @@
+use std::fmt;
+
+// Wraps a sensitive value so it can be handed to `tracing` or `{:?}` without
+// leaking it. Secrets (passwords, captcha answers, cookies, CSRF tokens) are
+// hidden entirely; registration numbers keep two characters at each end so
+// log lines from the same student can still be correlated.
+#[derive(Clone, Copy)]
+pub struct Redacted<'a> {
+    value: &'a str,
+    kind: RedactKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum RedactKind {
+    Secret,
+    RegNo,
+}
+
+impl<'a> Redacted<'a> {
+    pub fn secret(value: &'a str) -> Self {
+        Self {
+            value,
+            kind: RedactKind::Secret,
+        }
+    }
+
+    pub fn reg_no(value: &'a str) -> Self {
+        Self {
+            value,
+            kind: RedactKind::RegNo,
+        }
+    }
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RedactKind::Secret if self.value.is_empty() => write!(f, "[empty]"),
+            RedactKind::Secret => write!(f, "[redacted]"),
+            RedactKind::RegNo => {
+                let chars: Vec<char> = self.value.chars().collect();
+                if chars.len() <= 4 {
+                    return write!(f, "{}", "*".repeat(chars.len()));
+                }
+                let head: String = chars[..2].iter().collect();
+                let tail: String = chars[chars.len() - 2..].iter().collect();
+                write!(f, "{}{}{}", head, "*".repeat(chars.len() - 4), tail)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+// Replaces every occurrence of the given secrets, and the value of every
+// `_csrf` input or meta tag, in a captured page so debug dumps can be
+// shared safely. Short secrets are replaced too, even where they are part
+// of unrelated text.
+pub fn scrub_page(page: &str, secrets: &[&str]) -> String {
+    // Longest first, so a secret that contains another is replaced whole.
+    let mut secrets: Vec<&str> = secrets.iter().copied().filter(|s| !s.is_empty()).collect();
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+
+    let mut scrubbed = String::with_capacity(page.len());
+    let mut rest = page;
+    while let Some(c) = rest.chars().next() {
+        match secrets.iter().find(|secret| rest.starts_with(**secret)) {
+            Some(secret) => {
+                scrubbed.push_str("[redacted]");
+                rest = &rest[secret.len()..];
+            }
+            None => {
+                scrubbed.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    let mut out = String::with_capacity(scrubbed.len());
+    let mut rest = scrubbed.as_str();
+    while let Some(start) = rest.find('<') {
+        let (before, after) = rest.split_at(start);
+        out.push_str(before);
+        let tag_end = after.find('>').map(|i| i + 1).unwrap_or(after.len());
+        let tag = &after[..tag_end];
+        let lower = tag.to_ascii_lowercase();
+        if (lower.starts_with("<input") || lower.starts_with("<meta")) && lower.contains("_csrf") {
+            out.push_str(&redact_attribute(
+                &redact_attribute(tag, "value"),
+                "content",
+            ));
+        } else {
+            out.push_str(tag);
+        }
+        rest = &after[tag_end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// `tag` with the value of attribute `name` replaced, whether it is written
+// `name="…"`, `name='…'` or unquoted.
+fn redact_attribute(tag: &str, name: &str) -> String {
+    let lower = tag.to_ascii_lowercase();
+    let mut from = 0;
+    while let Some(found) = lower[from..].find(name).map(|i| from + i) {
+        from = found + name.len();
+        let starts_attribute = lower[..found].ends_with(|c: char| c.is_ascii_whitespace());
+        let after = lower[from..].trim_start();
+        if !starts_attribute || !after.starts_with('=') {
+            continue;
+        }
+        let value_at = lower.len() - after[1..].trim_start().len();
+        let (start, end) = match tag[value_at..].chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let start = value_at + 1;
+                let end = tag[start..].find(quote).map(|i| start + i);
+                (start, end.unwrap_or(tag.len()))
+            }
+            _ => {
+                let end = tag[value_at..]
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .map(|i| value_at + i);
+                (value_at, end.unwrap_or(tag.len()))
+            }
+        };
+        return format!("{}[redacted]{}", &tag[..start], &tag[end..]);
+    }
+    tag.to_string()
+}
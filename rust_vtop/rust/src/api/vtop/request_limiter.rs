@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use futures::StreamExt;
+use reqwest::{Body, Response, ResponseBuilderExt};
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use super::vtop_errors::{VtopError, VtopResult};
+
+#[derive(Debug, Clone)]
+pub struct LimiterConfig {
+    pub max_concurrent: usize,
+    pub per_account_interval: Duration,
+    pub queue_timeout: Duration,
+    pub breaker_threshold: u32,
+    pub breaker_cooldown: Duration,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 8,
+            per_account_interval: Duration::from_millis(250),
+            queue_timeout: Duration::from_secs(20),
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+// Each upstream has its own circuit breaker, so an unreachable captcha
+// solver does not stop requests to VTOP and the other way round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Upstream {
+    Vtop,
+    CaptchaSolver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LimiterMetrics {
+    pub max_concurrent: usize,
+    pub in_flight: usize,
+    pub queue_depth: usize,
+    pub tracked_accounts: usize,
+    pub breaker_state: BreakerState,
+    pub consecutive_failures: u32,
+    pub solver_breaker_state: BreakerState,
+    pub solver_consecutive_failures: u32,
+    pub rejected_queue_timeout: u64,
+    pub rejected_circuit_open: u64,
+}
+
+struct Breaker {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+    // Counts trials so a late `Trial` drop cannot clear a newer trial.
+    trials: u64,
+}
+
+impl Breaker {
+    fn new() -> Self {
+        Self {
+            state: BreakerState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            trial_in_flight: false,
+            trials: 0,
+        }
+    }
+}
+
+// The half-open trial a permit was granted, released when the permit is
+// dropped, including when the request future is cancelled before
+// reporting success or failure.
+struct Trial {
+    breaker: Arc<Mutex<Breaker>>,
+    number: u64,
+}
+
+impl Drop for Trial {
+    fn drop(&mut self) {
+        let mut breaker = self.breaker.lock().unwrap();
+        if breaker.trials == self.number {
+            breaker.trial_in_flight = false;
+        }
+    }
+}
+
+// Shared between every `VtopClient` in the process so that a burst of logins
+// cannot open unbounded connections to VTOP or the captcha solver.
+pub struct RequestLimiter {
+    config: LimiterConfig,
+    semaphore: Arc<Semaphore>,
+    accounts: Mutex<HashMap<String, Instant>>,
+    breaker: Arc<Mutex<Breaker>>,
+    solver_breaker: Arc<Mutex<Breaker>>,
+    queue_depth: AtomicUsize,
+    rejected_queue_timeout: AtomicU64,
+    rejected_circuit_open: AtomicU64,
+}
+
+pub struct RequestPermit {
+    _permit: OwnedSemaphorePermit,
+    _trial: Option<Trial>,
+}
+
+impl RequestPermit {
+    // Hands the permit to `response`, so the request counts against the
+    // limit until its body has been read or the response is dropped, not
+    // only until the headers arrive.
+    pub fn hold_until_read(self, response: Response) -> Response {
+        let mut builder = http::Response::builder()
+            .status(response.status())
+            .version(response.version())
+            .url(response.url().clone());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = response.headers().clone();
+        }
+        let body = response.bytes_stream().map(move |chunk| {
+            let _held = &self;
+            chunk
+        });
+        builder
+            .body(Body::wrap_stream(body))
+            .map(Response::from)
+            .expect("status, version and headers come from a valid response")
+    }
+}
+
+// Counts a caller in the queue for as long as it waits, including when its
+// future is dropped while waiting.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn enter(depth: &'a AtomicUsize) -> Self {
+        depth.fetch_add(1, Ordering::SeqCst);
+        Queued(depth)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+static GLOBAL_LIMITER: OnceLock<Arc<RequestLimiter>> = OnceLock::new();
+
+impl RequestLimiter {
+    pub fn new(config: LimiterConfig) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+            config,
+            accounts: Mutex::new(HashMap::new()),
+            breaker: Arc::new(Mutex::new(Breaker::new())),
+            solver_breaker: Arc::new(Mutex::new(Breaker::new())),
+            queue_depth: AtomicUsize::new(0),
+            rejected_queue_timeout: AtomicU64::new(0),
+            rejected_circuit_open: AtomicU64::new(0),
+        }
+    }
+
+    pub fn global() -> Arc<RequestLimiter> {
+        GLOBAL_LIMITER
+            .get_or_init(|| Arc::new(RequestLimiter::new(LimiterConfig::default())))
+            .clone()
+    }
+
+    // Must be called before the first client is built; returns false if the
+    // global limiter was already initialised.
+    pub fn configure_global(config: LimiterConfig) -> bool {
+        GLOBAL_LIMITER
+            .set(Arc::new(RequestLimiter::new(config)))
+            .is_ok()
+    }
+
+    pub async fn acquire(&self, upstream: Upstream, account: &str) -> VtopResult<RequestPermit> {
+        let trial = self.check_breaker(upstream)?;
+
+        let deadline = Instant::now() + self.config.queue_timeout;
+        let slot = self.reserve_account_slot(account, deadline)?;
+
+        let queued = Queued::enter(&self.queue_depth);
+        tokio::time::sleep_until(slot).await;
+        let permit =
+            tokio::time::timeout_at(deadline, self.semaphore.clone().acquire_owned()).await;
+        drop(queued);
+
+        match permit {
+            Ok(Ok(permit)) => Ok(RequestPermit {
+                _permit: permit,
+                _trial: trial,
+            }),
+            _ => {
+                self.rejected_queue_timeout.fetch_add(1, Ordering::Relaxed);
+                Err(VtopError::QueueTimeout)
+            }
+        }
+    }
+
+    pub fn record_success(&self, upstream: Upstream) {
+        let mut breaker = self.breaker_of(upstream).lock().unwrap();
+        breaker.state = BreakerState::Closed;
+        breaker.consecutive_failures = 0;
+        breaker.opened_at = None;
+        breaker.trial_in_flight = false;
+    }
+
+    pub fn record_failure(&self, upstream: Upstream) {
+        let mut breaker = self.breaker_of(upstream).lock().unwrap();
+        breaker.consecutive_failures += 1;
+        breaker.trial_in_flight = false;
+        if breaker.state == BreakerState::HalfOpen
+            || breaker.consecutive_failures >= self.config.breaker_threshold
+        {
+            breaker.state = BreakerState::Open;
+            breaker.opened_at = Some(Instant::now());
+        }
+    }
+
+    pub fn metrics(&self) -> LimiterMetrics {
+        let breaker = self.breaker.lock().unwrap();
+        let solver_breaker = self.solver_breaker.lock().unwrap();
+        LimiterMetrics {
+            max_concurrent: self.config.max_concurrent,
+            in_flight: self.config.max_concurrent.max(1) - self.semaphore.available_permits(),
+            queue_depth: self.queue_depth.load(Ordering::SeqCst),
+            tracked_accounts: self.accounts.lock().unwrap().len(),
+            breaker_state: breaker.state,
+            consecutive_failures: breaker.consecutive_failures,
+            solver_breaker_state: solver_breaker.state,
+            solver_consecutive_failures: solver_breaker.consecutive_failures,
+            rejected_queue_timeout: self.rejected_queue_timeout.load(Ordering::Relaxed),
+            rejected_circuit_open: self.rejected_circuit_open.load(Ordering::Relaxed),
+        }
+    }
+
+    fn breaker_of(&self, upstream: Upstream) -> &Arc<Mutex<Breaker>> {
+        match upstream {
+            Upstream::Vtop => &self.breaker,
+            Upstream::CaptchaSolver => &self.solver_breaker,
+        }
+    }
+
+    fn check_breaker(&self, upstream: Upstream) -> VtopResult<Option<Trial>> {
+        let shared = self.breaker_of(upstream);
+        let mut breaker = shared.lock().unwrap();
+        if breaker.state == BreakerState::Open {
+            let cooled_down = breaker
+                .opened_at
+                .map(|at| at.elapsed() >= self.config.breaker_cooldown)
+                .unwrap_or(true);
+            if cooled_down {
+                breaker.state = BreakerState::HalfOpen;
+            }
+        }
+        match breaker.state {
+            BreakerState::Closed => Ok(None),
+            // Only a single trial request is let through while half open.
+            BreakerState::HalfOpen if !breaker.trial_in_flight => {
+                breaker.trial_in_flight = true;
+                breaker.trials += 1;
+                Ok(Some(Trial {
+                    breaker: shared.clone(),
+                    number: breaker.trials,
+                }))
+            }
+            _ => {
+                self.rejected_circuit_open.fetch_add(1, Ordering::Relaxed);
+                Err(VtopError::CircuitOpen)
+            }
+        }
+    }
+
+    fn reserve_account_slot(&self, account: &str, deadline: Instant) -> VtopResult<Instant> {
+        let now = Instant::now();
+        let mut accounts = self.accounts.lock().unwrap();
+        if accounts.len() > 1024 {
+            accounts.retain(|_, next| *next > now);
+        }
+        let slot = accounts.get(account).copied().unwrap_or(now).max(now);
+        if slot >= deadline {
+            self.rejected_queue_timeout.fetch_add(1, Ordering::Relaxed);
+            return Err(VtopError::QueueTimeout);
+        }
+        accounts.insert(account.to_string(), slot + self.config.per_account_interval);
+        Ok(slot)
+    }
+}
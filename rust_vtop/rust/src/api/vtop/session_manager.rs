@@ -13,6 +13,12 @@ pub struct SessionManager {
     is_cookie_external: bool,
 }
 
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SessionManager {
     pub fn new() -> Self {
         #[cfg(not(target_arch = "wasm32"))]
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use reqwest::ClientBuilder;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, SignatureScheme};
+use serde::{Deserialize, Serialize};
+
+use super::vtop_errors::{VtopError, VtopResult};
+
+// How certificates presented by VTOP and the campus portals are checked.
+//
+// Certificate entries in `Verify` and `TrustedCa` are either PEM text or a
+// path to a PEM file. Pins are base64 SHA-256 hashes of the server's
+// SubjectPublicKeyInfo, the same format as `pin-sha256` in HPKP:
+//
+//     openssl x509 -pubkey -noout -in cert.pem | openssl pkey -pubin -outform der \
+//         | openssl dgst -sha256 -binary | base64
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TlsPolicy {
+    // System/webpki roots plus any extra roots.
+    Verify {
+        #[serde(default)]
+        extra_roots: Vec<String>,
+    },
+    // Only chains issued by the given CAs are accepted; built-in roots are off.
+    TrustedCa {
+        certificates: Vec<String>,
+    },
+    // Only a server whose own key matches one of the pins is accepted. The
+    // chain itself is not validated, which suits self-signed portal certs.
+    PinnedSpki {
+        pins: Vec<String>,
+    },
+    // Accepts any certificate. Anyone on the network can read credentials.
+    DangerAcceptInvalidCerts,
+}
+
+impl Default for TlsPolicy {
+    fn default() -> Self {
+        TlsPolicy::Verify {
+            extra_roots: vec![],
+        }
+    }
+}
+
+impl TlsPolicy {
+    pub fn pinned<I, S>(pins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        TlsPolicy::PinnedSpki {
+            pins: pins.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    // The policy for servers other than the one this policy was written for,
+    // such as the captcha solver: trusted CAs and pins describe that one
+    // server's certificate, so other servers get normal verification.
+    pub fn for_other_hosts(&self) -> TlsPolicy {
+        match self {
+            TlsPolicy::TrustedCa { .. } | TlsPolicy::PinnedSpki { .. } => TlsPolicy::default(),
+            policy => policy.clone(),
+        }
+    }
+
+    pub fn is_insecure(&self) -> bool {
+        matches!(self, TlsPolicy::DangerAcceptInvalidCerts)
+    }
+
+    pub fn configure(&self, builder: ClientBuilder) -> VtopResult<ClientBuilder> {
+        match self {
+            TlsPolicy::Verify { extra_roots } => {
+                let mut builder = builder;
+                for entry in extra_roots {
+                    builder = builder.add_root_certificate(load_certificate(entry)?);
+                }
+                Ok(builder)
+            }
+            TlsPolicy::TrustedCa { certificates } => {
+                if certificates.is_empty() {
+                    return Err(VtopError::ConfigurationError(
+                        "trusted_ca policy needs at least one certificate".into(),
+                    ));
+                }
+                let mut builder = builder.tls_built_in_root_certs(false);
+                for entry in certificates {
+                    builder = builder.add_root_certificate(load_certificate(entry)?);
+                }
+                Ok(builder)
+            }
+            TlsPolicy::PinnedSpki { pins } => {
+                let verifier = PinnedSpkiVerifier::new(pins)?;
+                let provider = verifier.provider.clone();
+                let config = rustls::ClientConfig::builder_with_provider(provider)
+                    .with_safe_default_protocol_versions()
+                    .map_err(|e| VtopError::ConfigurationError(e.to_string()))?
+                    .dangerous()
+                    .with_custom_certificate_verifier(Arc::new(verifier))
+                    .with_no_client_auth();
+                Ok(builder.use_preconfigured_tls(config))
+            }
+            TlsPolicy::DangerAcceptInvalidCerts => {
+                tracing::warn!("TLS certificate verification is disabled");
+                Ok(builder.danger_accept_invalid_certs(true))
+            }
+        }
+    }
+}
+
+// Returns the base64 `pin-sha256` value for the key in a DER certificate.
+pub fn spki_pin(certificate_der: &[u8]) -> Option<String> {
+    spki_sha256(certificate_der).map(|hash| STANDARD.encode(hash))
+}
+
+fn load_certificate(entry: &str) -> VtopResult<reqwest::Certificate> {
+    let pem = if entry.trim_start().starts_with("-----BEGIN") {
+        entry.as_bytes().to_vec()
+    } else {
+        std::fs::read(entry)
+            .map_err(|e| VtopError::ConfigurationError(format!("{}: {}", entry, e)))?
+    };
+    reqwest::Certificate::from_pem(&pem)
+        .map_err(|e| VtopError::ConfigurationError(format!("invalid certificate: {}", e)))
+}
+
+fn spki_sha256(certificate_der: &[u8]) -> Option<[u8; 32]> {
+    let spki = subject_public_key_info(certificate_der)?;
+    let digest = ::ring::digest::digest(&::ring::digest::SHA256, spki);
+    digest.as_ref().try_into().ok()
+}
+
+// Walks just enough of the X.509 DER structure to find the
+// SubjectPublicKeyInfo inside the TBSCertificate.
+fn subject_public_key_info(certificate_der: &[u8]) -> Option<&[u8]> {
+    let (_, certificate, _) = der_element(certificate_der)?;
+    let (_, tbs, _) = der_element(certificate)?;
+    let mut rest = tbs;
+    // Optional explicit version tag [0].
+    if rest.first() == Some(&0xa0) {
+        rest = der_element(rest)?.2;
+    }
+    // serialNumber, signature, issuer, validity, subject.
+    for _ in 0..5 {
+        rest = der_element(rest)?.2;
+    }
+    let (tag, _, after) = der_element(rest)?;
+    if tag != 0x30 {
+        return None;
+    }
+    Some(&rest[..rest.len() - after.len()])
+}
+
+// Returns (tag, contents, remaining input) for the element at the start.
+fn der_element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let tag = *input.first()?;
+    let first = *input.get(1)? as usize;
+    let (length, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let bytes = input.get(2..2 + count)?;
+        let length = bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (length, 2 + count)
+    };
+    let contents = input.get(header..header.checked_add(length)?)?;
+    Some((tag, contents, &input[header + length..]))
+}
+
+#[derive(Debug)]
+struct PinnedSpkiVerifier {
+    pins: Vec<[u8; 32]>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl PinnedSpkiVerifier {
+    fn new(pins: &[String]) -> VtopResult<Self> {
+        let pins = pins
+            .iter()
+            .map(|pin| {
+                let encoded = pin.trim().trim_start_matches("sha256/");
+                STANDARD
+                    .decode(encoded)
+                    .ok()
+                    .and_then(|hash| <[u8; 32]>::try_from(hash).ok())
+                    .ok_or_else(|| {
+                        VtopError::ConfigurationError(format!("invalid SPKI pin {}", pin))
+                    })
+            })
+            .collect::<VtopResult<Vec<_>>>()?;
+        if pins.is_empty() {
+            return Err(VtopError::ConfigurationError(
+                "pinned_spki policy needs at least one pin".into(),
+            ));
+        }
+        Ok(Self {
+            pins,
+            provider: Arc::new(ring::default_provider()),
+        })
+    }
+}
+
+impl ServerCertVerifier for PinnedSpkiVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // Only the end-entity key counts: intermediates are unauthenticated
+        // and a pinned CA belongs in `TlsPolicy::TrustedCa` instead.
+        match spki_sha256(end_entity) {
+            Some(hash) if self.pins.contains(&hash) => Ok(ServerCertVerified::assertion()),
+            Some(_) => Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            )),
+            None => Err(rustls::Error::InvalidCertificate(
+                CertificateError::BadEncoding,
+            )),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
@@ -164,3 +164,359 @@ pub struct SemesterData {
     pub update_time: u64,
 }
 
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoursePageCourse {
+    pub class_id: String,
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub slot: String,
+    pub faculty: String,
+    // VTOP's `erpId`, needed to open the class's course page.
+    pub faculty_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoursePageCourses {
+    pub courses: Vec<CoursePageCourse>,
+    pub semester_id: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CourseMaterial {
+    pub title: String,
+    // Relative to `/vtop/`; pass the material to `download_material`.
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoursePageLecture {
+    pub serial: String,
+    pub date: String,
+    pub day: String,
+    pub topic: String,
+    pub materials: Vec<CourseMaterial>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoursePageData {
+    // Course-wide material that is not tied to a lecture.
+    pub materials: Vec<CourseMaterial>,
+    pub lectures: Vec<CoursePageLecture>,
+    pub semester_id: String,
+    pub class_id: String,
+    pub faculty_id: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalAssignment {
+    pub serial: String,
+    // VTOP's assessment code, e.g. `Am01`; needed to upload.
+    pub code: String,
+    pub title: String,
+    pub max_marks: String,
+    pub weightage: String,
+    pub due_date: String,
+    pub question_paper: Option<CourseMaterial>,
+    pub uploaded: bool,
+    pub last_updated: String,
+    // Whether VTOP still shows the upload button.
+    pub can_upload: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalAssignmentCourse {
+    pub class_id: String,
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub faculty: String,
+    pub assignments: Vec<DigitalAssignment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigitalAssignmentData {
+    pub courses: Vec<DigitalAssignmentCourse>,
+    pub semester_id: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeDue {
+    pub serial: String,
+    pub fee_head: String,
+    pub term: String,
+    pub amount: String,
+    pub due_date: String,
+    pub fine: String,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeDuesData {
+    pub dues: Vec<FeeDue>,
+    // As VTOP prints it in the table's total row; empty when there is none.
+    pub total_due: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeePayment {
+    pub serial: String,
+    pub receipt_no: String,
+    pub date: String,
+    pub description: String,
+    pub amount: String,
+    pub mode: String,
+    pub status: String,
+    // Whether VTOP offers the receipt PDF for this payment.
+    pub has_receipt: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentHistoryData {
+    pub payments: Vec<FeePayment>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostelRequestKind {
+    // Leaving campus and returning the same day.
+    Outing,
+    // Staying away overnight or longer.
+    Leave,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostelApplication {
+    pub kind: HostelRequestKind,
+    pub id: String,
+    pub place: String,
+    pub purpose: String,
+    pub from_date: String,
+    pub from_time: String,
+    pub to_date: String,
+    pub to_time: String,
+    pub approver: String,
+    pub status: String,
+    // VTOP only offers to cancel applications that are still pending.
+    pub can_cancel: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostelApplicationsData {
+    pub applications: Vec<HostelApplication>,
+    pub kind: HostelRequestKind,
+    pub update_time: u64,
+}
+
+// A new outing or leave application. Dates are `dd-Mon-yyyy` and times
+// 24-hour `HH:MM`, as on VTOP's form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostelRequest {
+    pub kind: HostelRequestKind,
+    pub place: String,
+    pub purpose: String,
+    pub from_date: String,
+    pub from_time: String,
+    pub to_date: String,
+    pub to_time: String,
+    pub contact_number: String,
+}
+
+// One graded attempt at a course, from the semester grade view or the
+// arrears view. This is what `gpa` computes with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CourseGrade {
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub credits: f32,
+    pub grade: String,
+    // VTOP's own grade point when the page shows one.
+    pub grade_points: Option<f32>,
+    pub result_status: String,
+    // Month of the re-exam, e.g. `Dec-2024`; empty for regular results.
+    pub exam_month: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemesterGradesData {
+    pub grades: Vec<CourseGrade>,
+    // The GPA VTOP prints under the sheet, if any.
+    pub published_gpa: Option<f32>,
+    pub semester_id: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArrearsData {
+    pub records: Vec<CourseGrade>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurriculumCategory {
+    // `UC`, `PC`, `UE`, `PE`, ...
+    pub code: String,
+    pub name: String,
+    pub credits_required: f32,
+    // What VTOP reports as earned, when the page shows it.
+    pub credits_earned: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurriculumCourse {
+    pub category: String,
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub credits: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurriculumData {
+    pub categories: Vec<CurriculumCategory>,
+    // Courses the programme names; electives are only counted by category.
+    pub courses: Vec<CurriculumCourse>,
+    pub update_time: u64,
+}
+
+// A course the student was allotted at registration, with the curriculum
+// category it counts towards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CourseAllocation {
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub category: String,
+    pub credits: f32,
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllocationData {
+    pub allocations: Vec<CourseAllocation>,
+    pub update_time: u64,
+}
+
+// A spotlight announcement or a message from the messages page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Announcement {
+    // VTOP's id when the page has one, otherwise built from the category,
+    // date and title so it stays the same across fetches.
+    pub id: String,
+    pub category: String,
+    pub title: String,
+    pub body: String,
+    pub attachments: Vec<CourseMaterial>,
+    pub posted_date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncementsData {
+    pub announcements: Vec<Announcement>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacultyInfo {
+    pub name: String,
+    pub emp_id: String,
+    pub designation: String,
+    pub school: String,
+    pub cabin: String,
+    pub email: String,
+    pub phone: String,
+    // Image bytes as VTOP serves them, usually JPEG.
+    pub photo: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProctorDetails {
+    pub proctor: Option<FacultyInfo>,
+    pub class_advisor: Option<FacultyInfo>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BiometricPunch {
+    pub time: String,
+    pub location: String,
+    // `IN` or `OUT` when the device records it, otherwise empty.
+    pub direction: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BiometricDay {
+    pub date: String,
+    pub punches: Vec<BiometricPunch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BiometricLogData {
+    // One entry per day with at least one punch, in VTOP's order.
+    pub days: Vec<BiometricDay>,
+    pub update_time: u64,
+}
+
+// A course feedback form listed for the semester.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackForm {
+    pub form_id: String,
+    pub class_id: String,
+    pub course_code: String,
+    pub course_title: String,
+    pub course_type: String,
+    pub faculty: String,
+    pub submitted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackFormsData {
+    pub forms: Vec<FeedbackForm>,
+    pub semester_id: String,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FeedbackQuestionKind {
+    // A numbered scale, e.g. 1 (poor) to 5 (excellent).
+    Rating { min: u32, max: u32 },
+    // One of the listed option values.
+    Choice { options: Vec<String> },
+    Text,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackQuestion {
+    // The form field the answer is sent as.
+    pub id: String,
+    pub text: String,
+    pub kind: FeedbackQuestionKind,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackQuestionsData {
+    pub form_id: String,
+    pub semester_id: String,
+    pub questions: Vec<FeedbackQuestion>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FeedbackAnswer {
+    Rating(u32),
+    Choice(String),
+    Text(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedbackResponse {
+    pub question_id: String,
+    pub answer: FeedbackAnswer,
+}
@@ -1,7 +1,11 @@
 pub use super::types::*;
 pub use super::{
     paraser::*,
+    progress::{self, FetchKind, VtopProgress},
+    redact::{scrub_page, Redacted},
+    request_limiter::{RequestLimiter, Upstream},
     session_manager::SessionManager,
+    tls_policy::TlsPolicy,
     types::{AttendanceData, ExamScheduleData, FullAttendanceData},
     vtop_config::VtopConfig,
     vtop_errors::{VtopError, VtopResult},
@@ -11,18 +15,25 @@ use base64::{engine::general_purpose::URL_SAFE, Engine as _};
 #[cfg(not(target_arch = "wasm32"))]
 pub use reqwest::cookie::{CookieStore, Jar};
 use reqwest::{
-    header::{HeaderMap, HeaderValue, USER_AGENT},
-    multipart, Client, Url,
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+    multipart, Client, RequestBuilder, Response, Url,
 };
 
 use scraper::{Html, Selector};
 use serde::Serialize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 
 pub struct VtopClient {
     client: Client,
+    // For the captcha solver, with the same proxy, timeouts and user agent
+    // but without VTOP's cookies and headers.
+    solver: Client,
     config: VtopConfig,
-    session: SessionManager,
+    // Locked only briefly, never across a request, so fetches can share
+    // `&self` and run concurrently.
+    session: Mutex<SessionManager>,
+    limiter: Arc<RequestLimiter>,
     current_page: Option<String>,
     username: String,
     password: String,
@@ -30,16 +41,25 @@ pub struct VtopClient {
 }
 
 impl VtopClient {
+    fn session(&self) -> MutexGuard<'_, SessionManager> {
+        self.session.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // Goes to the callback of the surrounding `progress::with_progress`.
+    pub(super) fn report(&self, step: VtopProgress) {
+        progress::report(step);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub async fn get_cookie(&self, check: bool) -> VtopResult<Vec<u8>> {
-        if !self.session.is_authenticated() && check {
+        if !self.session().is_authenticated() && check {
             return Err(VtopError::SessionExpired);
         }
 
         let mut data = vec![];
         let url = format!("{}/vtop", self.config.base_url);
         let k = self
-            .session
+            .session()
             .get_cookie_store()
             .cookies(&Url::parse(&url).unwrap());
         if let Some(cookie) = k {
@@ -51,10 +71,10 @@ impl VtopClient {
     pub fn set_cookie(&mut self, cookie: String) {
         let url = format!("{}/vtop", self.config.base_url);
 
-        self.session.set_cookie_from_external(url, cookie);
+        self.session().set_cookie_from_external(url, cookie);
     }
-    pub async fn get_semesters(&mut self, check: bool) -> VtopResult<SemesterData> {
-        if !self.session.is_authenticated() && check {
+    pub async fn get_semesters(&self, check: bool) -> VtopResult<SemesterData> {
+        if !self.session().is_authenticated() && check {
             return Err(VtopError::SessionExpired);
         }
         let url = format!(
@@ -65,95 +85,70 @@ impl VtopClient {
         let body = format!(
             "verifyMenu=true&authorizedID={}&_csrf={}&nocache=@(new Date().getTime())",
             self.username,
-            self.session
+            self.session()
                 .get_csrf_token()
                 .ok_or(VtopError::SessionExpired)?,
         );
-        let res = self
-            .client
-            .post(url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
-            return Err(VtopError::SessionExpired);
-        }
-
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
+        self.report(VtopProgress::Fetching(FetchKind::Semesters));
+        let res = self.send(self.client.post(url).body(body)).await?;
+        let text = self.read_authenticated_page(res).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Semesters));
         Ok(parsett::parse_semid_timetable(text))
     }
 
-    pub async fn get_timetable(&mut self, semester_id: &str) -> VtopResult<TimetableData> {
-        if !self.session.is_authenticated() {
+    pub async fn get_timetable(&self, semester_id: &str) -> VtopResult<TimetableData> {
+        if !self.session().is_authenticated() {
             return Err(VtopError::SessionExpired);
         }
         let url = format!("{}/vtop/processViewTimeTable", self.config.base_url);
         let body = format!(
             "_csrf={}&semesterSubId={}&authorizedID={}",
-            self.session
+            self.session()
                 .get_csrf_token()
                 .ok_or(VtopError::SessionExpired)?,
             semester_id,
             self.username
         );
-        let res = self
-            .client
-            .post(url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
-            return Err(VtopError::SessionExpired);
-        }
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
+        self.report(VtopProgress::Fetching(FetchKind::Timetable));
+        let res = self.send(self.client.post(url).body(body)).await?;
+        let text = self.read_authenticated_page(res).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Timetable));
         Ok(parsett::parse_timetable(text, semester_id))
     }
 
-    pub async fn get_attendance(&mut self, semester_id: &str) -> VtopResult<AttendanceData> {
-        if !self.session.is_authenticated() {
+    pub async fn get_attendance(&self, semester_id: &str) -> VtopResult<AttendanceData> {
+        if !self.session().is_authenticated() {
             return Err(VtopError::SessionExpired);
         }
         let url = format!("{}/vtop/processViewStudentAttendance", self.config.base_url);
         let body = format!(
             "_csrf={}&semesterSubId={}&authorizedID={}",
-            self.session
+            self.session()
                 .get_csrf_token()
                 .ok_or(VtopError::SessionExpired)?,
             semester_id,
             self.username
         );
-        let res = self
-            .client
-            .post(url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
-            return Err(VtopError::SessionExpired);
-        };
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
+        self.report(VtopProgress::Fetching(FetchKind::Attendance));
+        let res = self.send(self.client.post(url).body(body)).await?;
+        let text = self.read_authenticated_page(res).await?;
+        self.report(VtopProgress::Parsing(FetchKind::Attendance));
         Ok(parseattn::parse_attendance(text, semester_id.to_string()))
     }
 
     pub async fn get_full_attendance(
-        &mut self,
+        &self,
         semester_id: &str,
         course_id: &str,
         course_type: &str,
     ) -> VtopResult<FullAttendanceData> {
-        if !self.session.is_authenticated() {
+        if !self.session().is_authenticated() {
             return Err(VtopError::SessionExpired);
         }
         let url = format!("{}/vtop/processViewAttendanceDetail", self.config.base_url);
         let body = format!(
             "_csrf={}&semesterSubId={}&registerNumber={}&courseId={}&courseType={}&authorizedID={}",
-            self.session
+            self.session()
                 .get_csrf_token()
                 .ok_or(VtopError::SessionExpired)?,
             semester_id,
@@ -162,18 +157,10 @@ impl VtopClient {
             course_type,
             self.username
         );
-        let res = self
-            .client
-            .post(url)
-            .body(body)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
-            return Err(VtopError::SessionExpired);
-        }
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
+        self.report(VtopProgress::Fetching(FetchKind::FullAttendance));
+        let res = self.send(self.client.post(url).body(body)).await?;
+        let text = self.read_authenticated_page(res).await?;
+        self.report(VtopProgress::Parsing(FetchKind::FullAttendance));
         Ok(parseattn::parse_full_attendance(
             text,
             semester_id.to_string(),
@@ -182,8 +169,8 @@ impl VtopClient {
         ))
     }
 
-    pub async fn get_marks(&mut self, semester_id: &str) -> VtopResult<MarksData> {
-        if !self.session.is_authenticated() {
+    pub async fn get_marks(&self, semester_id: &str) -> VtopResult<MarksData> {
+        if !self.session().is_authenticated() {
             return Err(VtopError::SessionExpired);
         }
         let url = format!(
@@ -195,30 +182,21 @@ impl VtopClient {
             .text("semesterSubId", semester_id.to_string())
             .text(
                 "_csrf",
-                self.session
+                self.session()
                     .get_csrf_token()
                     .ok_or(VtopError::SessionExpired)?,
             );
 
-        let res = self
-            .client
-            .post(url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
-            return Err(VtopError::SessionExpired);
-        }
-
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
+        self.report(VtopProgress::Fetching(FetchKind::Marks));
+        let res = self.send(self.client.post(url).multipart(form)).await?;
+        let text = self.read_authenticated_page(res).await?;
 
+        self.report(VtopProgress::Parsing(FetchKind::Marks));
         Ok(parsemarks::parse_marks(text, semester_id.to_string()))
     }
 
-    pub async fn get_exam_schedule(&mut self, semester_id: &str) -> VtopResult<ExamScheduleData> {
-        if !self.session.is_authenticated() {
+    pub async fn get_exam_schedule(&self, semester_id: &str) -> VtopResult<ExamScheduleData> {
+        if !self.session().is_authenticated() {
             return Err(VtopError::SessionExpired);
         }
         let url = format!(
@@ -230,47 +208,168 @@ impl VtopClient {
             .text("semesterSubId", semester_id.to_string())
             .text(
                 "_csrf",
-                self.session
+                self.session()
                     .get_csrf_token()
                     .ok_or(VtopError::SessionExpired)?,
             );
-        let res = self
-            .client
-            .post(url)
-            .multipart(form)
-            .send()
+        self.report(VtopProgress::Fetching(FetchKind::ExamSchedule));
+        let res = self.send(self.client.post(url).multipart(form)).await?;
+        let text = self.read_authenticated_page(res).await?;
+        self.report(VtopProgress::Parsing(FetchKind::ExamSchedule));
+        Ok(parsesched::parse_schedule(text, semester_id.to_string()))
+    }
+    pub fn is_authenticated(&self) -> bool {
+        self.session().is_authenticated()
+    }
+
+    // Every outgoing request goes through the shared limiter, which also
+    // feeds the circuit breaker with transport errors and 5xx responses.
+    // The permit stays with the response until its body has been read.
+    async fn send(&self, request: RequestBuilder) -> VtopResult<Response> {
+        self.send_to(Upstream::Vtop, request).await
+    }
+
+    async fn send_to(&self, upstream: Upstream, request: RequestBuilder) -> VtopResult<Response> {
+        let permit = self.limiter.acquire(upstream, &self.username).await?;
+        match request.send().await {
+            Ok(response) if response.status().is_server_error() => {
+                self.limiter.record_failure(upstream);
+                Ok(permit.hold_until_read(response))
+            }
+            Ok(response) => {
+                self.limiter.record_success(upstream);
+                Ok(permit.hold_until_read(response))
+            }
+            Err(e) => {
+                self.limiter.record_failure(upstream);
+                Err(VtopError::network(e))
+            }
+        }
+    }
+
+    // Turns the response of an authenticated page into its body. VTOP
+    // redirects to the login page once the session is gone.
+    async fn read_authenticated_page(&self, response: Response) -> VtopResult<String> {
+        self.check_authenticated(response)
+            .await?
+            .text()
             .await
-            .map_err(|_| VtopError::NetworkError)?;
-        if !res.status().is_success() || res.url().to_string().contains("login") {
-            self.session.set_authenticated(false);
+            .map_err(VtopError::network)
+    }
+
+    async fn check_authenticated(&self, response: Response) -> VtopResult<Response> {
+        let status = response.status();
+        let url = response.url().clone();
+        if status.is_server_error() {
+            let body = response.text().await.unwrap_or_default();
+            self.capture_page(&format!("http{}{}", status.as_u16(), url.path()), &body);
+            return Err(VtopError::server(&url, status, &body));
+        }
+        if !status.is_success() || url.as_str().contains("login") {
+            self.session().set_authenticated(false);
             return Err(VtopError::SessionExpired);
         }
-        let text = res.text().await.map_err(|_| VtopError::VtopServerError)?;
-        Ok(parsesched::parse_schedule(text, semester_id.to_string()))
+        Ok(response)
+    }
+
+    // Posts `fields` to `/vtop/{path}` along with the CSRF token and
+    // `authorizedID` that every student page expects, and returns the page.
+    #[cfg(feature = "unverified-endpoints")]
+    pub(super) async fn post_page(&self, path: &str, fields: &[(&str, &str)]) -> VtopResult<String> {
+        self.post_file(path, fields)
+            .await?
+            .text()
+            .await
+            .map_err(VtopError::network)
+    }
+
+    // Submits a form that VTOP answers with a notice, and returns the notice
+    // if it reports success.
+    #[cfg(feature = "unverified-endpoints")]
+    pub(super) async fn post_form(&self, path: &str, fields: &[(&str, &str)]) -> VtopResult<String> {
+        let page = self.post_page(path, fields).await?;
+        let document = Html::parse_document(&page);
+        let message = page_util::page_message(&document);
+        if page_util::reports_success(&document, &message) {
+            Ok(message)
+        } else {
+            self.capture_page(&format!("rejected_{}", path), &page);
+            Err(VtopError::RequestRejected(message))
+        }
+    }
+
+    // Like `post_page`, for VTOP's multipart forms such as file uploads.
+    #[cfg(feature = "unverified-endpoints")]
+    pub(super) async fn post_multipart(
+        &self,
+        path: &str,
+        form: multipart::Form,
+    ) -> VtopResult<String> {
+        if !self.session().is_authenticated() {
+            return Err(VtopError::SessionExpired);
+        }
+        let csrf = self
+            .session()
+            .get_csrf_token()
+            .ok_or(VtopError::SessionExpired)?;
+        let form = form
+            .text("_csrf", csrf)
+            .text("authorizedID", self.username.clone());
+        let url = format!("{}/vtop/{}", self.config.base_url, path);
+        let res = self.send(self.client.post(url).multipart(form)).await?;
+        self.read_authenticated_page(res).await
     }
-    pub fn is_authenticated(&mut self) -> bool {
-        self.session.is_authenticated()
+
+    // Opens `/vtop/{path}` without reading the body, for downloads.
+    #[cfg(feature = "unverified-endpoints")]
+    pub(super) async fn get_file(&self, path: &str) -> VtopResult<Response> {
+        if !self.session().is_authenticated() {
+            return Err(VtopError::SessionExpired);
+        }
+        let url = format!("{}/vtop/{}", self.config.base_url, path);
+        let res = self.send(self.client.get(url)).await?;
+        self.check_authenticated(res).await
+    }
+
+    // Downloads that VTOP serves in reply to a form, such as receipts.
+    #[cfg(feature = "unverified-endpoints")]
+    pub(super) async fn post_file(&self, path: &str, fields: &[(&str, &str)]) -> VtopResult<Response> {
+        if !self.session().is_authenticated() {
+            return Err(VtopError::SessionExpired);
+        }
+        let csrf = self
+            .session()
+            .get_csrf_token()
+            .ok_or(VtopError::SessionExpired)?;
+        let mut form = vec![("_csrf", csrf.as_str()), ("authorizedID", &self.username)];
+        form.extend_from_slice(fields);
+        let url = format!("{}/vtop/{}", self.config.base_url, path);
+        let res = self.send(self.client.post(url).form(&form)).await?;
+        self.check_authenticated(res).await
     }
 }
 // for login
 impl VtopClient {
+    #[tracing::instrument(skip_all, fields(username = %Redacted::reg_no(&self.username)))]
     pub async fn login(&mut self) -> VtopResult<()> {
-        if self.session.is_cookie_external() {
+        if self.session().is_cookie_external() {
+            self.report(VtopProgress::RestoringSession);
             let cookie = self.get_cookie(false).await;
             match cookie {
                 Ok(value_of_cookie) => {
                     if !value_of_cookie.is_empty() {
                         if self.get_csrf_for_cookie_set().await.is_ok() {
-                            self.session.set_authenticated(true);
-                            self.session.set_cookie_external(false);
+                            self.session().set_authenticated(true);
+                            self.session().set_cookie_external(false);
+                            self.report(VtopProgress::LoggedIn);
                             return Ok(());
                         }
-                        self.session.set_authenticated(false);
+                        self.session().set_authenticated(false);
                     }
-                    self.session.set_cookie_external(false);
+                    self.session().set_cookie_external(false);
                 }
                 Err(_e) => {
-                    self.session.set_cookie_external(false);
+                    self.session().set_cookie_external(false);
                 }
             }
         }
@@ -278,6 +377,8 @@ impl VtopClient {
         #[allow(non_snake_case)]
         let MAX_CAP_TRY = 4;
         for i in 0..MAX_CAP_TRY {
+            let attempt = i + 1;
+            self.report(VtopProgress::LoadingLoginPage { attempt });
             if i == 0 {
                 self.load_login_page(true).await?;
             } else {
@@ -285,13 +386,16 @@ impl VtopClient {
             }
 
             let captcha_answer = if let Some(captcha_data) = &self.captcha_data {
+                self.report(VtopProgress::SolvingCaptcha { attempt });
                 self.solve_captcha(captcha_data).await?
             } else {
                 return Err(VtopError::CaptchaRequired);
             };
+            self.report(VtopProgress::SubmittingLogin { attempt });
             match self.perform_login(&captcha_answer).await {
                 Ok(_) => {
-                    self.session.set_authenticated(true);
+                    self.session().set_authenticated(true);
+                    self.report(VtopProgress::LoggedIn);
                     return Ok(());
                 }
                 Err(VtopError::AuthenticationFailed(msg)) if msg.contains("Invalid Captcha") => {
@@ -306,36 +410,22 @@ impl VtopClient {
     }
     async fn get_csrf_for_cookie_set(&mut self) -> VtopResult<()> {
         let url = format!("{}/vtop/open/page", self.config.base_url);
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
+        let response = self.send(self.client.get(url)).await?;
 
         if !response.status().is_success() || response.url().to_string().contains("login") {
-            return Err(VtopError::VtopServerError);
+            let (url, status) = (response.url().clone(), response.status());
+            return Err(VtopError::server(&url, status, ""));
         }
-        self.current_page = Some(response.text().await.map_err(|_| VtopError::NetworkError)?);
+        self.current_page = Some(response.text().await.map_err(VtopError::network)?);
         let _ = self.extract_csrf_token();
         Ok(())
     }
     async fn perform_login(&mut self, captcha_answer: &str) -> VtopResult<()> {
         let csrf = self
-            .session
+            .session()
             .get_csrf_token()
             .ok_or(VtopError::SessionExpired)?;
 
-        // Note: We use reqwest's automatic encoding via multipart/form-data or simple form.
-        // But the existing code manually constructs a string body with `application/x-www-form-urlencoded`.
-        // If the password contains special chars, `urlencoding::encode` should be correct.
-        // However, if the server expects raw text (unlikely) or double encoding issue exists.
-        
-        // Let's try to remove manual encoding if possible, OR check if `reqwest` `.form()` can be used.
-        // Since `client.post(url).body(login_data)` is used, we must encode manually.
-        // BUT, `urlencoding` crate might not be encoding exactly as Java/Tomcat expects?
-        // Let's try using `form` method instead which handles encoding standardly.
-
         let params = [
             ("_csrf", csrf.as_str()),
             ("username", &self.username),
@@ -343,65 +433,75 @@ impl VtopClient {
             ("captchaStr", captcha_answer),
         ];
 
-        // Debug: Print what we are sending
-        eprintln!("Login attempt with:");
-        eprintln!("Username: {}", self.username);
-        eprintln!("Captcha Answer: {}", captcha_answer);
-        // Do not print password for security, even in logs, unless necessary for local debug
-        
+        tracing::debug!(
+            username = %Redacted::reg_no(&self.username),
+            captcha = %Redacted::secret(captcha_answer),
+            "submitting login form"
+        );
+
         let url = format!("{}/vtop/login", self.config.base_url);
 
-        let response = self
-            .client
-            .post(url)
-            .form(&params)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-        
+        let response = self.send(self.client.post(url).form(&params)).await?;
+
         let response_url = response.url().to_string();
         let status = response.status();
-        let response_text = response.text().await.map_err(|_| VtopError::NetworkError)?;
+        let response_text = response.text().await.map_err(VtopError::network)?;
 
-        eprintln!("Response URL: {}", response_url);
-        eprintln!("Response Status: {}", status);
+        tracing::debug!(url = %response_url, status = %status, "login response received");
 
         if response_url.contains("error") || response_text.contains("alert") {
-            // Debug: Write the failure page to a file for inspection
-            let _ = std::fs::write("login_fail.html", &response_text);
-            eprintln!("Login failed. Full page written to login_fail.html ({} bytes)", response_text.len());
-            
+            self.capture_page("login_fail", &response_text);
+
             if response_text.contains("Invalid Captcha") {
-                eprintln!("Detected: Invalid Captcha");
-                return Err(VtopError::AuthenticationFailed(
+                tracing::info!("login rejected: invalid captcha");
+                Err(VtopError::AuthenticationFailed(
                     "Invalid Captcha".to_string(),
-                ));
+                ))
             } else if response_text.contains("Invalid LoginId/Password")
                 || response_text.contains("Invalid  Username/Password")
                 || response_text.contains("User does not exist")
                 || response_text.contains("Invalid credentials")
                 || response_text.contains("Invalid credentials")
             {
-                eprintln!("Detected: Invalid Credentials");
-                return Err(VtopError::InvalidCredentials);
+                tracing::info!("login rejected: invalid credentials");
+                Err(VtopError::InvalidCredentials)
             } else {
-                 // Try to find any alert div
-                 if let Some(alert_idx) = response_text.find("alert") {
-                     let snippet: String = response_text[alert_idx..].chars().take(500).collect();
-                     eprintln!("Found alert snippet: {}", snippet);
-                 }
-                 return Err(VtopError::AuthenticationFailed(format!("Unknown error content. URL: {}", response_url)));
+                tracing::warn!(
+                    url = %response_url,
+                    bytes = response_text.len(),
+                    "login failed with an unrecognised page"
+                );
+                Err(VtopError::AuthenticationFailed(format!(
+                    "Unknown error content. URL: {}",
+                    response_url
+                )))
             }
         } else {
             self.current_page = Some(response_text);
-            self.extract_csrf_token()?;
-            self.get_regno()?;
+            if let Err(e) = self.extract_csrf_token().and_then(|_| self.get_regno()) {
+                self.capture_page("login_parse", self.current_page.as_deref().unwrap_or_default());
+                return Err(e);
+            }
 
             self.current_page = None;
             self.captcha_data = None;
             Ok(())
         }
     }
+
+    // Writes a scrubbed copy of `page` to the configured debug sink, if any.
+    // Called wherever VTOP answered with a page we could not use.
+    fn capture_page(&self, label: &str, page: &str) {
+        let Some(sink) = &self.config.debug_capture else {
+            return;
+        };
+        let csrf = self.session().get_csrf_token().unwrap_or_default();
+        let scrubbed = scrub_page(page, &[&self.password, &self.username, &csrf]);
+        match sink.capture(label, &scrubbed) {
+            Ok(path) => tracing::debug!(path = %path.display(), "captured {} page", label),
+            Err(e) => tracing::warn!(error = %e, "could not capture {} page", label),
+        }
+    }
     async fn load_login_page(&mut self, k: bool) -> VtopResult<()> {
         if k {
             self.load_initial_page().await?;
@@ -410,41 +510,32 @@ impl VtopClient {
         #[allow(non_snake_case)]
         let Max_RELOAD_ATTEMPTS = 8;
         let csrf = self
-            .session
+            .session()
             .get_csrf_token()
             .ok_or(VtopError::SessionExpired)?;
         let url = format!("{}/vtop/prelogin/setup", self.config.base_url);
         let body = format!("_csrf={}&flag=VTOP", csrf);
         for _ in 0..Max_RELOAD_ATTEMPTS {
-            let response = self
-                .client
-                .post(&url)
-                .body(body.clone())
-                .send()
-                .await
-                .map_err(|_| VtopError::NetworkError)?;
-            if !response.status().is_success() {
-                return Err(VtopError::VtopServerError);
+            let response = self.send(self.client.post(&url).body(body.clone())).await?;
+            let (status, page_url) = (response.status(), response.url().clone());
+            let text = response.text().await.map_err(VtopError::network)?;
+            if !status.is_success() {
+                self.capture_page("login_setup", &text);
+                return Err(VtopError::server(&page_url, status, &text));
             }
-            let text = response.text().await.map_err(|_| VtopError::NetworkError)?;
             if text.contains("base64,") {
-                eprintln!("Login page loaded. Searching for form fields...");
-                if let Some(form_idx) = text.find("<form") {
-                     let end_idx = text[form_idx..].find("</form>").unwrap_or(2000);
-                     eprintln!("Full Login Form: {}", &text[form_idx..form_idx + end_idx + 7]);
-                }
+                tracing::debug!("login page with captcha loaded");
                 self.current_page = Some(text);
                 self.extract_captcha_data()?;
                 self.extract_csrf_token()?; // CRITICAL: Extract the NEW CSRF token for the login POST
                 break;
             }
-            eprintln!("No captcha found in response. Text snippet: {}", text.chars().take(500).collect::<String>());
-            println!("No captcha found Reloading the page ");
+            tracing::debug!(bytes = text.len(), "no captcha in login page, reloading");
         }
         Ok(())
     }
     fn extract_captcha_data(&mut self) -> VtopResult<()> {
-        let document = Html::parse_document(&self.current_page.as_ref().ok_or(
+        let document = Html::parse_document(self.current_page.as_ref().ok_or(
             VtopError::ParseError("Current page not found at captcha extration".into()),
         )?);
         let selector = Selector::parse("img.form-control.img-fluid.bg-light.border-0").unwrap();
@@ -464,7 +555,7 @@ impl VtopClient {
     }
 
     fn get_regno(&mut self) -> VtopResult<()> {
-        let document = Html::parse_document(&self.current_page.as_ref().ok_or(
+        let document = Html::parse_document(self.current_page.as_ref().ok_or(
             VtopError::ParseError("Current page not found at captcha extration".into()),
         )?);
         let selector = Selector::parse("input[type=hidden][name=authorizedIDX]").unwrap();
@@ -479,31 +570,32 @@ impl VtopClient {
     }
     async fn solve_captcha(&self, captcha_data: &str) -> VtopResult<String> {
         let url_safe_encoded = URL_SAFE.encode(captcha_data.as_bytes());
-        let captcha_url = format!("https://cap.va.synaptic.gg/captcha");
+        let captcha_url = "https://cap.va.synaptic.gg/captcha";
 
         #[derive(Serialize)]
         struct PostData {
             imgstring: String,
         }
 
-        let client_for_post = reqwest::Client::new();
         let post_data = PostData {
             imgstring: url_safe_encoded,
         };
-        let response = client_for_post
-            .post(captcha_url)
-            .json(&post_data)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
-
-        if !response.status().is_success() {
-            return Err(VtopError::NetworkError);
+        let response = self
+            .send_to(
+                Upstream::CaptchaSolver,
+                self.solver.post(captcha_url).json(&post_data),
+            )
+            .await?;
+
+        let (status, url) = (response.status(), response.url().clone());
+        let text = response.text().await.map_err(VtopError::network)?;
+        if !status.is_success() {
+            return Err(VtopError::server(&url, status, &text));
         }
-        response.text().await.map_err(|_| VtopError::NetworkError)
+        Ok(text)
     }
     fn extract_csrf_token(&mut self) -> VtopResult<()> {
-        let document = Html::parse_document(&self.current_page.as_ref().ok_or(
+        let document = Html::parse_document(self.current_page.as_ref().ok_or(
             VtopError::ParseError("Current page not found at csrf extration".into()),
         )?);
         let selector = Selector::parse("input[name='_csrf']").unwrap();
@@ -512,28 +604,20 @@ impl VtopClient {
             .next()
             .and_then(|element| element.value().attr("value"))
             .ok_or(VtopError::ParseError("CSRF token not found".to_string()))?;
-        self.session.set_csrf_token(csrf_token.to_string());
+        self.session().set_csrf_token(csrf_token.to_string());
         Ok(())
     }
     async fn load_initial_page(&mut self) -> VtopResult<()> {
         let url = format!("{}/vtop/open/page", self.config.base_url);
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .await
-            .map_err(|_| VtopError::NetworkError)?;
+        let response = self.send(self.client.get(url)).await?;
 
-        if !response.status().is_success() {
-            return Err(VtopError::VtopServerError);
-        }
-        let text = response.text().await.map_err(|_| VtopError::NetworkError)?;
-        
-        eprintln!("Initial page loaded. Form snippet:");
-        if let Some(form_idx) = text.find("<form") {
-            let end_idx = text[form_idx..].find("</form>").unwrap_or(2000);
-            eprintln!("{}", &text[form_idx..form_idx + end_idx + 7]);
+        let (status, page_url) = (response.status(), response.url().clone());
+        let text = response.text().await.map_err(VtopError::network)?;
+        if !status.is_success() {
+            self.capture_page("open_page", &text);
+            return Err(VtopError::server(&page_url, status, &text));
         }
+        tracing::debug!(bytes = text.len(), "initial page loaded");
 
         self.current_page = Some(text);
 
@@ -556,21 +640,26 @@ impl VtopClient {
     pub fn with_config(
         config: VtopConfig,
         session: SessionManager,
+        limiter: Arc<RequestLimiter>,
         username: String,
         password: String,
-    ) -> Self {
+    ) -> VtopResult<Self> {
+        let config = config.normalized();
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let client = Self::make_client(session.get_cookie_store());
-            Self {
-                client: client,
-                config: config,
-                session: session,
+            let client = Self::make_client(&config, session.get_cookie_store())?;
+            let solver = Self::make_solver_client(&config)?;
+            Ok(Self {
+                client,
+                solver,
+                config,
+                session: Mutex::new(session),
+                limiter,
                 current_page: None,
-                username: username,
-                password: password,
+                username,
+                password,
                 captcha_data: None,
-            }
+            })
         }
         #[cfg(target_arch = "wasm32")]
         {
@@ -582,27 +671,29 @@ impl VtopClient {
             let client = reqwest::Client::builder()
                 .default_headers(headers)
                 .build()
-                .unwrap();
-            Self {
+                .map_err(|e| VtopError::ConfigurationError(e.to_string()))?;
+            Ok(Self {
                 client: client,
+                solver: reqwest::Client::new(),
                 config: config,
-                session: session,
+                session: Mutex::new(session),
+                limiter: limiter,
                 current_page: None,
                 username: username,
                 password: password,
                 captcha_data: None,
-            }
+            })
         }
     }
     #[cfg(not(target_arch = "wasm32"))]
-    fn make_client(cookie_store: Arc<Jar>) -> Client {
+    fn make_client(config: &VtopConfig, cookie_store: Arc<Jar>) -> VtopResult<Client> {
         let mut headers = HeaderMap::new();
 
         headers.insert(
             USER_AGENT,
-            HeaderValue::from_static(
-                "Mozilla/5.0 (Linux; U; Linux x86_64; en-US) Gecko/20100101 Firefox/130.5",
-            ),
+            HeaderValue::from_str(&config.user_agent).map_err(|_| {
+                VtopError::ConfigurationError("user agent is not a valid header value".into())
+            })?,
         );
         headers.insert(
             "Accept",
@@ -624,15 +715,50 @@ impl VtopClient {
         headers.insert("Sec-Fetch-Site", HeaderValue::from_static("same-origin"));
         headers.insert("Sec-Fetch-User", HeaderValue::from_static("?1"));
         headers.insert("Priority", HeaderValue::from_static("u=0, i"));
+        for (name, value) in &config.extra_headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                VtopError::ConfigurationError(format!("invalid header name {}", name))
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                VtopError::ConfigurationError(format!("invalid value for header {}", name))
+            })?;
+            headers.insert(name, value);
+        }
 
-        let client = reqwest::Client::builder()
+        let builder = reqwest::Client::builder()
             .default_headers(headers)
             .cookie_store(true)
-            .cookie_provider(cookie_store)
-            .danger_accept_invalid_certs(true)
+            .cookie_provider(cookie_store);
+        Self::configured(config, builder, &config.tls)?
             .build()
-            .unwrap();
-        return client;
+            .map_err(|e| VtopError::ConfigurationError(e.to_string()))
+    }
+
+    fn make_solver_client(config: &VtopConfig) -> VtopResult<Client> {
+        let builder = reqwest::Client::builder().user_agent(config.user_agent.as_str());
+        Self::configured(config, builder, &config.tls.for_other_hosts())?
+            .build()
+            .map_err(|e| VtopError::ConfigurationError(e.to_string()))
     }
-}
 
+    // Timeouts, proxy and TLS policy from `config`.
+    fn configured(
+        config: &VtopConfig,
+        builder: reqwest::ClientBuilder,
+        tls: &TlsPolicy,
+    ) -> VtopResult<reqwest::ClientBuilder> {
+        let mut builder = builder.timeout(Duration::from_secs(config.timeout_seconds));
+        if let Some(seconds) = config.connect_timeout_seconds {
+            builder = builder.connect_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(seconds) = config.read_timeout_seconds {
+            builder = builder.read_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(proxy) = &config.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| VtopError::ConfigurationError(format!("invalid proxy: {}", e)))?;
+            builder = builder.proxy(proxy);
+        }
+        tls.configure(builder)
+    }
+}
@@ -1,10 +1,31 @@
-use super::{session_manager::SessionManager, vtop_client::VtopClient};
+use super::{
+    debug_capture::DebugCaptureConfig,
+    request_limiter::RequestLimiter,
+    session_manager::SessionManager,
+    tls_policy::TlsPolicy,
+    vtop_client::VtopClient,
+    vtop_errors::{VtopError, VtopResult},
+};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct VtopConfig {
     pub base_url: String,
+    // Total time allowed for a request, from connect until the body is read.
     pub timeout_seconds: u64,
+    pub connect_timeout_seconds: Option<u64>,
+    pub read_timeout_seconds: Option<u64>,
     pub user_agent: String,
+    pub extra_headers: BTreeMap<String, String>,
+    pub proxy: Option<String>,
+    pub tls: TlsPolicy,
+    // Off unless set: error pages and pages that could not be read are
+    // written here after redaction.
+    pub debug_capture: Option<DebugCaptureConfig>,
 }
 
 impl Default for VtopConfig {
@@ -14,17 +35,111 @@ impl Default for VtopConfig {
         {}
 
         Self {
-            base_url: base_url,
+            base_url,
             timeout_seconds: 30,
+            connect_timeout_seconds: Some(10),
+            read_timeout_seconds: None,
             user_agent: "Mozilla/5.0 (Linux; U; Linux x86_64; en-US) Gecko/20100101 Firefox/130.5"
                 .to_string(),
+            extra_headers: BTreeMap::new(),
+            proxy: None,
+            tls: TlsPolicy::default(),
+            debug_capture: None,
         }
     }
 }
 
+impl VtopConfig {
+    pub fn from_toml_str(text: &str) -> VtopResult<Self> {
+        toml::from_str(text).map_err(|e| VtopError::ConfigurationError(e.to_string()))
+    }
+
+    // The configuration a client is built with: `base_url` loses surrounding
+    // whitespace and trailing slashes, since paths are appended as `/vtop/...`.
+    pub fn normalized(mut self) -> Self {
+        self.base_url = self.base_url.trim().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> VtopResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            VtopError::ConfigurationError(format!("{}: {}", path.display(), e))
+        })?;
+        Self::from_toml_str(&text)
+    }
+
+    // Loads the file named by `VTOP_CONFIG` (or `vtop.toml` in the working
+    // directory when present) and then applies `VTOP_*` environment overrides.
+    pub fn load() -> VtopResult<Self> {
+        let mut config = match std::env::var("VTOP_CONFIG") {
+            Ok(path) => Self::from_file(path)?,
+            Err(_) if Path::new("vtop.toml").exists() => Self::from_file("vtop.toml")?,
+            Err(_) => Self::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    pub fn apply_env(&mut self) -> VtopResult<()> {
+        fn seconds(name: &str, value: String) -> VtopResult<u64> {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| VtopError::ConfigurationError(format!("{} must be a number", name)))
+        }
+
+        if let Ok(value) = std::env::var("VTOP_BASE_URL") {
+            self.base_url = value;
+        }
+        if let Ok(value) = std::env::var("VTOP_TIMEOUT_SECONDS") {
+            self.timeout_seconds = seconds("VTOP_TIMEOUT_SECONDS", value)?;
+        }
+        if let Ok(value) = std::env::var("VTOP_CONNECT_TIMEOUT_SECONDS") {
+            self.connect_timeout_seconds = Some(seconds("VTOP_CONNECT_TIMEOUT_SECONDS", value)?);
+        }
+        if let Ok(value) = std::env::var("VTOP_READ_TIMEOUT_SECONDS") {
+            self.read_timeout_seconds = Some(seconds("VTOP_READ_TIMEOUT_SECONDS", value)?);
+        }
+        if let Ok(value) = std::env::var("VTOP_USER_AGENT") {
+            self.user_agent = value;
+        }
+        if let Ok(value) = std::env::var("VTOP_PROXY") {
+            self.proxy = Some(value).filter(|v| !v.trim().is_empty());
+        }
+        if let Ok(value) = std::env::var("VTOP_TLS_PINS") {
+            self.tls = TlsPolicy::pinned(value.split(',').filter(|p| !p.trim().is_empty()));
+        }
+        if let Ok(value) = std::env::var("VTOP_DANGER_ACCEPT_INVALID_CERTS") {
+            if matches!(value.trim(), "1" | "true" | "yes") {
+                self.tls = TlsPolicy::DangerAcceptInvalidCerts;
+            }
+        }
+        if let Ok(value) = std::env::var("VTOP_DEBUG_CAPTURE_DIR") {
+            let mut capture = DebugCaptureConfig::new(value, 20);
+            if let Ok(max) = std::env::var("VTOP_DEBUG_CAPTURE_MAX_FILES") {
+                capture.max_files = max.trim().parse().map_err(|_| {
+                    VtopError::ConfigurationError(
+                        "VTOP_DEBUG_CAPTURE_MAX_FILES must be a number".into(),
+                    )
+                })?;
+            }
+            self.debug_capture = Some(capture).filter(|c| !c.directory.trim().is_empty());
+        }
+        Ok(())
+    }
+}
+
 pub struct VtopClientBuilder {
     config: VtopConfig,
     session: SessionManager,
+    limiter: Option<Arc<RequestLimiter>>,
+}
+
+impl Default for VtopClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl VtopClientBuilder {
@@ -32,16 +147,78 @@ impl VtopClientBuilder {
         Self {
             config: VtopConfig::default(),
             session: SessionManager::new(),
+            limiter: None,
         }
     }
 
+    pub fn config(mut self, config: VtopConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.config.base_url = base_url.into();
+        self
+    }
+
     pub fn timeout(mut self, seconds: u64) -> Self {
         self.config.timeout_seconds = seconds;
         self
     }
 
-    pub fn build(self, username: String, password: String) -> VtopClient {
-        VtopClient::with_config(self.config, self.session, username.to_uppercase(), password)
+    pub fn connect_timeout(mut self, seconds: u64) -> Self {
+        self.config.connect_timeout_seconds = Some(seconds);
+        self
+    }
+
+    pub fn read_timeout(mut self, seconds: u64) -> Self {
+        self.config.read_timeout_seconds = Some(seconds);
+        self
     }
-}
 
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.config.extra_headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.config.proxy = Some(url.into());
+        self
+    }
+
+    pub fn tls_policy(mut self, policy: TlsPolicy) -> Self {
+        self.config.tls = policy;
+        self
+    }
+
+    pub fn danger_accept_invalid_certs(mut self) -> Self {
+        self.config.tls = TlsPolicy::DangerAcceptInvalidCerts;
+        self
+    }
+
+    pub fn debug_capture(mut self, directory: impl Into<String>, max_files: usize) -> Self {
+        self.config.debug_capture = Some(DebugCaptureConfig::new(directory, max_files));
+        self
+    }
+
+    // Clients share `RequestLimiter::global()` unless a dedicated limiter is set.
+    pub fn limiter(mut self, limiter: Arc<RequestLimiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    pub fn build(self, username: String, password: String) -> VtopResult<VtopClient> {
+        VtopClient::with_config(
+            self.config,
+            self.session,
+            self.limiter.unwrap_or_else(RequestLimiter::global),
+            username.to_uppercase(),
+            password,
+        )
+    }
+}
@@ -1,11 +1,141 @@
-
 use serde::Serialize;
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Serialize)]
+use super::redact::scrub_page;
+
+// Where a network or server error happened. `source` keeps the underlying
+// error for `std::error::Error::source`, `detail` is its message so the
+// context survives serialization. URLs are kept without their query, and
+// the start of the page has its CSRF tokens scrubbed; the page can still
+// name the student, so it is only shown by `Display`, for logs.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErrorContext {
+    pub endpoint: Option<String>,
+    pub url: Option<String>,
+    pub status: Option<u16>,
+    #[serde(skip)]
+    pub snippet: Option<String>,
+    pub detail: Option<String>,
+    pub timeout: bool,
+    #[serde(skip)]
+    pub source: Option<Arc<dyn std::error::Error + Send + Sync>>,
+}
+
+const SNIPPET_LEN: usize = 200;
+
+impl ErrorContext {
+    pub fn from_reqwest(error: reqwest::Error) -> Self {
+        let url = error.url().cloned();
+        let error = error.without_url();
+        Self {
+            endpoint: url.as_ref().map(endpoint_name),
+            url: url.as_ref().map(without_query),
+            status: error.status().map(|s| s.as_u16()),
+            snippet: None,
+            detail: Some(error.to_string()),
+            timeout: error.is_timeout(),
+            source: Some(Arc::new(error)),
+        }
+    }
 
+    pub fn from_response(url: &reqwest::Url, status: reqwest::StatusCode, body: &str) -> Self {
+        let snippet: String = scrub_page(body, &[])
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .take(SNIPPET_LEN)
+            .collect();
+        Self {
+            endpoint: Some(endpoint_name(url)),
+            url: Some(without_query(url)),
+            status: Some(status.as_u16()),
+            snippet: Some(snippet).filter(|s| !s.is_empty()),
+            ..Self::default()
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(endpoint) = &self.endpoint {
+            write!(f, " at {}", endpoint)?;
+        }
+        if let Some(status) = self.status {
+            write!(f, " (HTTP {})", status)?;
+        }
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        if let Some(snippet) = &self.snippet {
+            write!(f, " [{}]", snippet)?;
+        }
+        Ok(())
+    }
+}
+
+fn without_query(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+    url.to_string()
+}
+
+fn endpoint_name(url: &reqwest::Url) -> String {
+    url.path_segments()
+        .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+        .unwrap_or(url.path())
+        .to_string()
+}
+
+// Why a form was refused before anything was sent to VTOP.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum FormError {
+    MissingField(String),
+    // Dates are `dd-Mon-yyyy` as VTOP shows them, e.g. `05-Mar-2025`.
+    InvalidDate(String),
+    // Times are 24-hour `HH:MM`.
+    InvalidTime(String),
+    InvalidPhone(String),
+    EndsBeforeStart,
+    OutingSpansDays,
+    // Feedback answers, with the question's text; an unknown question only
+    // has the id the caller gave.
+    UnansweredQuestion(String),
+    InvalidAnswer(String),
+    UnknownQuestion(String),
+}
+
+impl std::fmt::Display for FormError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormError::MissingField(field) => write!(f, "Please fill in the {}.", field),
+            FormError::InvalidDate(date) => {
+                write!(f, "\"{}\" is not a date like 05-Mar-2025.", date)
+            }
+            FormError::InvalidTime(time) => write!(f, "\"{}\" is not a time like 18:30.", time),
+            FormError::InvalidPhone(phone) => {
+                write!(f, "\"{}\" is not a 10 digit phone number.", phone)
+            }
+            FormError::EndsBeforeStart => write!(f, "The return must be after the departure."),
+            FormError::OutingSpansDays => {
+                write!(f, "An outing has to end on the day it starts; apply for leave instead.")
+            }
+            FormError::UnansweredQuestion(question) => write!(f, "Please answer \"{}\".", question),
+            FormError::InvalidAnswer(question) => {
+                write!(f, "That is not a valid answer to \"{}\".", question)
+            }
+            FormError::UnknownQuestion(question) => {
+                write!(f, "\"{}\" is not a question on this form.", question)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub enum VtopError {
-    NetworkError,
-    VtopServerError,
+    NetworkError(Box<ErrorContext>),
+    VtopServerError(Box<ErrorContext>),
     AuthenticationFailed(String),
     RegistrationParsingError,
     InvalidCredentials,
@@ -14,13 +144,107 @@ pub enum VtopError {
     ConfigurationError(String),
     CaptchaRequired,
     InvalidResponse,
+    QueueTimeout,
+    CircuitOpen,
+    // Caught before submitting, so VTOP never saw the request.
+    InvalidForm(FormError),
+    // VTOP turned the request down; holds VTOP's own explanation.
+    RequestRejected(String),
+    // Writing a download to disk or to the caller's writer failed.
+    StorageError(String),
+    // The caller's `CancelToken` fired before the call finished.
+    Cancelled,
+}
+
+impl VtopError {
+    pub fn network(error: reqwest::Error) -> Self {
+        VtopError::NetworkError(Box::new(ErrorContext::from_reqwest(error)))
+    }
+
+    pub fn server(url: &reqwest::Url, status: reqwest::StatusCode, body: &str) -> Self {
+        VtopError::VtopServerError(Box::new(ErrorContext::from_response(url, status, body)))
+    }
+
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            VtopError::NetworkError(context) | VtopError::VtopServerError(context) => {
+                Some(context)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        self.context().and_then(|c| c.status)
+    }
+
+    // Whether repeating the same call later can reasonably succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            VtopError::NetworkError(_) | VtopError::QueueTimeout | VtopError::CaptchaRequired => {
+                true
+            }
+            VtopError::VtopServerError(context) => {
+                context.status.map(|s| s >= 500 || s == 429).unwrap_or(true)
+            }
+            VtopError::AuthenticationFailed(msg) => msg.contains("Invalid Captcha"),
+            _ => false,
+        }
+    }
+
+    // Whether the user has to log in again or fix their credentials.
+    pub fn is_auth_problem(&self) -> bool {
+        matches!(
+            self,
+            VtopError::AuthenticationFailed(_)
+                | VtopError::InvalidCredentials
+                | VtopError::SessionExpired
+                | VtopError::RegistrationParsingError
+        )
+    }
+
+    // A short message that is safe to show to students; use `Display` for logs.
+    pub fn user_message(&self) -> String {
+        match self {
+            VtopError::NetworkError(context) if context.timeout => {
+                "VTOP took too long to respond. Please try again.".into()
+            }
+            VtopError::NetworkError(_) => {
+                "Could not reach VTOP. Check your internet connection.".into()
+            }
+            VtopError::VtopServerError(_) => {
+                "VTOP is having problems right now. Please try again later.".into()
+            }
+            VtopError::AuthenticationFailed(msg) if msg.contains("Invalid Captcha") => {
+                "Captcha could not be solved. Please try again.".into()
+            }
+            VtopError::AuthenticationFailed(_) => "Login to VTOP failed.".into(),
+            VtopError::RegistrationParsingError => {
+                "Logged in, but VTOP did not return your registration number.".into()
+            }
+            VtopError::InvalidCredentials => "Invalid username or password.".into(),
+            VtopError::SessionExpired => "Your VTOP session expired. Please log in again.".into(),
+            VtopError::ParseError(_) | VtopError::InvalidResponse => {
+                "VTOP returned a page we could not read.".into()
+            }
+            VtopError::ConfigurationError(_) => "The app is misconfigured.".into(),
+            VtopError::CaptchaRequired => "VTOP did not provide a captcha. Please retry.".into(),
+            VtopError::QueueTimeout | VtopError::CircuitOpen => {
+                "VTOP is busy. Please try again in a minute.".into()
+            }
+            VtopError::InvalidForm(problem) => problem.to_string(),
+            VtopError::RequestRejected(msg) => msg.clone(),
+            VtopError::StorageError(_) => "Could not save the downloaded file.".into(),
+            VtopError::Cancelled => "Cancelled.".into(),
+        }
+    }
 }
 
 impl std::fmt::Display for VtopError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            VtopError::NetworkError => write!(f, "Network connection error"),
-            VtopError::VtopServerError => write!(f, "VTOP server error"),
+            VtopError::NetworkError(context) => write!(f, "Network connection error{}", context),
+            VtopError::VtopServerError(context) => write!(f, "VTOP server error{}", context),
             VtopError::AuthenticationFailed(msg) => write!(f, "Authentication failed: {}", msg),
             VtopError::RegistrationParsingError => write!(f, "Failed to parse registration number"),
             VtopError::InvalidCredentials => write!(f, "Invalid username or password"),
@@ -29,11 +253,24 @@ impl std::fmt::Display for VtopError {
             VtopError::ConfigurationError(msg) => write!(f, "Configuration error: {}", msg),
             VtopError::CaptchaRequired => write!(f, "Captcha verification required"),
             VtopError::InvalidResponse => write!(f, "Invalid response from server"),
+            VtopError::QueueTimeout => write!(f, "Timed out waiting for a VTOP request slot"),
+            VtopError::CircuitOpen => {
+                write!(f, "VTOP is temporarily unavailable, requests are paused")
+            }
+            VtopError::InvalidForm(problem) => write!(f, "Invalid form: {}", problem),
+            VtopError::RequestRejected(msg) => write!(f, "Rejected by VTOP: {}", msg),
+            VtopError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            VtopError::Cancelled => write!(f, "Cancelled by the caller"),
         }
     }
 }
 
-impl std::error::Error for VtopError {}
+impl std::error::Error for VtopError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.context()
+            .and_then(|c| c.source.as_deref())
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
 
 pub type VtopResult<T> = Result<T, VtopError>;
-
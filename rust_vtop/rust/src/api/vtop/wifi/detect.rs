@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use futures::future::join_all;
+use reqwest::{header::LOCATION, redirect::Policy, Url};
+use serde::Serialize;
+use tokio::net::TcpStream;
+
+use super::profile::{ConnectivityCheck, NetworkProfiles, PortalKind};
+use crate::api::vtop::paraser::wifi_portal::{fingerprint, next_hop, PortalVendor};
+use crate::api::vtop::tls_policy::TlsPolicy;
+use crate::api::vtop::vtop_errors::{VtopError, VtopResult};
+
+const MAX_HOPS: usize = 5;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+const REACH_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl PortalVendor {
+    pub fn kind(self) -> Option<PortalKind> {
+        match self {
+            PortalVendor::Fortinet => Some(PortalKind::Fortinet),
+            PortalVendor::Sophos => Some(PortalKind::Sophos),
+            PortalVendor::Unknown => None,
+        }
+    }
+}
+
+impl From<PortalKind> for PortalVendor {
+    fn from(kind: PortalKind) -> Self {
+        match kind {
+            PortalKind::Fortinet => PortalVendor::Fortinet,
+            PortalKind::Sophos => PortalVendor::Sophos,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Connectivity {
+    Online,
+    CaptivePortal,
+    // No connectivity check got through and nothing intercepted them.
+    Offline,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortalDetection {
+    pub connectivity: Connectivity,
+    pub vendor: PortalVendor,
+    // Last page of the portal's redirect chain, when one was seen.
+    pub login_url: Option<String>,
+    // Network profile for the portal. Also set while online when a
+    // profile's portal host is reachable, so we know whom to log out from.
+    pub profile: Option<String>,
+}
+
+enum Probe {
+    Online,
+    Intercepted { url: Url, vendor: PortalVendor },
+    Failed,
+}
+
+// Probes every connectivity check at once, follows whatever intercepted
+// them to the portal's login page and works out which portal it is. Fails
+// only when `tls` cannot be applied.
+pub async fn detect_portal(
+    profiles: &NetworkProfiles,
+    tls: &TlsPolicy,
+) -> VtopResult<PortalDetection> {
+    let client = tls
+        .configure(
+            reqwest::Client::builder()
+                .timeout(PROBE_TIMEOUT)
+                .redirect(Policy::none()),
+        )?
+        .build()
+        .map_err(|e| VtopError::ConfigurationError(format!("portal probe client: {}", e)))?;
+
+    let probes = join_all(
+        profiles
+            .connectivity_checks
+            .iter()
+            .map(|check| probe(&client, check)),
+    );
+    let (probes, reachable) = tokio::join!(probes, reachable_profile(profiles));
+
+    if probes.iter().any(|p| matches!(p, Probe::Online)) {
+        return Ok(PortalDetection {
+            connectivity: Connectivity::Online,
+            vendor: vendor_of(profiles, reachable.as_deref()),
+            login_url: None,
+            profile: reachable,
+        });
+    }
+
+    // Prefer a probe that led to a portal we recognise.
+    let intercepted = probes
+        .into_iter()
+        .filter_map(|p| match p {
+            Probe::Intercepted { url, vendor } => Some((url, vendor)),
+            _ => None,
+        })
+        .min_by_key(|(_, vendor)| *vendor == PortalVendor::Unknown);
+    let Some((url, vendor)) = intercepted else {
+        return Ok(PortalDetection {
+            connectivity: Connectivity::Offline,
+            vendor: vendor_of(profiles, reachable.as_deref()),
+            login_url: None,
+            profile: reachable,
+        });
+    };
+
+    let profile = profiles
+        .for_portal_url(url.as_str())
+        .or_else(|| vendor.kind().and_then(|kind| profiles.first_of(kind)))
+        .map(|profile| profile.name.clone())
+        .or(reachable);
+    let vendor = match vendor {
+        PortalVendor::Unknown => vendor_of(profiles, profile.as_deref()),
+        vendor => vendor,
+    };
+    Ok(PortalDetection {
+        connectivity: Connectivity::CaptivePortal,
+        vendor,
+        login_url: Some(url.to_string()),
+        profile,
+    })
+}
+
+async fn probe(client: &reqwest::Client, check: &ConnectivityCheck) -> Probe {
+    let Ok(mut url) = Url::parse(&check.url) else {
+        return Probe::Failed;
+    };
+    let Ok(response) = client.get(url.clone()).send().await else {
+        return Probe::Failed;
+    };
+    let status = response.status().as_u16();
+    let mut location = redirect_target(&response);
+    let mut page = response.text().await.unwrap_or_default();
+    if location.is_none() && check.is_expected(status, &page) {
+        return Probe::Online;
+    }
+
+    // Follow the chain to the actual login page; the vendor is whatever the
+    // first recognisable hop looks like.
+    let mut vendor = fingerprint(&url, &page);
+    for _ in 0..MAX_HOPS {
+        let Some(next) = location.take().or_else(|| next_hop(&page, &url)) else {
+            break;
+        };
+        url = next;
+        if vendor == PortalVendor::Unknown {
+            vendor = fingerprint(&url, "");
+        }
+        // The portal itself may not be fetchable (e.g. a self-signed
+        // certificate), in which case its URL is the best we have.
+        let Ok(response) = client.get(url.clone()).send().await else {
+            break;
+        };
+        location = redirect_target(&response);
+        page = response.text().await.unwrap_or_default();
+        if vendor == PortalVendor::Unknown {
+            vendor = fingerprint(&url, &page);
+        }
+    }
+    Probe::Intercepted { url, vendor }
+}
+
+fn redirect_target(response: &reqwest::Response) -> Option<Url> {
+    if !response.status().is_redirection() {
+        return None;
+    }
+    let location = response.headers().get(LOCATION)?.to_str().ok()?;
+    response.url().join(location).ok()
+}
+
+// First profile whose portal accepts a TCP connection, i.e. the campus
+// network we are on.
+async fn reachable_profile(profiles: &NetworkProfiles) -> Option<String> {
+    let attempts = profiles.profiles.iter().map(|profile| async move {
+        let url = Url::parse(&profile.base_url).ok()?;
+        let host = url.host_str()?.to_string();
+        let port = url.port_or_known_default()?;
+        match tokio::time::timeout(REACH_TIMEOUT, TcpStream::connect((host, port))).await {
+            Ok(Ok(_)) => Some(profile.name.clone()),
+            _ => None,
+        }
+    });
+    join_all(attempts).await.into_iter().flatten().next()
+}
+
+fn vendor_of(profiles: &NetworkProfiles, profile: Option<&str>) -> PortalVendor {
+    profile
+        .and_then(|name| profiles.get(name))
+        .map(|profile| profile.kind.into())
+        .unwrap_or(PortalVendor::Unknown)
+}
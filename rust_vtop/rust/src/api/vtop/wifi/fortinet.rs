@@ -0,0 +1,293 @@
+use std::time::Duration;
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+
+use super::profile::{NetworkProfile, NetworkProfiles, PortalMarkers};
+use super::{portal_client, rejected_certificate, unreachable_outcome, WifiOutcome, WifiPortal};
+use crate::api::vtop::tls_policy::TlsPolicy;
+use crate::api::vtop::vtop_errors::VtopResult;
+
+// FortiGate captive portal used in the academic blocks.
+#[derive(Debug, Clone)]
+pub struct FortinetPortal {
+    client: reqwest::Client,
+    profile: NetworkProfile,
+    internet_check_url: String,
+}
+
+// Where and when to refresh an authenticated FortiGate session. The portal
+// embeds a session token in the URL and drops the session if it is not
+// visited before the countdown runs out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeepaliveLink {
+    pub url: Url,
+    pub interval: Option<Duration>,
+}
+
+impl FortinetPortal {
+    pub fn new(tls: &TlsPolicy) -> VtopResult<Self> {
+        Self::from_profile(
+            NetworkProfile::vitap_academic(),
+            &NetworkProfiles::default(),
+            tls,
+        )
+    }
+
+    pub fn from_profile(
+        profile: NetworkProfile,
+        profiles: &NetworkProfiles,
+        tls: &TlsPolicy,
+    ) -> VtopResult<Self> {
+        Ok(Self {
+            client: portal_client(profile.tls(tls))?,
+            internet_check_url: profiles.internet_check_url.clone(),
+            profile,
+        })
+    }
+
+    // The academic profile at another address, e.g. a local stand-in.
+    pub fn with_base_url(tls: &TlsPolicy, base_url: impl Into<String>) -> VtopResult<Self> {
+        let mut profile = NetworkProfile::vitap_academic();
+        profile.base_url = base_url.into();
+        profile.logout_base_url = None;
+        Self::from_profile(profile, &NetworkProfiles::default(), tls)
+    }
+
+    pub fn profile(&self) -> &NetworkProfile {
+        &self.profile
+    }
+
+    // Logs in and returns the session's keepalive link.
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<KeepaliveLink, WifiOutcome> {
+        let page = match self
+            .client
+            .get(self.profile.url(&self.profile.login_path))
+            .send()
+            .await
+        {
+            Ok(response) => response.text().await.unwrap_or_default(),
+            Err(error) => return Err(self.unreachable(&error, WifiOutcome::NoNetwork).await),
+        };
+        let Some(magic) = self.parse_token(&page) else {
+            return Err(WifiOutcome::Unknown(
+                "The login page did not contain a session token.".into(),
+            ));
+        };
+
+        let fields = &self.profile.fields;
+        let body = format!(
+            "{}={}&{}={}&{}={}",
+            self.token_field(),
+            urlencoding::encode(&magic),
+            fields.username,
+            urlencoding::encode(username),
+            fields.password,
+            urlencoding::encode(password)
+        );
+        let submit_path = self
+            .profile
+            .submit_path
+            .as_deref()
+            .unwrap_or(&self.profile.login_path);
+        let response = match self
+            .client
+            .post(self.profile.url(submit_path))
+            .body(body)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(error) => {
+                return Err(self
+                    .unreachable(&error, WifiOutcome::PortalUnreachable)
+                    .await)
+            }
+        };
+        let page_url = response.url().clone();
+        let page = response.text().await.unwrap_or_default();
+        // The portal only renders a <p> when the login was rejected.
+        if let Some(message) = first_text(&page, "p") {
+            return Err(classify_failure(&self.profile.markers, message));
+        }
+
+        let link = match (
+            parse_keepalive(&page, &page_url),
+            &self.profile.keepalive_path,
+        ) {
+            (Some(link), _) => link,
+            (None, Some(path)) => KeepaliveLink {
+                url: Url::parse(&self.profile.url(path))
+                    .map_err(|e| WifiOutcome::Unknown(e.to_string()))?,
+                interval: None,
+            },
+            (None, None) => {
+                return Err(WifiOutcome::Unknown(
+                    "The portal did not provide a keepalive link.".into(),
+                ))
+            }
+        };
+        self.keepalive(&link).await
+    }
+
+    // Visits the keepalive link. Returns the link to use next time while the
+    // session is alive, `LoggedOut` once the portal has dropped it.
+    pub async fn keepalive(&self, link: &KeepaliveLink) -> Result<KeepaliveLink, WifiOutcome> {
+        let response = self
+            .client
+            .get(link.url.clone())
+            .send()
+            .await
+            .map_err(|error| match rejected_certificate(&error) {
+                true => WifiOutcome::CertificateRejected,
+                false => WifiOutcome::PortalUnreachable,
+            })?;
+        if !response.status().is_success() {
+            return Err(WifiOutcome::LoggedOut);
+        }
+        let page_url = response.url().clone();
+        let page = response.text().await.unwrap_or_default();
+        // An expired session is sent back to the login form.
+        if self.parse_token(&page).is_some() {
+            return Err(WifiOutcome::LoggedOut);
+        }
+        Ok(
+            parse_keepalive(&page, &page_url).unwrap_or_else(|| KeepaliveLink {
+                url: link.url.clone(),
+                interval: None,
+            }),
+        )
+    }
+
+    async fn unreachable(&self, error: &reqwest::Error, otherwise: WifiOutcome) -> WifiOutcome {
+        unreachable_outcome(&self.client, &self.internet_check_url, error, otherwise).await
+    }
+
+    fn token_field(&self) -> &str {
+        self.profile.fields.token.as_deref().unwrap_or("magic")
+    }
+
+    fn parse_token(&self, page: &str) -> Option<String> {
+        let document = Html::parse_document(page);
+        let selector = Selector::parse(&format!(
+            "input[type=hidden][name=\"{}\"]",
+            self.token_field()
+        ))
+        .ok()?;
+        document
+            .select(&selector)
+            .next()
+            .and_then(|element| element.value().attr("value").map(|value| value.to_string()))
+    }
+}
+
+impl WifiPortal for FortinetPortal {
+    fn name(&self) -> &'static str {
+        "fortinet"
+    }
+
+    async fn login(&self, username: &str, password: &str) -> WifiOutcome {
+        match self.authenticate(username, password).await {
+            Ok(_) => WifiOutcome::LoggedIn,
+            Err(outcome) => outcome,
+        }
+    }
+
+    async fn logout(&self, _username: &str) -> WifiOutcome {
+        match self.client.get(self.profile.logout_url()).send().await {
+            Ok(response) => {
+                let page = response.text().await.unwrap_or_default();
+                // FortiGate confirms a logout with an <h3> heading.
+                if first_text(&page, "h3").is_some()
+                    || PortalMarkers::matches(&self.profile.markers.logout_success, &page)
+                {
+                    WifiOutcome::LoggedOut
+                } else {
+                    WifiOutcome::Unknown("You are not logged in.".into())
+                }
+            }
+            Err(error) if rejected_certificate(&error) => WifiOutcome::CertificateRejected,
+            Err(_) => WifiOutcome::NoNetwork,
+        }
+    }
+
+    async fn status(&self, _username: &str) -> WifiOutcome {
+        // The portal only hands out a login token while we are not
+        // authenticated. Once we are, it either shows the keepalive page or
+        // lets the request through to wherever it redirects.
+        let login_url = self.profile.url(&self.profile.login_path);
+        let response = match self.client.get(&login_url).send().await {
+            Ok(response) => response,
+            Err(error) => return self.unreachable(&error, WifiOutcome::NoNetwork).await,
+        };
+        if !response.status().is_success() {
+            return WifiOutcome::Unknown(format!(
+                "The portal answered with HTTP {}.",
+                response.status().as_u16()
+            ));
+        }
+        let page_url = response.url().clone();
+        let page = response.text().await.unwrap_or_default();
+        let left_portal = Url::parse(&login_url)
+            .map(|login| login.host_str() != page_url.host_str())
+            .unwrap_or(false);
+        if self.parse_token(&page).is_some() {
+            WifiOutcome::LoggedOut
+        } else if left_portal || parse_keepalive(&page, &page_url).is_some() {
+            WifiOutcome::LoggedIn
+        } else {
+            WifiOutcome::Unknown("The portal did not say whether you are logged in.".into())
+        }
+    }
+}
+
+fn classify_failure(markers: &PortalMarkers, message: String) -> WifiOutcome {
+    if PortalMarkers::matches(&markers.limit_reached, &message) {
+        WifiOutcome::LimitReached
+    } else if PortalMarkers::matches(&markers.invalid_credentials, &message) {
+        WifiOutcome::InvalidCredentials
+    } else {
+        WifiOutcome::Unknown(message)
+    }
+}
+
+// FortiGate redirects to and refreshes the keepalive page from script
+// (`window.location = "...keepalive?token"`, `countDownTime = 2400`) or a
+// meta refresh (`content="2400;URL=...keepalive?token"`).
+fn parse_keepalive(page: &str, page_url: &Url) -> Option<KeepaliveLink> {
+    let marker = page.find("keepalive?")?;
+    let start = page[..marker]
+        .rfind(|c: char| c == '"' || c == '\'' || c == '=' || c.is_whitespace())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = marker
+        + page[marker..]
+            .find(|c: char| c == '"' || c == '\'' || c == '<' || c.is_whitespace())
+            .unwrap_or(page.len() - marker);
+    let url = page_url.join(&page[start..end]).ok()?;
+
+    let interval = number_after(page, "countDownTime")
+        .or_else(|| number_after(page, "content=\""))
+        .map(Duration::from_secs);
+    Some(KeepaliveLink { url, interval })
+}
+
+fn number_after(page: &str, key: &str) -> Option<u64> {
+    let rest = &page[page.find(key)? + key.len()..];
+    let rest = rest.trim_start_matches(|c: char| c == '=' || c.is_whitespace());
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+fn first_text(page: &str, sel: &str) -> Option<String> {
+    let document = Html::parse_document(page);
+    let selector = Selector::parse(sel).unwrap();
+    document
+        .select(&selector)
+        .next()
+        .map(|element| element.text().collect::<String>().trim().to_string())
+}
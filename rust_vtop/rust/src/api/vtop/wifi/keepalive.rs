@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+
+use super::fortinet::{FortinetPortal, KeepaliveLink};
+use super::WifiOutcome;
+
+#[derive(Debug, Clone)]
+pub struct KeepaliveOptions {
+    // Used when the portal does not say how long the session lasts.
+    pub fallback_interval: Duration,
+    // Lower bound on the refresh interval, whatever the portal says.
+    pub min_interval: Duration,
+    // Wait before logging in again after a failed attempt.
+    pub retry_delay: Duration,
+}
+
+impl Default for KeepaliveOptions {
+    fn default() -> Self {
+        Self {
+            fallback_interval: Duration::from_secs(300),
+            min_interval: Duration::from_secs(5),
+            retry_delay: Duration::from_secs(15),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum KeepaliveState {
+    LoggingIn,
+    Online {
+        keepalive_url: String,
+        refresh_in_seconds: u64,
+    },
+    SessionLost,
+    Retrying {
+        outcome: WifiOutcome,
+        retry_in_seconds: u64,
+    },
+    // Retrying cannot help, e.g. the password is wrong. The task has ended.
+    Failed(WifiOutcome),
+    Stopped,
+}
+
+// Stops the keepalive task when `stop` is called or the handle is dropped.
+pub struct KeepaliveHandle {
+    cancel: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl KeepaliveHandle {
+    pub async fn stop(self) {
+        let _ = self.cancel.send(true);
+        let _ = self.task.await;
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+// Keeps an academic-block session alive: logs in, visits the keepalive URL
+// the portal hands out at the interval it asks for, and logs in again with
+// the same credentials whenever the session is dropped.
+pub fn spawn_keepalive(
+    portal: FortinetPortal,
+    username: String,
+    password: String,
+    options: KeepaliveOptions,
+) -> (KeepaliveHandle, mpsc::UnboundedReceiver<KeepaliveState>) {
+    let (cancel, cancel_rx) = watch::channel(false);
+    let (states, states_rx) = mpsc::unbounded_channel();
+    let task = tokio::spawn(run(portal, username, password, options, cancel_rx, states));
+    (KeepaliveHandle { cancel, task }, states_rx)
+}
+
+async fn run(
+    portal: FortinetPortal,
+    username: String,
+    password: String,
+    options: KeepaliveOptions,
+    mut cancel: watch::Receiver<bool>,
+    states: mpsc::UnboundedSender<KeepaliveState>,
+) {
+    let mut reporter = Reporter { states, last: None };
+    let mut link: Option<KeepaliveLink> = None;
+
+    loop {
+        let wait = match link.take() {
+            None => {
+                reporter.report(KeepaliveState::LoggingIn);
+                match portal.authenticate(&username, &password).await {
+                    Ok(next) => {
+                        let wait = reporter.online(&next, &options);
+                        link = Some(next);
+                        wait
+                    }
+                    Err(
+                        outcome @ (WifiOutcome::InvalidCredentials
+                        | WifiOutcome::CertificateRejected),
+                    ) => {
+                        tracing::warn!(?outcome, "wifi keepalive stopped: retrying cannot help");
+                        reporter.report(KeepaliveState::Failed(outcome));
+                        return;
+                    }
+                    Err(outcome) => {
+                        tracing::debug!(?outcome, "wifi login failed, retrying");
+                        reporter.report(KeepaliveState::Retrying {
+                            outcome,
+                            retry_in_seconds: options.retry_delay.as_secs(),
+                        });
+                        options.retry_delay
+                    }
+                }
+            }
+            Some(current) => match portal.keepalive(&current).await {
+                Ok(next) => {
+                    let wait = reporter.online(&next, &options);
+                    link = Some(next);
+                    wait
+                }
+                Err(outcome) => {
+                    tracing::info!(?outcome, "wifi session lost, logging in again");
+                    reporter.report(KeepaliveState::SessionLost);
+                    Duration::ZERO
+                }
+            },
+        };
+
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = cancelled(&mut cancel) => break,
+        }
+    }
+    reporter.report(KeepaliveState::Stopped);
+}
+
+// Resolves once a stop was requested or the handle is gone.
+async fn cancelled(cancel: &mut watch::Receiver<bool>) {
+    while !*cancel.borrow_and_update() {
+        if cancel.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+struct Reporter {
+    states: mpsc::UnboundedSender<KeepaliveState>,
+    last: Option<KeepaliveState>,
+}
+
+impl Reporter {
+    // Only changes are sent; nobody may be listening, which is fine.
+    fn report(&mut self, state: KeepaliveState) {
+        if self.last.as_ref() != Some(&state) {
+            let _ = self.states.send(state.clone());
+            self.last = Some(state);
+        }
+    }
+
+    fn online(&mut self, link: &KeepaliveLink, options: &KeepaliveOptions) -> Duration {
+        let wait = link
+            .interval
+            .unwrap_or(options.fallback_interval)
+            .max(options.min_interval);
+        self.report(KeepaliveState::Online {
+            keepalive_url: link.url.to_string(),
+            refresh_in_seconds: wait.as_secs(),
+        });
+        wait
+    }
+}
@@ -0,0 +1,289 @@
+use std::future::Future;
+use std::time::Duration;
+
+use futures::stream::FuturesUnordered;
+use futures::StreamExt;
+use serde::Serialize;
+
+use super::tls_policy::TlsPolicy;
+use super::vtop_errors::{VtopError, VtopResult};
+
+pub mod detect;
+pub mod fortinet;
+pub mod keepalive;
+pub mod profile;
+pub mod sophos;
+
+pub use super::paraser::wifi_portal::PortalVendor;
+pub use detect::{detect_portal, Connectivity, PortalDetection};
+pub use fortinet::{FortinetPortal, KeepaliveLink};
+pub use keepalive::{spawn_keepalive, KeepaliveHandle, KeepaliveOptions, KeepaliveState};
+pub use profile::{NetworkProfile, NetworkProfiles, PortalKind};
+pub use sophos::SophosPortal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum WifiAction {
+    Login,
+    Logout,
+    Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum WifiOutcome {
+    LoggedIn,
+    LoggedOut,
+    AlreadyOnline,
+    LimitReached,
+    InvalidCredentials,
+    // Neither the portal nor the internet answered.
+    NoNetwork,
+    // The portal answered the first request but not a later one.
+    PortalUnreachable,
+    // The portal's certificate failed the TLS policy, as an unpinned
+    // self-signed portal does. Says nothing about connectivity.
+    CertificateRejected,
+    Cancelled,
+    Unknown(String),
+}
+
+impl WifiOutcome {
+    pub fn is_success(&self) -> bool {
+        matches!(
+            self,
+            WifiOutcome::LoggedIn | WifiOutcome::LoggedOut | WifiOutcome::AlreadyOnline
+        )
+    }
+
+    // Outcomes that only say the portal could not be reached.
+    pub fn is_connectivity(&self) -> bool {
+        matches!(
+            self,
+            WifiOutcome::NoNetwork | WifiOutcome::PortalUnreachable | WifiOutcome::Unknown(_)
+        )
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            WifiOutcome::LoggedIn => "You have successfully logged in.".into(),
+            WifiOutcome::LoggedOut => "Signed out successfully.".into(),
+            WifiOutcome::AlreadyOnline => "You are already connected to the internet.".into(),
+            WifiOutcome::LimitReached => "Login failed. Limit Reached.".into(),
+            WifiOutcome::InvalidCredentials => "Invalid username or password.".into(),
+            WifiOutcome::NoNetwork => "Not connected to the campus network.".into(),
+            WifiOutcome::PortalUnreachable => "The WiFi portal stopped responding.".into(),
+            WifiOutcome::CertificateRejected => {
+                "The WiFi portal's certificate is not trusted; pin it in network_profiles.toml."
+                    .into()
+            }
+            WifiOutcome::Cancelled => "Cancelled.".into(),
+            WifiOutcome::Unknown(msg) if msg.is_empty() => "Unknown error.".into(),
+            WifiOutcome::Unknown(msg) => msg.clone(),
+        }
+    }
+}
+
+// What the hostel portal reports about a session. Fields the portal did
+// not send are left empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct WifiSessionStatus {
+    pub logged_in: bool,
+    pub username: Option<String>,
+    // As the portal wrote it, e.g. "1.5 GB".
+    pub remaining_quota: Option<String>,
+    pub remaining_quota_bytes: Option<u64>,
+    pub session_seconds: Option<u64>,
+    pub active_devices: Option<u32>,
+    pub limit_reached: bool,
+    // Sessions holding the account, listed when the device limit is hit.
+    pub active_sessions: Vec<ActiveSession>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ActiveSession {
+    pub ip: Option<String>,
+    pub mac: Option<String>,
+    pub login_time: Option<String>,
+}
+
+// A captive portal that students authenticate against to get internet
+// access on campus.
+pub trait WifiPortal: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn login(&self, username: &str, password: &str) -> impl Future<Output = WifiOutcome> + Send;
+
+    fn logout(&self, username: &str) -> impl Future<Output = WifiOutcome> + Send;
+
+    fn status(&self, username: &str) -> impl Future<Output = WifiOutcome> + Send;
+
+    fn perform(
+        &self,
+        action: WifiAction,
+        username: &str,
+        password: &str,
+    ) -> impl Future<Output = WifiOutcome> + Send {
+        async move {
+            match action {
+                WifiAction::Login => self.login(username, password).await,
+                WifiAction::Logout => self.logout(username).await,
+                WifiAction::Status => self.status(username).await,
+            }
+        }
+    }
+}
+
+// The portals deployed on the VIT-AP campus.
+#[derive(Debug, Clone)]
+pub enum CampusPortal {
+    // Academic blocks.
+    Fortinet(FortinetPortal),
+    // Hostels.
+    Sophos(SophosPortal),
+}
+
+impl CampusPortal {
+    pub fn from_profile(
+        profile: &NetworkProfile,
+        profiles: &NetworkProfiles,
+        tls: &TlsPolicy,
+    ) -> VtopResult<CampusPortal> {
+        let profile = profile.clone();
+        Ok(match profile.kind {
+            PortalKind::Fortinet => {
+                CampusPortal::Fortinet(FortinetPortal::from_profile(profile, profiles, tls)?)
+            }
+            PortalKind::Sophos => {
+                CampusPortal::Sophos(SophosPortal::from_profile(profile, profiles, tls)?)
+            }
+        })
+    }
+
+    // The portal of the profile picked by `detect_portal`.
+    pub fn for_detection(
+        detection: &PortalDetection,
+        profiles: &NetworkProfiles,
+        tls: &TlsPolicy,
+    ) -> Option<VtopResult<CampusPortal>> {
+        let profile = profiles.get(detection.profile.as_deref()?)?;
+        Some(CampusPortal::from_profile(profile, profiles, tls))
+    }
+
+    pub fn all(profiles: &NetworkProfiles, tls: &TlsPolicy) -> VtopResult<Vec<CampusPortal>> {
+        profiles
+            .profiles
+            .iter()
+            .map(|profile| CampusPortal::from_profile(profile, profiles, tls))
+            .collect()
+    }
+}
+
+impl WifiPortal for CampusPortal {
+    fn name(&self) -> &'static str {
+        match self {
+            CampusPortal::Fortinet(portal) => portal.name(),
+            CampusPortal::Sophos(portal) => portal.name(),
+        }
+    }
+
+    async fn login(&self, username: &str, password: &str) -> WifiOutcome {
+        match self {
+            CampusPortal::Fortinet(portal) => portal.login(username, password).await,
+            CampusPortal::Sophos(portal) => portal.login(username, password).await,
+        }
+    }
+
+    async fn logout(&self, username: &str) -> WifiOutcome {
+        match self {
+            CampusPortal::Fortinet(portal) => portal.logout(username).await,
+            CampusPortal::Sophos(portal) => portal.logout(username).await,
+        }
+    }
+
+    async fn status(&self, username: &str) -> WifiOutcome {
+        match self {
+            CampusPortal::Fortinet(portal) => portal.status(username).await,
+            CampusPortal::Sophos(portal) => portal.status(username).await,
+        }
+    }
+}
+
+// Runs `action` against every portal at once. A login returns the first
+// success. A logout or status check waits for every portal, as only the
+// one holding the session knows about it: `LoggedOut` or `LoggedIn` from
+// any portal wins, then any other success. Otherwise the last failure is
+// returned.
+pub async fn perform_on_any(
+    portals: &[CampusPortal],
+    action: WifiAction,
+    username: &str,
+    password: &str,
+) -> WifiOutcome {
+    let mut tasks: FuturesUnordered<_> = portals
+        .iter()
+        .map(|portal| portal.perform(action, username, password))
+        .collect();
+    let decisive = match action {
+        WifiAction::Login => None,
+        WifiAction::Logout => Some(WifiOutcome::LoggedOut),
+        WifiAction::Status => Some(WifiOutcome::LoggedIn),
+    };
+    let mut success = None;
+    let mut last = WifiOutcome::Unknown(String::new());
+    while let Some(outcome) = tasks.next().await {
+        if Some(&outcome) == decisive.as_ref() {
+            success = Some(outcome);
+        } else if outcome.is_success() {
+            if decisive.is_none() {
+                return outcome;
+            }
+            success.get_or_insert(outcome);
+        } else if !(last == WifiOutcome::CertificateRejected && outcome.is_connectivity()) {
+            // A portal we reached but could not trust explains more than
+            // another portal that was simply out of range.
+            last = outcome;
+        }
+    }
+    success.unwrap_or(last)
+}
+
+pub(crate) fn portal_client(tls: &TlsPolicy) -> VtopResult<reqwest::Client> {
+    tls.configure(reqwest::Client::builder().timeout(Duration::from_secs(2)))?
+        .build()
+        .map_err(|e| VtopError::ConfigurationError(format!("portal client: {}", e)))
+}
+
+// Used when the portal did not answer: either we are already past it or
+// there is no network at all. A rejected certificate is neither.
+pub(crate) async fn unreachable_outcome(
+    client: &reqwest::Client,
+    internet_check_url: &str,
+    error: &reqwest::Error,
+    otherwise: WifiOutcome,
+) -> WifiOutcome {
+    if rejected_certificate(error) {
+        return WifiOutcome::CertificateRejected;
+    }
+    match client.get(internet_check_url).send().await {
+        Ok(_) => WifiOutcome::AlreadyOnline,
+        Err(_) => otherwise,
+    }
+}
+
+// Whether `error` is the portal's certificate failing the TLS policy rather
+// than a network problem. rustls errors reach reqwest wrapped in (nested)
+// `io::Error`s, whose `source` skips the wrapped error, so those are
+// unwrapped by hand.
+pub(crate) fn rejected_certificate(error: &reqwest::Error) -> bool {
+    let mut next: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(error) = next {
+        if let Some(rustls::Error::InvalidCertificate(_)) = error.downcast_ref::<rustls::Error>() {
+            return true;
+        }
+        next = match error.downcast_ref::<std::io::Error>() {
+            Some(io) => io.get_ref().map(|inner| inner as _),
+            None => error.source(),
+        };
+    }
+    false
+}
@@ -0,0 +1,285 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::vtop::tls_policy::TlsPolicy;
+use crate::api::vtop::vtop_errors::{VtopError, VtopResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortalKind {
+    Fortinet,
+    Sophos,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormFields {
+    pub username: String,
+    pub password: String,
+    // Hidden session token copied from the login page (Fortinet `magic`).
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+// Case-insensitive substrings looked for in portal responses.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PortalMarkers {
+    pub login_success: Vec<String>,
+    pub logout_success: Vec<String>,
+    pub limit_reached: Vec<String>,
+    pub invalid_credentials: Vec<String>,
+}
+
+impl PortalMarkers {
+    pub(crate) fn matches(markers: &[String], text: &str) -> bool {
+        let text = text.to_lowercase();
+        markers
+            .iter()
+            .any(|marker| text.contains(&marker.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub kind: PortalKind,
+    pub base_url: String,
+    // Defaults to `base_url`.
+    #[serde(default)]
+    pub logout_base_url: Option<String>,
+    pub login_path: String,
+    // Where the login form is posted, when it differs from `login_path`.
+    #[serde(default)]
+    pub submit_path: Option<String>,
+    pub logout_path: String,
+    #[serde(default)]
+    pub keepalive_path: Option<String>,
+    #[serde(default)]
+    pub status_path: Option<String>,
+    pub fields: FormFields,
+    #[serde(default)]
+    pub markers: PortalMarkers,
+    // Hosts that show up in captive-portal redirects to this portal.
+    #[serde(default)]
+    pub hosts: Vec<String>,
+    // Overrides the caller's TLS policy, e.g. to pin a self-signed portal.
+    #[serde(default)]
+    pub tls: Option<TlsPolicy>,
+}
+
+// Neither built-in profile ships a pin: the portals' certificates have not
+// been captured from the campus network yet, so the caller's policy applies
+// and a self-signed portal is rejected until it is pinned in
+// network_profiles.toml (see network_profiles.example.toml).
+impl NetworkProfile {
+    pub fn vitap_academic() -> Self {
+        Self {
+            name: "vitap-academic".into(),
+            kind: PortalKind::Fortinet,
+            base_url: "http://172.18.10.10:1000".into(),
+            logout_base_url: Some("https://172.18.10.10:1000".into()),
+            login_path: "/login?".into(),
+            submit_path: Some("/?".into()),
+            logout_path: "/logout?".into(),
+            keepalive_path: Some("/keepalive?".into()),
+            status_path: None,
+            fields: FormFields {
+                username: "username".into(),
+                password: "password".into(),
+                token: Some("magic".into()),
+            },
+            markers: PortalMarkers {
+                login_success: vec![],
+                logout_success: vec![],
+                limit_reached: vec!["limit".into()],
+                invalid_credentials: vec!["failed".into(), "invalid".into()],
+            },
+            hosts: vec!["172.18.10.10".into()],
+            tls: None,
+        }
+    }
+
+    pub fn vitap_hostel() -> Self {
+        Self {
+            name: "vitap-hostel".into(),
+            kind: PortalKind::Sophos,
+            base_url: "https://172.18.8.14:8090".into(),
+            logout_base_url: None,
+            login_path: "/login.xml".into(),
+            submit_path: None,
+            logout_path: "/logout.xml".into(),
+            keepalive_path: None,
+            status_path: Some("/live".into()),
+            fields: FormFields {
+                username: "username".into(),
+                password: "password".into(),
+                token: None,
+            },
+            markers: PortalMarkers {
+                login_success: vec!["you are signed in".into()],
+                logout_success: vec!["signed out".into()],
+                limit_reached: vec!["limit reached".into()],
+                invalid_credentials: vec!["invalid".into(), "incorrect".into()],
+            },
+            hosts: vec!["hfw.vitap.ac.in".into(), "172.18.8.14".into()],
+            tls: None,
+        }
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        join(&self.base_url, path)
+    }
+
+    pub fn logout_url(&self) -> String {
+        join(
+            self.logout_base_url.as_deref().unwrap_or(&self.base_url),
+            &self.logout_path,
+        )
+    }
+
+    pub fn tls<'a>(&'a self, fallback: &'a TlsPolicy) -> &'a TlsPolicy {
+        self.tls.as_ref().unwrap_or(fallback)
+    }
+
+    // Whether `url` is on one of `hosts`; the host has to match whole.
+    pub fn serves(&self, url: &str) -> bool {
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            return false;
+        };
+        self.hosts
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(&host))
+    }
+}
+
+fn join(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
+
+// A URL with a known answer. A captive portal intercepts it and answers
+// with a redirect or its own page instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectivityCheck {
+    pub url: String,
+    #[serde(default = "default_expect_status")]
+    pub expect_status: u16,
+    #[serde(default)]
+    pub expect_body: Option<String>,
+}
+
+fn default_expect_status() -> u16 {
+    204
+}
+
+impl ConnectivityCheck {
+    pub fn new(url: impl Into<String>, expect_status: u16, expect_body: Option<&str>) -> Self {
+        Self {
+            url: url.into(),
+            expect_status,
+            expect_body: expect_body.map(Into::into),
+        }
+    }
+
+    pub fn is_expected(&self, status: u16, body: &str) -> bool {
+        status == self.expect_status
+            && self
+                .expect_body
+                .as_deref()
+                .map(|expected| body.contains(expected))
+                .unwrap_or(true)
+    }
+}
+
+// Captive portals and connectivity probes for the networks the app knows.
+// A file that sets `profiles` replaces the built-in list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkProfiles {
+    // Probed together; one expected answer means we are online.
+    pub connectivity_checks: Vec<ConnectivityCheck>,
+    // Any site outside campus, used to tell "online" from "no network".
+    pub internet_check_url: String,
+    pub profiles: Vec<NetworkProfile>,
+}
+
+impl Default for NetworkProfiles {
+    fn default() -> Self {
+        Self {
+            connectivity_checks: vec![
+                ConnectivityCheck::new(
+                    "http://connectivitycheck.gstatic.com/generate_204",
+                    204,
+                    None,
+                ),
+                ConnectivityCheck::new(
+                    "http://www.msftconnecttest.com/connecttest.txt",
+                    200,
+                    Some("Microsoft Connect Test"),
+                ),
+                ConnectivityCheck::new(
+                    "http://captive.apple.com/hotspot-detect.html",
+                    200,
+                    Some("Success"),
+                ),
+            ],
+            internet_check_url: "https://1.1.1.1".into(),
+            profiles: vec![
+                NetworkProfile::vitap_academic(),
+                NetworkProfile::vitap_hostel(),
+            ],
+        }
+    }
+}
+
+impl NetworkProfiles {
+    pub fn from_toml_str(text: &str) -> VtopResult<Self> {
+        let profiles: Self =
+            toml::from_str(text).map_err(|e| VtopError::ConfigurationError(e.to_string()))?;
+        if profiles.profiles.is_empty() {
+            return Err(VtopError::ConfigurationError(
+                "at least one network profile is required".into(),
+            ));
+        }
+        Ok(profiles)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> VtopResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| VtopError::ConfigurationError(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml_str(&text)
+    }
+
+    // Loads the file named by `VTOP_NETWORK_PROFILES` (or
+    // `network_profiles.toml` in the working directory when present).
+    pub fn load() -> VtopResult<Self> {
+        match std::env::var("VTOP_NETWORK_PROFILES") {
+            Ok(path) => Self::from_file(path),
+            Err(_) if Path::new("network_profiles.toml").exists() => {
+                Self::from_file("network_profiles.toml")
+            }
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NetworkProfile> {
+        self.profiles.iter().find(|profile| profile.name == name)
+    }
+
+    pub fn first_of(&self, kind: PortalKind) -> Option<&NetworkProfile> {
+        self.profiles.iter().find(|profile| profile.kind == kind)
+    }
+
+    // The profile whose hosts appear in a portal URL.
+    pub fn for_portal_url(&self, url: &str) -> Option<&NetworkProfile> {
+        self.profiles.iter().find(|profile| profile.serves(url))
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::profile::{NetworkProfile, NetworkProfiles, PortalMarkers};
+use super::{
+    portal_client, rejected_certificate, unreachable_outcome, WifiOutcome, WifiPortal,
+    WifiSessionStatus,
+};
+use crate::api::vtop::paraser::wifi_status::{parse_session_status, xml_text};
+use crate::api::vtop::tls_policy::TlsPolicy;
+use crate::api::vtop::vtop_errors::VtopResult;
+
+// Sophos (Cyberoam) captive portal used in the hostels. It speaks a small
+// XML protocol selected by the `mode` field.
+#[derive(Debug, Clone)]
+pub struct SophosPortal {
+    client: reqwest::Client,
+    profile: NetworkProfile,
+    internet_check_url: String,
+}
+
+impl SophosPortal {
+    pub fn new(tls: &TlsPolicy) -> VtopResult<Self> {
+        Self::from_profile(
+            NetworkProfile::vitap_hostel(),
+            &NetworkProfiles::default(),
+            tls,
+        )
+    }
+
+    pub fn from_profile(
+        profile: NetworkProfile,
+        profiles: &NetworkProfiles,
+        tls: &TlsPolicy,
+    ) -> VtopResult<Self> {
+        Ok(Self {
+            client: portal_client(profile.tls(tls))?,
+            internet_check_url: profiles.internet_check_url.clone(),
+            profile,
+        })
+    }
+
+    pub fn profile(&self) -> &NetworkProfile {
+        &self.profile
+    }
+
+    async fn post(&self, url: String, body: String) -> Result<String, reqwest::Error> {
+        let response = self
+            .client
+            .post(url)
+            .body(body)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .send()
+            .await?;
+        Ok(response.text().await.unwrap_or_default())
+    }
+}
+
+impl WifiPortal for SophosPortal {
+    fn name(&self) -> &'static str {
+        "sophos"
+    }
+
+    async fn login(&self, username: &str, password: &str) -> WifiOutcome {
+        let markers = &self.profile.markers;
+        match self.login_status(username, password).await {
+            Ok(status) if status.logged_in => WifiOutcome::LoggedIn,
+            Ok(status) if status.limit_reached => WifiOutcome::LimitReached,
+            Ok(status) => match status.message {
+                Some(message) if PortalMarkers::matches(&markers.invalid_credentials, &message) => {
+                    WifiOutcome::InvalidCredentials
+                }
+                message => WifiOutcome::Unknown(message.unwrap_or_default()),
+            },
+            Err(outcome) => outcome,
+        }
+    }
+
+    async fn logout(&self, username: &str) -> WifiOutcome {
+        let body = format!(
+            "mode=193&{}={}&a={}&producttype=0",
+            self.profile.fields.username,
+            urlencoding::encode(username),
+            timestamp()
+        );
+        match self.post(self.profile.logout_url(), body).await {
+            Ok(text) if PortalMarkers::matches(&self.profile.markers.logout_success, &text) => {
+                WifiOutcome::LoggedOut
+            }
+            Ok(text) => WifiOutcome::Unknown(xml_text(&text, "message").unwrap_or_default()),
+            Err(error) if rejected_certificate(&error) => WifiOutcome::CertificateRejected,
+            Err(_) => WifiOutcome::NoNetwork,
+        }
+    }
+
+    async fn status(&self, username: &str) -> WifiOutcome {
+        match self.session_status(username).await {
+            Ok(status) if status.logged_in => WifiOutcome::LoggedIn,
+            Ok(_) => WifiOutcome::LoggedOut,
+            Err(outcome) => outcome,
+        }
+    }
+}
+
+impl SophosPortal {
+    // Signs in and returns what the portal said about the session, including
+    // the other sessions holding the account when the device limit is hit.
+    pub async fn login_status(
+        &self,
+        username: &str,
+        password: &str,
+    ) -> Result<WifiSessionStatus, WifiOutcome> {
+        let fields = &self.profile.fields;
+        let body = format!(
+            "mode=191&{}={}&{}={}&a={}&producttype=0",
+            fields.username,
+            urlencoding::encode(username),
+            fields.password,
+            urlencoding::encode(password),
+            timestamp()
+        );
+        match self
+            .post(self.profile.url(&self.profile.login_path), body)
+            .await
+        {
+            Ok(text) => Ok(self.parse(&text)),
+            Err(error) => Err(self.unreachable(&error, WifiOutcome::NoNetwork).await),
+        }
+    }
+
+    // Asks the portal about the live session of `username`.
+    pub async fn session_status(&self, username: &str) -> Result<WifiSessionStatus, WifiOutcome> {
+        let Some(status_path) = &self.profile.status_path else {
+            return Err(WifiOutcome::Unknown(
+                "This portal does not report its status.".into(),
+            ));
+        };
+        let url = format!(
+            "{}?mode=192&{}={}&a={}&producttype=0",
+            self.profile.url(status_path),
+            self.profile.fields.username,
+            urlencoding::encode(username),
+            timestamp()
+        );
+        match self.client.get(url).send().await {
+            // An error page says nothing about the session.
+            Ok(response) if !response.status().is_success() => Err(WifiOutcome::Unknown(format!(
+                "The portal answered with HTTP {}.",
+                response.status().as_u16()
+            ))),
+            Ok(response) => {
+                let text = response.text().await.unwrap_or_default();
+                let mut status = self.parse(&text);
+                if status.logged_in && status.username.is_none() {
+                    status.username = Some(username.to_string());
+                }
+                Ok(status)
+            }
+            Err(error) => Err(self.unreachable(&error, WifiOutcome::NoNetwork).await),
+        }
+    }
+
+    fn parse(&self, xml: &str) -> WifiSessionStatus {
+        let markers = &self.profile.markers;
+        let mut status = parse_session_status(xml);
+        status.logged_in |= PortalMarkers::matches(&markers.login_success, xml);
+        status.limit_reached |= PortalMarkers::matches(&markers.limit_reached, xml);
+        status
+    }
+
+    async fn unreachable(&self, error: &reqwest::Error, otherwise: WifiOutcome) -> WifiOutcome {
+        unreachable_outcome(&self.client, &self.internet_check_url, error, otherwise).await
+    }
+}
+
+fn timestamp() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        .to_string()
+}
@@ -1,148 +1,811 @@
+use std::future::Future;
+use std::sync::Arc;
+
+#[cfg(feature = "unverified-endpoints")]
+use futures::future::BoxFuture;
+
 use crate::api::vtop::{
-    paraser::wifi_portal::find_captivative_portal,
+    bundle::SemesterBundle,
+    gpa::{self, GpaSummary},
+    progress::{with_progress, CancelToken, ProgressFn, VtopEvent, VtopProgress},
+    request_limiter::{LimiterMetrics, RequestLimiter},
+    tls_policy::TlsPolicy,
     types::{
-        AttendanceData, ExamScheduleData, FullAttendanceData, MarksData, SemesterData,
-        TimetableData,
+        AttendanceData, CourseGrade, ExamScheduleData, FullAttendanceData, MarksData,
+        SemesterData, TimetableData,
     },
     vtop_client::{VtopClient, VtopError},
-    vtop_config::VtopClientBuilder,
-    wifi::*,
+    vtop_config::{VtopClientBuilder, VtopConfig},
+    wifi::{
+        detect_portal, perform_on_any, CampusPortal, Connectivity, NetworkProfiles,
+        PortalDetection, PortalKind, SophosPortal, WifiAction, WifiOutcome, WifiPortal,
+        WifiSessionStatus,
+    },
+};
+#[cfg(feature = "unverified-endpoints")]
+use crate::api::vtop::{
+    announcements,
+    biometric::{self, AttendanceMismatch},
+    course_page::{DownloadedMaterial, MaterialSyncReport},
+    curriculum::{curriculum_progress, CurriculumProgress},
+    digital_assignment::DigitalAssignmentsReport,
+    feedback::FeedbackSubmission,
+    types::{
+        AllocationData, Announcement, AnnouncementsData, ArrearsData, BiometricLogData,
+        CourseMaterial, CoursePageCourses, CoursePageData, CurriculumData, FeeDuesData,
+        FeedbackForm, FeedbackFormsData, FeedbackQuestionsData, FeedbackResponse,
+        HostelApplicationsData, HostelRequest, HostelRequestKind, PaymentHistoryData,
+        ProctorDetails, SemesterGradesData,
+    },
 };
-use futures::stream::FuturesUnordered;
-use futures::StreamExt;
-use tokio::task;
+use crate::stream_sink::{EventSink, StreamSink};
 
 
-pub fn get_vtop_client(username: String, password: String, cookie: Option<String>) -> VtopClient {
-    let mut client = VtopClientBuilder::new().build(username, password);
+pub fn get_vtop_client(
+    username: String,
+    password: String,
+    cookie: Option<String>,
+) -> Result<VtopClient, VtopError> {
+    get_vtop_client_with_config(VtopConfig::default(), username, password, cookie)
+}
+
+
+pub fn get_vtop_client_with_config(
+    config: VtopConfig,
+    username: String,
+    password: String,
+    cookie: Option<String>,
+) -> Result<VtopClient, VtopError> {
+    let mut client = VtopClientBuilder::new()
+        .config(config)
+        .build(username, password)?;
     if let Some(cookie) = cookie {
-        if (!cookie.is_empty()) {
+        if !cookie.is_empty() {
             client.set_cookie(cookie);
         }
     }
-    return client;
+    Ok(client)
+}
+
+
+// Keeps the full error, including its HTTP context, in the logs. The app
+// shows `vtop_error_user_message` instead.
+fn logged<T>(operation: &str, result: Result<T, VtopError>) -> Result<T, VtopError> {
+    if let Err(error) = &result {
+        tracing::warn!(operation, error = %error, retryable = error.is_retryable(), "VTOP call failed");
+    }
+    result
+}
+
+
+pub fn vtop_error_user_message(error: VtopError) -> String {
+    error.user_message()
+}
+
+
+pub fn vtop_error_is_retryable(error: VtopError) -> bool {
+    error.is_retryable()
+}
+
+
+pub fn vtop_error_is_auth_problem(error: VtopError) -> bool {
+    error.is_auth_problem()
 }
 
 
 pub async fn vtop_client_login(client: &mut VtopClient) -> Result<(), VtopError> {
-    client.login().await
+    logged("vtop_client_login", client.login().await)
 }
 
-pub async fn fetch_semesters(client: &mut VtopClient) -> Result<SemesterData, VtopError> {
-    client.get_semesters(true).await
+pub async fn fetch_semesters(client: &VtopClient) -> Result<SemesterData, VtopError> {
+    logged("fetch_semesters", client.get_semesters(true).await)
 }
 
 pub async fn fetch_attendance(
-    client: &mut VtopClient,
+    client: &VtopClient,
     semester_id: String,
 ) -> Result<AttendanceData, VtopError> {
-    client.get_attendance(&semester_id).await
+    logged("fetch_attendance", client.get_attendance(&semester_id).await)
 }
 
 
 pub async fn fetch_full_attendance(
-    client: &mut VtopClient,
+    client: &VtopClient,
     semester_id: String,
     course_id: String,
     course_type: String,
+    cancel: Option<CancelToken>,
 ) -> Result<FullAttendanceData, VtopError> {
-    client
-        .get_full_attendance(&semester_id, &course_id, &course_type)
-        .await
+    logged(
+        "fetch_full_attendance",
+        cancel
+            .unwrap_or_default()
+            .run(client.get_full_attendance(&semester_id, &course_id, &course_type))
+            .await,
+    )
 }
 
 
 pub async fn fetch_timetable(
-    client: &mut VtopClient,
+    client: &VtopClient,
     semester_id: String,
 ) -> Result<TimetableData, VtopError> {
-    client.get_timetable(&semester_id).await
+    logged("fetch_timetable", client.get_timetable(&semester_id).await)
 }
 
 
 pub async fn fetch_marks(
-    client: &mut VtopClient,
+    client: &VtopClient,
     semester_id: String,
 ) -> Result<MarksData, VtopError> {
-    client.get_marks(&semester_id).await
+    logged("fetch_marks", client.get_marks(&semester_id).await)
 }
 
 
 pub async fn fetch_exam_shedule(
-    client: &mut VtopClient,
+    client: &VtopClient,
     semester_id: String,
 ) -> Result<ExamScheduleData, VtopError> {
-    client.get_exam_schedule(&semester_id).await
+    logged("fetch_exam_shedule", client.get_exam_schedule(&semester_id).await)
+}
+
+
+// Timetable, attendance, marks and exam schedule in one call, fetched
+// concurrently. Parts that fail are listed in `failures` instead of failing
+// the whole bundle.
+pub async fn fetch_semester_bundle(
+    client: &VtopClient,
+    semester_id: String,
+    include_full_attendance: bool,
+    cancel: Option<CancelToken>,
+) -> SemesterBundle {
+    let bundle = client
+        .get_semester_bundle(
+            &semester_id,
+            include_full_attendance,
+            &cancel.unwrap_or_default(),
+        )
+        .await;
+    for failure in &bundle.failures {
+        tracing::warn!(
+            operation = "fetch_semester_bundle",
+            part = ?failure.part,
+            course_id = failure.course_id.as_deref(),
+            error = %failure.error,
+            "VTOP call failed"
+        );
+    }
+    bundle
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_course_page_courses(
+    client: &VtopClient,
+    semester_id: String,
+) -> Result<CoursePageCourses, VtopError> {
+    logged(
+        "fetch_course_page_courses",
+        client.get_course_page_courses(&semester_id).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_course_page(
+    client: &VtopClient,
+    semester_id: String,
+    class_id: String,
+    faculty_id: String,
+) -> Result<CoursePageData, VtopError> {
+    logged(
+        "fetch_course_page",
+        client
+            .get_course_page(&semester_id, &class_id, &faculty_id)
+            .await,
+    )
+}
+
+
+// Saves one material to `file_path`, replacing any file already there.
+#[cfg(feature = "unverified-endpoints")]
+pub async fn download_course_material(
+    client: &VtopClient,
+    material: CourseMaterial,
+    file_path: String,
+) -> Result<DownloadedMaterial, VtopError> {
+    let result = async {
+        let mut file = tokio::fs::File::create(&file_path)
+            .await
+            .map_err(|e| VtopError::StorageError(e.to_string()))?;
+        client.download_material(&material, &mut file).await
+    }
+    .await;
+    logged("download_course_material", result)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn sync_course_materials(
+    client: &VtopClient,
+    semester_id: String,
+    directory: String,
+    cancel: Option<CancelToken>,
+) -> Result<MaterialSyncReport, VtopError> {
+    let report = logged(
+        "sync_course_materials",
+        client
+            .sync_course_materials(
+                &semester_id,
+                std::path::Path::new(&directory),
+                &cancel.unwrap_or_default(),
+            )
+            .await,
+    )?;
+    for failure in &report.failures {
+        tracing::warn!(
+            operation = "sync_course_materials",
+            course = %failure.course_code,
+            error = %failure.error,
+            "VTOP call failed"
+        );
+    }
+    Ok(report)
+}
+
+
+// Courses whose assignments could not be loaded are listed in `failures`.
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_digital_assignments(
+    client: &VtopClient,
+    semester_id: String,
+    cancel: Option<CancelToken>,
+) -> Result<DigitalAssignmentsReport, VtopError> {
+    let report = logged(
+        "fetch_digital_assignments",
+        cancel
+            .unwrap_or_default()
+            .run(client.get_digital_assignments(&semester_id))
+            .await,
+    )?;
+    for failure in &report.failures {
+        tracing::warn!(
+            operation = "fetch_digital_assignments",
+            course = %failure.course_code,
+            error = %failure.error,
+            "VTOP call failed"
+        );
+    }
+    Ok(report)
+}
+
+
+// Uploads the file at `file_path`. `otp` is called with VTOP's prompt when
+// it mails an OTP and should resolve to the code, or `None` to give up.
+#[cfg(feature = "unverified-endpoints")]
+pub async fn upload_digital_assignment(
+    client: &VtopClient,
+    class_id: String,
+    code: String,
+    file_path: String,
+    otp: impl Fn(String) -> BoxFuture<'static, Option<String>>,
+) -> Result<String, VtopError> {
+    let result = async {
+        let content = tokio::fs::read(&file_path)
+            .await
+            .map_err(|e| VtopError::StorageError(e.to_string()))?;
+        let file_name = std::path::Path::new(&file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "assignment.pdf".to_string());
+        client
+            .upload_digital_assignment(&class_id, &code, &file_name, content, otp)
+            .await
+    }
+    .await;
+    logged("upload_digital_assignment", result)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_fee_dues(client: &VtopClient) -> Result<FeeDuesData, VtopError> {
+    logged("fetch_fee_dues", client.get_fee_dues().await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_payment_history(client: &VtopClient) -> Result<PaymentHistoryData, VtopError> {
+    logged("fetch_payment_history", client.get_payment_history().await)
+}
+
+
+// The receipt PDF for a `FeePayment` with `has_receipt`.
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_fee_receipt(
+    client: &VtopClient,
+    receipt_no: String,
+) -> Result<Vec<u8>, VtopError> {
+    logged("fetch_fee_receipt", client.get_fee_receipt(&receipt_no).await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_hostel_applications(
+    client: &VtopClient,
+    kind: HostelRequestKind,
+) -> Result<HostelApplicationsData, VtopError> {
+    logged(
+        "fetch_hostel_applications",
+        client.get_hostel_applications(kind).await,
+    )
+}
+
+
+// Fails with `VtopError::InvalidForm` without contacting VTOP when the
+// request does not pass `HostelRequest::validate`.
+#[cfg(feature = "unverified-endpoints")]
+pub async fn submit_hostel_request(
+    client: &VtopClient,
+    request: HostelRequest,
+) -> Result<String, VtopError> {
+    logged(
+        "submit_hostel_request",
+        client.submit_hostel_request(&request).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn cancel_hostel_application(
+    client: &VtopClient,
+    kind: HostelRequestKind,
+    application_id: String,
+) -> Result<String, VtopError> {
+    logged(
+        "cancel_hostel_application",
+        client.cancel_hostel_application(kind, &application_id).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_semester_grades(
+    client: &VtopClient,
+    semester_id: String,
+) -> Result<SemesterGradesData, VtopError> {
+    logged(
+        "fetch_semester_grades",
+        client.get_semester_grades(&semester_id).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_arrears(client: &VtopClient) -> Result<ArrearsData, VtopError> {
+    logged("fetch_arrears", client.get_arrears().await)
+}
+
+
+pub fn compute_semester_gpa(grades: Vec<CourseGrade>) -> GpaSummary {
+    gpa::semester_gpa(&grades)
+}
+
+
+// `grades` oldest first; see `gpa::cgpa`.
+pub fn compute_cgpa(grades: Vec<CourseGrade>) -> GpaSummary {
+    gpa::cgpa(&grades)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_curriculum(client: &VtopClient) -> Result<CurriculumData, VtopError> {
+    logged("fetch_curriculum", client.get_curriculum().await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_registration_allocation(
+    client: &VtopClient,
+) -> Result<AllocationData, VtopError> {
+    logged(
+        "fetch_registration_allocation",
+        client.get_registration_allocation().await,
+    )
+}
+
+
+// `grades` should hold every semester's grades and the arrears, and
+// `timetable` the current semester's.
+#[cfg(feature = "unverified-endpoints")]
+pub fn compute_curriculum_progress(
+    curriculum: CurriculumData,
+    allocation: AllocationData,
+    grades: Vec<CourseGrade>,
+    timetable: TimetableData,
+) -> CurriculumProgress {
+    curriculum_progress(&curriculum, &allocation, &grades, &timetable)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_announcements(client: &VtopClient) -> Result<AnnouncementsData, VtopError> {
+    logged("fetch_announcements", client.get_announcements().await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_messages(client: &VtopClient) -> Result<AnnouncementsData, VtopError> {
+    logged("fetch_messages", client.get_messages().await)
+}
+
+
+// What to notify about after a refetch; `previous` is the last stored copy.
+#[cfg(feature = "unverified-endpoints")]
+pub fn find_new_announcements(
+    previous: AnnouncementsData,
+    current: AnnouncementsData,
+) -> Vec<Announcement> {
+    announcements::new_announcements(&previous, &current)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_proctor_details(client: &VtopClient) -> Result<ProctorDetails, VtopError> {
+    logged("fetch_proctor_details", client.get_proctor_details().await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_proctor_contacts(client: &VtopClient) -> Result<ProctorDetails, VtopError> {
+    logged("fetch_proctor_contacts", client.get_proctor_contacts().await)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_biometric_log(
+    client: &VtopClient,
+    from_date: String,
+    to_date: String,
+) -> Result<BiometricLogData, VtopError> {
+    logged(
+        "fetch_biometric_log",
+        client.get_biometric_log(&from_date, &to_date).await,
+    )
+}
+
+
+// `attendance` holds the full attendance of each course to check, e.g. from
+// `fetch_semester_bundle`.
+#[cfg(feature = "unverified-endpoints")]
+pub fn cross_check_biometric(
+    log: BiometricLogData,
+    attendance: Vec<FullAttendanceData>,
+) -> Vec<AttendanceMismatch> {
+    biometric::cross_check(&log, &attendance)
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_feedback_forms(
+    client: &VtopClient,
+    semester_id: String,
+) -> Result<FeedbackFormsData, VtopError> {
+    logged(
+        "fetch_feedback_forms",
+        client.get_feedback_forms(&semester_id).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn fetch_feedback_questions(
+    client: &VtopClient,
+    semester_id: String,
+    form: FeedbackForm,
+) -> Result<FeedbackQuestionsData, VtopError> {
+    logged(
+        "fetch_feedback_questions",
+        client.get_feedback_questions(&semester_id, &form).await,
+    )
+}
+
+
+#[cfg(feature = "unverified-endpoints")]
+pub async fn submit_feedback(
+    client: &VtopClient,
+    form: FeedbackQuestionsData,
+    responses: Vec<FeedbackResponse>,
+) -> Result<FeedbackSubmission, VtopError> {
+    logged(
+        "submit_feedback",
+        client.submit_feedback(&form, &responses).await,
+    )
 }
 
 
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_cookies(client: &mut VtopClient) -> Result<Vec<u8>, VtopError> {
+pub async fn fetch_cookies(client: &VtopClient) -> Result<Vec<u8>, VtopError> {
     client.get_cookie(true).await.clone()
 }
 
 
-pub async fn fetch_is_auth(client: &mut VtopClient) -> bool {
-    client.is_authenticated().clone()
+pub async fn fetch_is_auth(client: &VtopClient) -> bool {
+    client.is_authenticated()
 }
 
 
-pub async fn fetch_wifi(username: String, password: String, i: i32) -> (bool, String) {
-    if (i != 0) {
-        // let k = wifi_login_logout(i, username.clone(), password.clone()).await;
-        // if (k.0) {
-        //     return k;
-        // } else {
-        //     return wifi_login_logout_hostel(i, username, password).await;
-        // }
-        let mut tasks = FuturesUnordered::new();
+pub fn fetch_limiter_metrics() -> LimiterMetrics {
+    RequestLimiter::global().metrics()
+}
 
-        let username_clone = username.clone();
-        let password_clone = password.clone();
-        tasks.push(task::spawn(async move {
-            (
-                "non_hostel",
-                wifi_login_logout(i, username_clone, password_clone).await,
-            )
-        }));
-
-        let username_clone_2 = username.clone();
-        let password_clone_2 = password.clone();
-        tasks.push(task::spawn(async move {
-            (
-                "hostel",
-                wifi_login_logout_hostel(i, username_clone_2, password_clone_2).await,
-            )
-        }));
-
-        while let Some(result) = tasks.next().await {
-            match result {
-                Ok((_task_name, (is_success, message))) => {
-                    if is_success {
-                        return (is_success, message);
-                    }
-
-                    if tasks.is_empty() {
-                        return (is_success, message);
-                    }
-                }
-                Err(_) => {}
+
+pub fn new_cancel_token() -> CancelToken {
+    CancelToken::new()
+}
+
+
+pub fn cancel(token: &CancelToken) {
+    token.cancel();
+}
+
+
+// Runs one client call for a `*_stream` function: its progress goes to
+// `sink`, then the data `finish` makes of the result. Failures end the
+// stream with `Cancelled` or `Failed` and are returned as well.
+async fn streamed<T, S: EventSink>(
+    operation: &str,
+    sink: S,
+    cancel: CancelToken,
+    call: impl Future<Output = Result<T, VtopError>>,
+    finish: impl FnOnce(T) -> Option<VtopEvent>,
+) -> Result<(), VtopError> {
+    let sink = Arc::new(sink);
+    let progress = sink.clone();
+    let progress: ProgressFn = Arc::new(move |step| progress.send_event(VtopEvent::Progress(step)));
+    let result = logged(operation, with_progress(progress, cancel.run(call)).await);
+
+    match result {
+        Ok(data) => {
+            if let Some(event) = finish(data) {
+                sink.send_event(event);
             }
+            Ok(())
+        }
+        Err(error) => {
+            let step = match &error {
+                VtopError::Cancelled => VtopProgress::Cancelled,
+                error => VtopProgress::Failed {
+                    message: error.user_message(),
+                },
+            };
+            sink.send_event(VtopEvent::Progress(step));
+            Err(error)
         }
-        return (false, "Unknow Error".to_string());
     }
+}
 
-    let captive = find_captivative_portal().await;
 
-    if (captive.contains("hfw.vitap.ac.in")) {
-        return wifi_login_logout_hostel(i, username, password).await;
-    } else if (captive == "IE") {
-        return (
-            false,
-            "You are already connected to the internet.".to_string(),
-        );
-    } else {
-        wifi_login_logout(i, username, password).await
+pub async fn vtop_client_login_stream(
+    client: &mut VtopClient,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "vtop_client_login",
+        sink,
+        cancel,
+        client.login(),
+        |_| None,
+    )
+    .await
+}
+
+
+pub async fn fetch_semesters_stream(
+    client: &VtopClient,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_semesters",
+        sink,
+        cancel,
+        client.get_semesters(true),
+        |data| Some(VtopEvent::Semesters(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_timetable_stream(
+    client: &VtopClient,
+    semester_id: String,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_timetable",
+        sink,
+        cancel,
+        client.get_timetable(&semester_id),
+        |data| Some(VtopEvent::Timetable(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_attendance_stream(
+    client: &VtopClient,
+    semester_id: String,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_attendance",
+        sink,
+        cancel,
+        client.get_attendance(&semester_id),
+        |data| Some(VtopEvent::Attendance(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_full_attendance_stream(
+    client: &VtopClient,
+    semester_id: String,
+    course_id: String,
+    course_type: String,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_full_attendance",
+        sink,
+        cancel,
+        client.get_full_attendance(&semester_id, &course_id, &course_type),
+        |data| Some(VtopEvent::FullAttendance(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_marks_stream(
+    client: &VtopClient,
+    semester_id: String,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_marks",
+        sink,
+        cancel,
+        client.get_marks(&semester_id),
+        |data| Some(VtopEvent::Marks(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_exam_shedule_stream(
+    client: &VtopClient,
+    semester_id: String,
+    sink: StreamSink<VtopEvent>,
+    cancel: CancelToken,
+) -> Result<(), VtopError> {
+    streamed(
+        "fetch_exam_shedule",
+        sink,
+        cancel,
+        client.get_exam_schedule(&semester_id),
+        |data| Some(VtopEvent::ExamSchedule(data)),
+    )
+    .await
+}
+
+
+pub async fn fetch_wifi(
+    username: String,
+    password: String,
+    action: WifiAction,
+    profiles: NetworkProfiles,
+    tls: TlsPolicy,
+    cancel: Option<CancelToken>,
+) -> WifiOutcome {
+    let Some(cancel) = cancel else {
+        return wifi(username, password, action, profiles, tls).await;
+    };
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => WifiOutcome::Cancelled,
+        outcome = wifi(username, password, action, profiles, tls) => outcome,
     }
 }
 
+
+async fn wifi(
+    username: String,
+    password: String,
+    action: WifiAction,
+    profiles: NetworkProfiles,
+    tls: TlsPolicy,
+) -> WifiOutcome {
+    let detection = match detect_portal(&profiles, &tls).await {
+        Ok(detection) => detection,
+        Err(e) => return WifiOutcome::Unknown(e.to_string()),
+    };
+    tracing::debug!(?detection, ?action, "captive portal detection");
+    if action == WifiAction::Login && detection.connectivity == Connectivity::Online {
+        return WifiOutcome::AlreadyOnline;
+    }
+
+    match CampusPortal::for_detection(&detection, &profiles, &tls) {
+        Some(Ok(portal)) => portal.perform(action, &username, &password).await,
+        Some(Err(e)) => WifiOutcome::Unknown(e.to_string()),
+        None if detection.connectivity == Connectivity::Offline => WifiOutcome::NoNetwork,
+        // A portal without a matching profile: the only option left is to
+        // try each known one.
+        None => match CampusPortal::all(&profiles, &tls) {
+            Ok(portals) => perform_on_any(&portals, action, &username, &password).await,
+            Err(e) => WifiOutcome::Unknown(e.to_string()),
+        },
+    }
+}
+
+
+pub async fn detect_wifi_portal(
+    profiles: NetworkProfiles,
+    tls: TlsPolicy,
+) -> Result<PortalDetection, VtopError> {
+    detect_portal(&profiles, &tls).await
+}
+
+
+fn hostel_portal(profiles: &NetworkProfiles, tls: &TlsPolicy) -> Result<SophosPortal, WifiOutcome> {
+    let profile = profiles
+        .first_of(PortalKind::Sophos)
+        .cloned()
+        .ok_or_else(|| WifiOutcome::Unknown("No hostel network profile is configured.".into()))?;
+    SophosPortal::from_profile(profile, profiles, tls)
+        .map_err(|e| WifiOutcome::Unknown(e.to_string()))
+}
+
+
+pub async fn fetch_hostel_wifi_status(
+    username: String,
+    profiles: NetworkProfiles,
+    tls: TlsPolicy,
+) -> Result<WifiSessionStatus, WifiOutcome> {
+    hostel_portal(&profiles, &tls)?
+        .session_status(&username)
+        .await
+}
+
+
+// Like `fetch_wifi` for the hostel portal, but keeps the details, e.g. the
+// sessions to end when the device limit is reached.
+pub async fn hostel_wifi_login(
+    username: String,
+    password: String,
+    profiles: NetworkProfiles,
+    tls: TlsPolicy,
+) -> Result<WifiSessionStatus, WifiOutcome> {
+    hostel_portal(&profiles, &tls)?
+        .login_status(&username, &password)
+        .await
+}
+
+
+pub fn default_network_profiles() -> NetworkProfiles {
+    NetworkProfiles::default()
+}
+
+
+pub fn load_network_profiles(path: String) -> Result<NetworkProfiles, VtopError> {
+    NetworkProfiles::from_file(path)
+}
+
+
+pub fn wifi_outcome_message(outcome: WifiOutcome) -> String {
+    outcome.message()
+}
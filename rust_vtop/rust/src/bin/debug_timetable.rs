@@ -1,14 +1,25 @@
+use rust_lib_vitapmate::api::vtop::redact::Redacted;
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
 use rust_lib_vitapmate::api::vtop_get_client::{
-    get_vtop_client,
+    get_vtop_client_with_config,
     vtop_client_login,
     fetch_semesters,
     fetch_timetable,
 };
 
     use std::env;
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() {
+    // Logs go to stderr; stdout is reserved for the JSON result.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+        )
+        .with_writer(std::io::stderr)
+        .init();
+
     let args: Vec<String> = env::args().collect();
     if args.len() < 3 {
         eprintln!("Usage: {} <username> <password>", args[0]);
@@ -18,13 +29,28 @@ async fn main() {
     let username = args[1].to_uppercase();
     let password = &args[2];
 
-    eprintln!("Attempting login for user: {}", username);
+    eprintln!("Attempting login for user: {}", Redacted::reg_no(&username));
+
+    let config = match VtopConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let mut client = get_vtop_client(
+    let mut client = match get_vtop_client_with_config(
+        config,
         username.clone(),
         password.to_string(),
         None,
-    );
+    ) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
     let mut retry_count = 0;
     while retry_count < 3 {
@@ -49,7 +75,7 @@ async fn main() {
         }
     }
 
-    let semesters = match fetch_semesters(&mut client).await {
+    let semesters = match fetch_semesters(&client).await {
         Ok(s) => s,
         Err(e) => {
              let error_msg = format!("{:?}", e);
@@ -73,7 +99,7 @@ async fn main() {
         semesters.semesters[0].id.clone()
     };
 
-    let timetable = match fetch_timetable(&mut client, semester_id).await {
+    let timetable = match fetch_timetable(&client, semester_id).await {
         Ok(t) => t,
         Err(e) => {
              let error_msg = format!("{:?}", e);
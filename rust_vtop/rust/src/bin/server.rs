@@ -1,17 +1,42 @@
 use axum::{
-    extract::Json,
-    routing::post,
+    extract::{FromRef, Json, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
     Router,
-    http::StatusCode,
 };
+use futures::stream::{self, Stream};
 use serde::{Deserialize, Serialize};
 use tower_http::cors::{Any, CorsLayer};
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing_subscriber::EnvFilter;
+use rust_lib_vitapmate::api::live_events::{
+    AccessDenied, AccessTokens, EventHub, SubscriptionItem, Topic,
+};
+#[cfg(feature = "unverified-endpoints")]
+use rust_lib_vitapmate::api::vtop::types::AnnouncementsData;
+use rust_lib_vitapmate::api::vtop::types::FacultyInfo;
+use rust_lib_vitapmate::api::vtop::vtop_client::{VtopClient, VtopError};
+use rust_lib_vitapmate::api::vtop::request_limiter::LimiterMetrics;
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
 use rust_lib_vitapmate::api::vtop_get_client::{
-    get_vtop_client,
+    get_vtop_client_with_config,
+    fetch_limiter_metrics,
     vtop_client_login,
     fetch_semesters,
     fetch_timetable,
+    fetch_attendance,
+    fetch_marks,
+};
+#[cfg(feature = "unverified-endpoints")]
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_announcements,
+    fetch_messages,
+    fetch_proctor_contacts,
+    find_new_announcements,
 };
 
 #[derive(Deserialize)]
@@ -20,11 +45,40 @@ struct LoginRequest {
     password: String,
     #[serde(rename = "semesterId")]
     semester_id: Option<String>,
+    // Fetch attendance, marks and announcements in the background and push
+    // change events to the student's `attendance:`, `marks:` and
+    // `announcements:` topics. Announcements need the
+    // `unverified-endpoints` feature.
+    #[serde(rename = "syncUpdates", default)]
+    sync_updates: bool,
+}
+
+#[derive(Deserialize)]
+struct EventsQuery {
+    topics: String,
+    // EventSource cannot set headers on the first connection, so the last
+    // seen id may also be passed as a query parameter.
+    #[serde(rename = "lastEventId")]
+    last_event_id: Option<u64>,
+    // The `eventsToken` from login, for the same reason.
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FacultyStatusRequest {
+    #[serde(rename = "cabinId")]
+    cabin_id: String,
+    name: String,
+    status: String,
 }
 
 #[derive(Serialize)]
 struct LoginResponse {
     success: bool,
+    // Needed to subscribe to this student's topics and to post faculty
+    // status; sent as `Authorization: Bearer` or the `token` parameter.
+    #[serde(rename = "eventsToken")]
+    events_token: String,
     faculty: Vec<Faculty>,
     semesters: Vec<Semester>,
 }
@@ -34,6 +88,10 @@ struct Faculty {
     #[serde(rename = "cabinId")]
     cabin_id: String,
     name: String,
+    // The student's own proctor, added even when they teach none of the
+    // student's courses.
+    #[serde(rename = "isProctor")]
+    is_proctor: bool,
 }
 
 #[derive(Serialize)]
@@ -48,16 +106,68 @@ struct ErrorResponse {
     details: Option<String>,
 }
 
+#[derive(Clone)]
+struct AppState {
+    hub: Arc<EventHub>,
+    tokens: Arc<AccessTokens>,
+    config: Arc<VtopConfig>,
+}
+
+impl FromRef<AppState> for Arc<AccessTokens> {
+    fn from_ref(state: &AppState) -> Self {
+        state.tokens.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<EventHub> {
+    fn from_ref(state: &AppState) -> Self {
+        state.hub.clone()
+    }
+}
+
+impl FromRef<AppState> for Arc<VtopConfig> {
+    fn from_ref(state: &AppState) -> Self {
+        state.config.clone()
+    }
+}
+
 #[tokio::main]
 async fn main() {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let config = VtopConfig::load().expect("invalid VTOP configuration");
+
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
+    let buffer_size = std::env::var("EVENT_BUFFER_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(256);
+    let hub = Arc::new(EventHub::new(buffer_size));
+    let token_ttl = std::env::var("EVENT_TOKEN_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(12 * 60 * 60);
+    let tokens = Arc::new(AccessTokens::new(Duration::from_secs(token_ttl)));
+
     let app = Router::new()
         .route("/api/vtop-login", post(handle_login))
-        .layer(cors);
+        .route("/api/events", get(handle_events))
+        .route("/api/faculty-status", post(handle_faculty_status))
+        .route("/api/metrics", get(handle_metrics))
+        .layer(cors)
+        .with_state(AppState {
+            hub,
+            tokens,
+            config: Arc::new(config),
+        });
 
     let port = std::env::var("PORT")
         .unwrap_or_else(|_| "3001".to_string())
@@ -65,50 +175,247 @@ async fn main() {
         .expect("PORT must be a number");
     
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    println!("Server running on {}", addr);
+    tracing::info!(%addr, "server running");
 
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
 
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+fn access_denied(denied: AccessDenied) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, error) = match denied {
+        AccessDenied::Unauthenticated => (StatusCode::UNAUTHORIZED, "Log in to receive events"),
+        AccessDenied::Forbidden => (StatusCode::FORBIDDEN, "Not your topic"),
+    };
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.to_string(),
+            details: None,
+        }),
+    )
+}
+
+async fn handle_events(
+    State(hub): State<Arc<EventHub>>,
+    State(tokens): State<Arc<AccessTokens>>,
+    headers: HeaderMap,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<ErrorResponse>)> {
+    let mut topics = Vec::new();
+    for name in query.topics.split(',').filter(|t| !t.trim().is_empty()) {
+        let topic = Topic::parse(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Unknown topic".to_string(),
+                    details: Some(name.to_string()),
+                }),
+            )
+        })?;
+        topics.push(topic);
+    }
+    if topics.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "At least one topic is required".to_string(),
+                details: None,
+            }),
+        ));
+    }
+
+    let token = bearer_token(&headers).or(query.token.as_deref());
+    tokens.authorize(token, &topics).map_err(access_denied)?;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .or(query.last_event_id);
+
+    let subscription = hub.subscribe(topics, last_event_id);
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let event = match subscription.next().await? {
+            SubscriptionItem::Event(event) => Event::default()
+                .id(event.id.to_string())
+                .event(event.kind.clone())
+                .json_data(&event)
+                .unwrap_or_default(),
+            SubscriptionItem::Resync => Event::default().event("resync").data("{}"),
+        };
+        Some((Ok(event), subscription))
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+async fn handle_metrics() -> Json<LimiterMetrics> {
+    Json(fetch_limiter_metrics())
+}
+
+async fn handle_faculty_status(
+    State(hub): State<Arc<EventHub>>,
+    State(tokens): State<Arc<AccessTokens>>,
+    headers: HeaderMap,
+    Json(payload): Json<FacultyStatusRequest>,
+) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
+    // Any logged-in student may report a cabin's status.
+    tokens
+        .authorize(bearer_token(&headers), &[Topic::FacultyCabin])
+        .map_err(access_denied)?;
+    let data = serde_json::json!({
+        "cabinId": payload.cabin_id,
+        "name": payload.name,
+        "status": payload.status,
+    });
+    hub.publish_if_changed(&Topic::FacultyCabin, "availability", &payload.cabin_id, data);
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn sync_updates(hub: Arc<EventHub>, client: VtopClient, username: String, semester_id: String) {
+    let sync_topic = Topic::Sync(username.clone());
+    hub.publish(&sync_topic, "sync-started", serde_json::json!({ "semesterId": semester_id }));
+
+    let mut failed = Vec::new();
+    match fetch_attendance(&client, semester_id.clone()).await {
+        Ok(attendance) => {
+            let data = serde_json::json!(attendance.records);
+            hub.publish_if_changed(&Topic::Attendance(username.clone()), "attendance-changed", &semester_id, data);
+        }
+        Err(e) => failed.push(format!("attendance: {}", e)),
+    }
+    match fetch_marks(&client, semester_id.clone()).await {
+        Ok(marks) => {
+            let data = serde_json::json!(marks.records);
+            hub.publish_if_changed(&Topic::Marks(username.clone()), "marks-changed", &semester_id, data);
+        }
+        Err(e) => failed.push(format!("marks: {}", e)),
+    }
+    #[cfg(feature = "unverified-endpoints")]
+    sync_announcements(&hub, &client, &username, &mut failed).await;
+
+    if failed.is_empty() {
+        hub.publish(&sync_topic, "sync-completed", serde_json::json!({ "semesterId": semester_id }));
+    } else {
+        hub.publish(
+            &sync_topic,
+            "sync-failed",
+            serde_json::json!({ "semesterId": semester_id, "errors": failed }),
+        );
+    }
+}
+
+#[cfg(feature = "unverified-endpoints")]
+async fn sync_announcements(hub: &EventHub, client: &VtopClient, username: &str, failed: &mut Vec<String>) {
+    let topic = Topic::Announcements(username.to_string());
+    match fetch_announcements(client).await {
+        Ok(data) => publish_new_announcements(hub, &topic, "announcement", data),
+        Err(e) => failed.push(format!("announcements: {}", e)),
+    }
+    match fetch_messages(client).await {
+        Ok(data) => publish_new_announcements(hub, &topic, "message", data),
+        Err(e) => failed.push(format!("messages: {}", e)),
+    }
+}
+
+// Pushes the items `find_new_announcements` reports against the previous
+// sync's copy. The first sync, or one after the copy was evicted, only
+// stores the page, so logging in does not replay every notice on it.
+#[cfg(feature = "unverified-endpoints")]
+fn publish_new_announcements(hub: &EventHub, topic: &Topic, kind: &str, current: AnnouncementsData) {
+    let previous = hub
+        .replace_last(topic, kind, "page", serde_json::json!(current))
+        .and_then(|previous| serde_json::from_value(previous).ok());
+    if let Some(previous) = previous {
+        for item in find_new_announcements(previous, current) {
+            hub.publish(topic, kind, serde_json::json!(item));
+        }
+    }
+}
+
+// Login still succeeds without the proctor; the page is missing for some
+// programmes, and is only read with the `unverified-endpoints` feature.
+#[cfg(feature = "unverified-endpoints")]
+async fn proctor_of(client: &VtopClient) -> Option<FacultyInfo> {
+    match fetch_proctor_contacts(client).await {
+        Ok(details) => details.proctor,
+        Err(e) => {
+            tracing::debug!(error = %e, "proctor details unavailable");
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "unverified-endpoints"))]
+async fn proctor_of(_client: &VtopClient) -> Option<FacultyInfo> {
+    None
+}
+
+// Students get `VtopError::user_message`; the full error, with its HTTP
+// context, only goes to the server log.
+fn vtop_failure(
+    status: StatusCode,
+    operation: &str,
+    error: VtopError,
+) -> (StatusCode, Json<ErrorResponse>) {
+    tracing::warn!(operation, error = %error, retryable = error.is_retryable(), "VTOP request failed");
+    (
+        status,
+        Json(ErrorResponse {
+            error: error.user_message(),
+            details: None,
+        }),
+    )
+}
+
 async fn handle_login(
+    State(hub): State<Arc<EventHub>>,
+    State(tokens): State<Arc<AccessTokens>>,
+    State(config): State<Arc<VtopConfig>>,
     Json(payload): Json<LoginRequest>,
 ) -> Result<Json<LoginResponse>, (StatusCode, Json<ErrorResponse>)> {
     let username = payload.username.to_uppercase();
     let password = payload.password;
 
-    let mut client = get_vtop_client(username.clone(), password, None);
+    let mut client = get_vtop_client_with_config((*config).clone(), username.clone(), password, None)
+        .map_err(|e| vtop_failure(StatusCode::INTERNAL_SERVER_ERROR, "configure client", e))?;
 
     // Login with retries
     let mut retry_count = 0;
     while retry_count < 3 {
         match vtop_client_login(&mut client).await {
             Ok(_) => break,
-            Err(e) if retry_count < 2 => {
+            Err(e @ (VtopError::QueueTimeout | VtopError::CircuitOpen)) => {
+                return Err(vtop_failure(StatusCode::SERVICE_UNAVAILABLE, "login", e));
+            }
+            Err(e) if e.is_retryable() && retry_count < 2 => {
+                tracing::debug!(error = %e, attempt = retry_count + 1, "retrying VTOP login");
                 retry_count += 1;
                 tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
             }
             Err(e) => {
-                return Err((
-                    StatusCode::UNAUTHORIZED,
-                    Json(ErrorResponse {
-                        error: "Login failed".to_string(),
-                        details: Some(format!("{:?}", e)),
-                    }),
-                ));
+                let status = if e.is_auth_problem() {
+                    StatusCode::UNAUTHORIZED
+                } else {
+                    StatusCode::BAD_GATEWAY
+                };
+                return Err(vtop_failure(status, "login", e));
             }
         }
     }
 
     // Fetch Semesters
-    let semesters_data = fetch_semesters(&mut client).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: "Failed to fetch semesters".to_string(),
-                details: Some(format!("{:?}", e)),
-            }),
-        )
+    let semesters_data = fetch_semesters(&client).await.map_err(|e| {
+        vtop_failure(StatusCode::BAD_GATEWAY, "fetch semesters", e)
     })?;
 
     if semesters_data.semesters.is_empty() {
@@ -124,16 +431,17 @@ async fn handle_login(
     let semester_id = payload.semester_id.unwrap_or_else(|| semesters_data.semesters[0].id.clone());
 
     // Fetch Timetable
-    let timetable = fetch_timetable(&mut client, semester_id).await.map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse {
-                error: "Failed to fetch timetable".to_string(),
-                details: Some(format!("{:?}", e)),
-            }),
-        )
+    let timetable = fetch_timetable(&client, semester_id.clone()).await.map_err(|e| {
+        vtop_failure(StatusCode::BAD_GATEWAY, "fetch timetable", e)
     })?;
 
+    let proctor = proctor_of(&client).await;
+
+    let events_token = tokens.issue(&username);
+    if payload.sync_updates {
+        tokio::spawn(sync_updates(hub, client, username, semester_id));
+    }
+
     // Extract Faculty
     let mut faculty_map = std::collections::HashMap::new();
     for slot in timetable.slots {
@@ -149,6 +457,34 @@ async fn handle_login(
                     Faculty {
                         cabin_id,
                         name: slot.faculty,
+                        is_proctor: false,
+                    },
+                );
+            }
+        }
+    }
+
+    // The proctor is matched by name since the timetable has no employee
+    // ids; one who does not teach the student gets an entry of their own,
+    // even when it shares a cabin with another faculty.
+    if let Some(proctor) = proctor {
+        let teaching = faculty_map
+            .values_mut()
+            .find(|f| f.name.trim().eq_ignore_ascii_case(proctor.name.trim()));
+        match teaching {
+            Some(faculty) => faculty.is_proctor = true,
+            None => {
+                let cabin_id = if !proctor.cabin.trim().is_empty() {
+                    proctor.cabin.trim().to_string()
+                } else {
+                    format!("UNKNOWN-{}", proctor.name.replace(' ', "-"))
+                };
+                faculty_map.insert(
+                    format!("{}|{}", cabin_id, proctor.name),
+                    Faculty {
+                        cabin_id,
+                        name: proctor.name,
+                        is_proctor: true,
                     },
                 );
             }
@@ -157,6 +493,7 @@ async fn handle_login(
 
     Ok(Json(LoginResponse {
         success: true,
+        events_token,
         faculty: faculty_map.into_values().collect(),
         semesters: semesters_data
             .semesters
@@ -1,2 +1,7 @@
 pub mod api;
+pub mod stream_sink;
 
+// Generated by flutter_rust_bridge_codegen from flutter_rust_bridge.yaml; run
+// it before building with the `flutter` feature.
+#[cfg(feature = "flutter")]
+mod frb_generated;
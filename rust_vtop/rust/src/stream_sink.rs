@@ -0,0 +1,30 @@
+use tokio::sync::mpsc;
+
+use crate::api::vtop::progress::VtopEvent;
+
+// Flutter builds get frb's `StreamSink` from the regenerated bindings;
+// everywhere else (server, tests, the node and python bindings) a channel
+// stands in for it so the `*_stream` calls work unchanged.
+#[cfg(feature = "flutter")]
+pub use crate::frb_generated::StreamSink;
+
+#[cfg(not(feature = "flutter"))]
+pub type StreamSink<T> = mpsc::UnboundedSender<T>;
+
+pub trait EventSink: Send + Sync + 'static {
+    // Events nobody listens to any more are dropped.
+    fn send_event(&self, event: VtopEvent);
+}
+
+#[cfg(feature = "flutter")]
+impl EventSink for StreamSink<VtopEvent> {
+    fn send_event(&self, event: VtopEvent) {
+        let _ = self.add(event);
+    }
+}
+
+impl EventSink for mpsc::UnboundedSender<VtopEvent> {
+    fn send_event(&self, event: VtopEvent) {
+        let _ = self.send(event);
+    }
+}
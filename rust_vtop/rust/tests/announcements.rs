@@ -0,0 +1,119 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Html;
+use axum::routing::post;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_announcements, fetch_messages, find_new_announcements,
+};
+
+use common::logged_in;
+
+const SPOTLIGHT: &str = r#"<div class="card">
+<div class="card-header">Examinations</div>
+<ul>
+<li id="spot1201"><b>CAT-1 schedule</b><p>CAT-1 starts on 10-Mar-2025.</p>
+<small>Posted on 01-Mar-2025</small>
+<a href="javascript:vtopDownload('downloadPdf/spotlight/1201')">Schedule</a></li>
+</ul>
+</div>
+<div class="card">
+<div class="card-header">Academics</div>
+<ul>
+<li><a onclick="viewSpotlight('1188')"><b>Course withdrawal deadline</b></a> 25-Feb-2025</li>
+</ul>
+</div>"#;
+
+const NEW_NOTICE: &str = r#"<div class="card">
+<div class="card-header">Academics</div>
+<ul>
+<li><b>Fee payment extended</b> 05-Mar-2025</li>
+</ul>
+</div>"#;
+
+const MESSAGES: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Faculty</th><th>Subject</th><th>Message</th><th>Date</th><th>Attachment</th></tr>
+<tr><td>1</td><td>Dr. Rao</td><td>Lab shifted</td><td>Today's lab is in AB2 204.</td><td>03-Mar-2025 10:15</td>
+<td><a href="/vtop/downloadPdf/messages/77">lab.pdf</a></td></tr>
+</table>"#;
+
+#[derive(Clone, Default)]
+struct Vtop {
+    posted_notice: Arc<AtomicBool>,
+}
+
+async fn spotlight(State(vtop): State<Vtop>) -> Html<String> {
+    let mut page = SPOTLIGHT.to_string();
+    if vtop.posted_notice.load(Ordering::SeqCst) {
+        page.push_str(NEW_NOTICE);
+    }
+    Html(page)
+}
+
+async fn spawn_fake_vtop(vtop: Vtop) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route("/vtop/spotlight/viewSpotlight", post(spotlight))
+            .route(
+                "/vtop/academics/common/StudentMessages",
+                post(|| async { Html(MESSAGES) }),
+            )
+            .with_state(vtop),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn spotlight_and_messages_parse_into_items() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0701").await;
+
+    let spotlight = fetch_announcements(&client).await.unwrap();
+    let [exam, withdrawal] = spotlight.announcements.as_slice() else {
+        panic!("expected two notices, got {:?}", spotlight.announcements);
+    };
+    assert_eq!(exam.id, "spot1201");
+    assert_eq!(exam.category, "Examinations");
+    assert_eq!(exam.title, "CAT-1 schedule");
+    assert_eq!(exam.body, "CAT-1 starts on 10-Mar-2025.");
+    assert_eq!(exam.posted_date, "01-Mar-2025");
+    assert_eq!(exam.attachments[0].path, "downloadPdf/spotlight/1201");
+    assert_eq!(withdrawal.id, "1188");
+    assert_eq!(withdrawal.category, "Academics");
+    assert_eq!(withdrawal.posted_date, "25-Feb-2025");
+    assert!(withdrawal.attachments.is_empty());
+
+    let messages = fetch_messages(&client).await.unwrap();
+    let [lab] = messages.announcements.as_slice() else {
+        panic!("expected one message, got {:?}", messages.announcements);
+    };
+    assert_eq!(lab.category, "Dr. Rao");
+    assert_eq!(lab.title, "Lab shifted");
+    assert_eq!(lab.body, "Today's lab is in AB2 204.");
+    assert_eq!(lab.posted_date, "03-Mar-2025");
+    assert_eq!(lab.id, "Dr. Rao|03-Mar-2025|Lab shifted");
+    assert_eq!(lab.attachments[0].title, "lab.pdf");
+}
+
+#[tokio::test]
+async fn only_new_announcements_are_reported() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0701").await;
+
+    let before = fetch_announcements(&client).await.unwrap();
+    let again = fetch_announcements(&client).await.unwrap();
+    assert!(find_new_announcements(before.clone(), again).is_empty());
+
+    vtop.posted_notice.store(true, Ordering::SeqCst);
+    let after = fetch_announcements(&client).await.unwrap();
+    let fresh = find_new_announcements(before, after);
+    let [notice] = fresh.as_slice() else {
+        panic!("expected one new notice, got {:?}", fresh);
+    };
+    assert_eq!(notice.title, "Fee payment extended");
+    assert_eq!(notice.id, "Academics|05-Mar-2025|Fee payment extended");
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use std::net::SocketAddr;
+
+use axum::response::Html;
+use axum::routing::post;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::paraser::page_util::{any_date, vtop_date};
+use rust_lib_vitapmate::api::vtop::paraser::parseattn::parse_full_attendance;
+use rust_lib_vitapmate::api::vtop_get_client::{cross_check_biometric, fetch_biometric_log};
+
+use common::logged_in;
+
+const LOG: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Date</th><th>Time</th><th>Location</th><th>In/Out</th></tr>
+<tr><td>1</td><td>03/03/2025</td><td>08:41:12</td><td>AB1 Main Gate</td><td>in</td></tr>
+<tr><td>2</td><td>03/03/2025</td><td>17:05:40</td><td>AB1 Main Gate</td><td>out</td></tr>
+<tr><td>3</td><td>04/03/2025</td><td>09:02:03</td><td>Library</td><td>in</td></tr>
+</table>"#;
+
+const FULL_ATTENDANCE: &str = r#"<table>
+<tr><th>Course</th></tr><tr><td>CSE3005</td></tr>
+<tr><th>Sl.No.</th><th>Date</th><th>Slot</th><th>Day / Time</th><th>Status</th><th>Remark</th></tr>
+<tr><td>1</td><td>03-Mar-2025</td><td>A1</td><td>MON 09:00</td><td>Absent</td><td>-</td></tr>
+<tr><td>2</td><td>04-Mar-2025</td><td>A1</td><td>TUE 09:00</td><td>Present</td><td>-</td></tr>
+<tr><td>3</td><td>05-Mar-2025</td><td>A1</td><td>WED 09:00</td><td>Absent</td><td>-</td></tr>
+</table>"#;
+
+async fn spawn_fake_vtop() -> SocketAddr {
+    common::spawn_fake_vtop(Router::new().route(
+        "/vtop/academics/common/BiometricInfo",
+        post(|| async { Html(LOG) }),
+    ))
+    .await
+}
+
+#[tokio::test]
+async fn absences_on_punched_in_days_are_flagged() {
+    let client = logged_in(spawn_fake_vtop().await, "21BCE0901").await;
+
+    let log = fetch_biometric_log(&client, "01-Mar-2025".into(), "05-Mar-2025".into())
+        .await
+        .unwrap();
+    let [monday, tuesday] = log.days.as_slice() else {
+        panic!("expected two days, got {:?}", log.days);
+    };
+    assert_eq!(monday.date, "03/03/2025");
+    assert_eq!(monday.punches.len(), 2);
+    assert_eq!(monday.punches[0].time, "08:41:12");
+    assert_eq!(monday.punches[0].location, "AB1 Main Gate");
+    assert_eq!(monday.punches[1].direction, "OUT");
+    assert_eq!(tuesday.punches[0].location, "Library");
+    let monday_punches = monday.punches.clone();
+
+    let attendance = parse_full_attendance(
+        FULL_ATTENDANCE.into(),
+        "AP2024252".into(),
+        "AP2024252000123".into(),
+        "ETH".into(),
+    );
+    // Wednesday's absence has no punches, and Tuesday was marked present.
+    let mismatches = cross_check_biometric(log, vec![attendance]);
+    let [mismatch] = mismatches.as_slice() else {
+        panic!("expected one mismatch, got {:?}", mismatches);
+    };
+    assert_eq!(mismatch.course_id, "AP2024252000123");
+    assert_eq!(mismatch.date, "03-Mar-2025");
+    assert_eq!(mismatch.slot, "A1");
+    assert_eq!(mismatch.punches, monday_punches);
+}
+
+#[test]
+fn device_dates_are_read_day_first() {
+    assert_eq!(any_date("05-Mar-2025"), Some((2025, 3, 5)));
+    assert_eq!(any_date("05/03/2025"), Some((2025, 3, 5)));
+    assert_eq!(any_date("2025-03-05 08:41:12"), Some((2025, 3, 5)));
+    assert_eq!(any_date("29/02/2024"), Some((2024, 2, 29)));
+    // There is no 13th month, so this is not read as March 13th either.
+    assert_eq!(any_date("03/13/2025"), None);
+    assert_eq!(any_date("29/02/2025"), None);
+    assert_eq!(any_date("5 March"), None);
+    assert_eq!(any_date(""), None);
+
+    // Forms only take VTOP's own format.
+    assert_eq!(vtop_date(" 31-dec-2025 "), Some((2025, 12, 31)));
+    assert_eq!(vtop_date("31-Nov-2025"), None);
+    assert_eq!(vtop_date("05/03/2025"), None);
+    assert_eq!(vtop_date("2025-03-05"), None);
+}
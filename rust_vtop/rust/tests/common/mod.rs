@@ -0,0 +1,82 @@
+// The fake VTOP shared by the integration tests.
+//
+// Its pages are written by hand from what the portal is known to send; no
+// scrubbed capture of the real page backs them yet. Until one does, these
+// endpoints and their parsers are unverified against VTOP itself:
+//
+//   course pages         getCourseForCoursePage, processViewStudentCourseDetail,
+//                        downloadPdf/*
+//   digital assignments  examinations/doDigitalAssignment,
+//                        examinations/processDigitalAssignment,
+//                        examinations/doDAUpload, examinations/doDAUploadOtp
+//   fees                 finance/Payments, p2p/getReceiptsApplno,
+//                        finance/getDownloadReceipt
+//   hostel               hostel/StudentGeneralOuting, hostel/saveGeneralOutingForm,
+//                        hostel/deleteGeneralOutingInfo, hostel/StudentLeaveRequest,
+//                        hostel/saveLeaveRequestForm, hostel/deleteLeaveRequestInfo
+//   grades               examinations/examGradeView/doStudentGradeView,
+//                        examinations/doStudentArrearView
+//   curriculum           academics/common/Curriculum,
+//                        academics/common/StudentRegistrationAllocation
+//   announcements        spotlight/viewSpotlight, academics/common/StudentMessages
+//   proctor              proctor/viewProctorDetails, users/image/*
+//   biometric            academics/common/BiometricInfo
+//   feedback             academics/common/StudentFeedback,
+//                        academics/common/getFeedbackQuestions,
+//                        academics/common/saveFeedback
+//
+// These endpoints are only built with the `unverified-endpoints` feature, and
+// their tests only run with it. A capture replacing a hand-written page
+// should have names, registration numbers, ids and tokens replaced, and the
+// endpoint taken off this list and out of the feature.
+
+// Each test binary uses only part of this module.
+#![allow(dead_code)]
+
+use std::net::SocketAddr;
+
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopClient;
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop_get_client::{get_vtop_client_with_config, vtop_client_login};
+use tokio::net::TcpListener;
+
+// Serves `routes` next to the login page and returns where it listens.
+// Routes are given with their full `/vtop/...` path.
+pub async fn spawn_fake_vtop(routes: Router) -> SocketAddr {
+    let app = Router::new()
+        .route(
+            "/vtop/open/page",
+            get(|| async { Html(r#"<input name="_csrf" value="t0k3n">"#) }),
+        )
+        .merge(routes);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+// Requests are rate limited per account across the test binary, so tests
+// that run side by side use different usernames.
+pub async fn logged_in(addr: SocketAddr, username: &str) -> VtopClient {
+    let config = VtopConfig {
+        base_url: format!("http://{}", addr),
+        ..VtopConfig::default()
+    };
+    logged_in_with(config, username).await
+}
+
+// Logs `username` in with the password `secret`.
+pub async fn logged_in_with(config: VtopConfig, username: &str) -> VtopClient {
+    let mut client = get_vtop_client_with_config(
+        config,
+        username.into(),
+        "secret".into(),
+        Some("SESSION=abc".into()),
+    )
+    .unwrap();
+    vtop_client_login(&mut client).await.unwrap();
+    client
+}
@@ -0,0 +1,361 @@
+mod common;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use axum::extract::{Path, State};
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Form, Router};
+use rust_lib_vitapmate::api::vtop::paraser::parsecoursepage::parse_course_page;
+use rust_lib_vitapmate::api::vtop::progress::CancelToken;
+use rust_lib_vitapmate::api::vtop::request_limiter::{LimiterConfig, RequestLimiter};
+use rust_lib_vitapmate::api::vtop::types::CourseMaterial;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::vtop_config::{VtopClientBuilder, VtopConfig};
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_course_page, fetch_course_page_courses, sync_course_materials, vtop_client_login,
+};
+use tokio::io::AsyncWrite;
+use tokio::sync::Notify;
+
+use common::logged_in;
+
+const COURSES: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Class Id</th><th>Slot</th><th>Faculty</th><th></th></tr>
+<tr><td>1</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>AP2024252000123</td><td>A1+TA1</td><td>Dr. A</td>
+<td><button onclick="processViewStudentCourseDetail('AP2024252','AP2024252000123','70123')">View</button></td></tr>
+<tr><td>2</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>AP2024252000456</td><td>B1</td><td>Dr. B</td>
+<td><button onclick="processViewStudentCourseDetail('AP2024252','AP2024252000456','70456')">View</button></td></tr>
+</table>"#;
+
+const COMPILER_PAGE: &str = r#"<div>
+<a href="javascript:vtopDownload('downloadPdf/AP2024252000123/SYLLABUS')">Syllabus</a>
+<a href="https://example.com/book">Reference book</a>
+</div>
+<table>
+<tr><th>Sl.No.</th><th>Date</th><th>Day</th><th>Topic</th><th>Material</th></tr>
+<tr><td>1</td><td>02-Jan-2025</td><td>THU</td><td>Lexical analysis</td>
+<td><a href="/vtop/downloadPdf/AP2024252000123/L1">Unit 1: Lexing</a></td></tr>
+<tr><td>2</td><td>03-Jan-2025</td><td>FRI</td><td>Parsing</td><td></td></tr>
+</table>"#;
+
+#[derive(Clone, Default)]
+struct Vtop {
+    downloads: Arc<AtomicUsize>,
+    // Lecture material never finishes downloading while set.
+    stall_lectures: Arc<AtomicBool>,
+    stalled: Arc<Notify>,
+    // The syllabus comes as a Word document instead of a PDF while set.
+    syllabus_as_docx: Arc<AtomicBool>,
+}
+
+async fn course_detail(Form(form): Form<HashMap<String, String>>) -> Response {
+    match form.get("classId").map(String::as_str) {
+        Some("AP2024252000123") if form.get("erpId").map(String::as_str) == Some("70123") => {
+            Html(COMPILER_PAGE).into_response()
+        }
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "no such class").into_response(),
+    }
+}
+
+async fn download(State(vtop): State<Vtop>, Path((_, id)): Path<(String, String)>) -> Response {
+    if vtop.stall_lectures.load(Ordering::SeqCst) && id.starts_with('L') {
+        vtop.stalled.notify_one();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    }
+    vtop.downloads.fetch_add(1, Ordering::SeqCst);
+    let extension = match vtop.syllabus_as_docx.load(Ordering::SeqCst) && id == "SYLLABUS" {
+        true => "DOCX",
+        false => "PDF",
+    };
+    let name = format!("attachment; filename=\"{}.{}\"", id, extension);
+    (
+        [(header::CONTENT_DISPOSITION, name)],
+        format!("%PDF {}", id),
+    )
+        .into_response()
+}
+
+async fn spawn_fake_vtop(vtop: Vtop) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route(
+                "/vtop/getCourseForCoursePage",
+                post(|| async { Html(COURSES) }),
+            )
+            .route("/vtop/processViewStudentCourseDetail", post(course_detail))
+            .route("/vtop/downloadPdf/:class/:id", get(download))
+            .with_state(vtop),
+    )
+    .await
+}
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vitapmate-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn part_files(folder: &std::path::Path) -> Vec<String> {
+    std::fs::read_dir(folder)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".part"))
+        .collect()
+}
+
+#[test]
+fn course_page_separates_lecture_and_course_material() {
+    let page = parse_course_page(
+        COMPILER_PAGE.into(),
+        "AP2024252".into(),
+        "AP2024252000123".into(),
+        "70123".into(),
+    );
+    assert_eq!(
+        page.materials,
+        vec![CourseMaterial {
+            title: "Syllabus".into(),
+            path: "downloadPdf/AP2024252000123/SYLLABUS".into(),
+        }]
+    );
+    assert_eq!(page.lectures.len(), 2);
+    assert_eq!(page.lectures[0].topic, "Lexical analysis");
+    assert_eq!(
+        page.lectures[0].materials[0].path,
+        "downloadPdf/AP2024252000123/L1"
+    );
+    assert!(page.lectures[1].materials.is_empty());
+}
+
+#[tokio::test]
+async fn lists_classes_and_their_course_pages() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0201").await;
+
+    let courses = fetch_course_page_courses(&client, "AP2024252".into())
+        .await
+        .unwrap();
+    assert_eq!(courses.courses.len(), 2);
+    let compiler = &courses.courses[0];
+    assert_eq!(compiler.course_code, "CSE3005");
+    assert_eq!(compiler.class_id, "AP2024252000123");
+    assert_eq!(compiler.faculty_id, "70123");
+    assert_eq!(compiler.faculty, "Dr. A");
+
+    let page = fetch_course_page(
+        &client,
+        "AP2024252".into(),
+        compiler.class_id.clone(),
+        compiler.faculty_id.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(page.lectures.len(), 2);
+}
+
+#[tokio::test]
+async fn sync_skips_material_that_is_already_on_disk() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0201").await;
+    let dir = scratch_dir("materials");
+    let directory = dir.to_string_lossy().into_owned();
+
+    let first = sync_course_materials(&client, "AP2024252".into(), directory.clone(), None)
+        .await
+        .unwrap();
+    let mut downloaded = first.downloaded.clone();
+    downloaded.sort();
+    let folder = "CSE3005 ETH Compiler Design";
+    assert_eq!(
+        downloaded,
+        vec![
+            format!("{}/Syllabus.pdf", folder),
+            format!("{}/Unit 1_ Lexing.pdf", folder),
+        ]
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.join(folder).join("Syllabus.pdf")).unwrap(),
+        "%PDF SYLLABUS"
+    );
+    // The second class's page fails; the first still synced.
+    let [failure] = first.failures.as_slice() else {
+        panic!("expected one failure, got {:?}", first.failures);
+    };
+    assert_eq!(failure.course_code, "MAT2002");
+    assert!(matches!(failure.error, VtopError::VtopServerError(_)));
+    assert_eq!(vtop.downloads.load(Ordering::SeqCst), 2);
+
+    std::fs::remove_file(dir.join(folder).join("Syllabus.pdf")).unwrap();
+    let second = sync_course_materials(&client, "AP2024252".into(), directory, None)
+        .await
+        .unwrap();
+    assert_eq!(second.unchanged, 1);
+    assert_eq!(second.downloaded, vec![format!("{}/Syllabus.pdf", folder)]);
+    assert_eq!(vtop.downloads.load(Ordering::SeqCst), 3);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn cancelled_sync_keeps_what_finished() {
+    let vtop = Vtop::default();
+    vtop.stall_lectures.store(true, Ordering::SeqCst);
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0201").await;
+    let dir = scratch_dir("materials-cancel");
+    let directory = dir.to_string_lossy().into_owned();
+    let folder = dir.join("CSE3005 ETH Compiler Design");
+
+    let cancel = CancelToken::new();
+    let (canceller, stalled) = (cancel.clone(), vtop.stalled.clone());
+    tokio::spawn(async move {
+        stalled.notified().await;
+        canceller.cancel();
+    });
+    let result =
+        sync_course_materials(&client, "AP2024252".into(), directory.clone(), Some(cancel)).await;
+    assert!(matches!(result, Err(VtopError::Cancelled)));
+    assert!(folder.join("Syllabus.pdf").exists());
+    assert_eq!(part_files(&folder), Vec::<String>::new());
+    assert_eq!(vtop.downloads.load(Ordering::SeqCst), 1);
+
+    // The next sync only fetches what the cancelled one did not.
+    vtop.stall_lectures.store(false, Ordering::SeqCst);
+    let resumed = sync_course_materials(&client, "AP2024252".into(), directory, None)
+        .await
+        .unwrap();
+    assert_eq!(resumed.unchanged, 1);
+    assert_eq!(vtop.downloads.load(Ordering::SeqCst), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn sync_never_overwrites_files_it_did_not_write() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0202").await;
+    let dir = scratch_dir("materials-foreign");
+    let folder = dir.join("CSE3005 ETH Compiler Design");
+    std::fs::create_dir_all(&folder).unwrap();
+    std::fs::write(folder.join("Syllabus.pdf"), "my annotated copy").unwrap();
+
+    let report = sync_course_materials(
+        &client,
+        "AP2024252".into(),
+        dir.to_string_lossy().into_owned(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(report
+        .downloaded
+        .contains(&"CSE3005 ETH Compiler Design/Syllabus (2).pdf".to_string()));
+    assert_eq!(
+        std::fs::read_to_string(folder.join("Syllabus.pdf")).unwrap(),
+        "my annotated copy"
+    );
+    assert_eq!(
+        std::fs::read_to_string(folder.join("Syllabus (2).pdf")).unwrap(),
+        "%PDF SYLLABUS"
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn a_material_downloaded_under_a_new_name_replaces_its_old_file() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0203").await;
+    let dir = scratch_dir("materials-renamed");
+    let directory = dir.to_string_lossy().into_owned();
+    let folder = dir.join("CSE3005 ETH Compiler Design");
+
+    sync_course_materials(&client, "AP2024252".into(), directory.clone(), None)
+        .await
+        .unwrap();
+    // Truncated on disk, and VTOP now serves it as a Word document.
+    std::fs::write(folder.join("Syllabus.pdf"), "%PD").unwrap();
+    vtop.syllabus_as_docx.store(true, Ordering::SeqCst);
+
+    let report = sync_course_materials(&client, "AP2024252".into(), directory, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.downloaded,
+        vec!["CSE3005 ETH Compiler Design/Syllabus.docx".to_string()]
+    );
+    assert!(!folder.join("Syllabus.pdf").exists());
+    assert!(folder.join("Syllabus.docx").exists());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// Records how many requests the limiter counts while the body is written.
+struct Probe {
+    limiter: Arc<RequestLimiter>,
+    seen: Vec<usize>,
+}
+
+impl AsyncWrite for Probe {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let in_flight = self.limiter.metrics().in_flight;
+        self.seen.push(in_flight);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[tokio::test]
+async fn downloads_hold_their_permit_until_the_body_is_read() {
+    let addr = common::spawn_fake_vtop(
+        Router::new().route("/vtop/downloadPdf/slides", get(|| async { "%PDF slides" })),
+    )
+    .await;
+    let limiter = Arc::new(RequestLimiter::new(LimiterConfig::default()));
+    let config = VtopConfig {
+        base_url: format!("http://{}", addr),
+        ..VtopConfig::default()
+    };
+    let mut client = VtopClientBuilder::new()
+        .config(config)
+        .limiter(limiter.clone())
+        .build("21BCE0301".into(), "secret".into())
+        .unwrap();
+    client.set_cookie("SESSION=abc".into());
+    vtop_client_login(&mut client).await.unwrap();
+
+    let material = CourseMaterial {
+        title: "Slides".into(),
+        path: "downloadPdf/slides".into(),
+    };
+    let mut probe = Probe {
+        limiter: limiter.clone(),
+        seen: vec![],
+    };
+    let downloaded = client
+        .download_material(&material, &mut probe)
+        .await
+        .unwrap();
+    assert_eq!(downloaded.bytes, 11);
+    assert!(!probe.seen.is_empty());
+    assert!(probe.seen.iter().all(|&in_flight| in_flight == 1));
+    assert_eq!(limiter.metrics().in_flight, 0);
+}
@@ -0,0 +1,101 @@
+use rust_lib_vitapmate::api::vtop::curriculum::curriculum_progress;
+use rust_lib_vitapmate::api::vtop::paraser::parsecurriculum::{parse_allocation, parse_curriculum};
+use rust_lib_vitapmate::api::vtop::paraser::parsegrades::parse_semester_grades;
+use rust_lib_vitapmate::api::vtop::types::{CurriculumCourse, TimetableData, TimetableSlot};
+
+const CURRICULUM: &str = r#"<table>
+<tr><th>Category</th><th>Description</th><th>Credits Required</th><th>Credits Earned</th></tr>
+<tr><td>UC</td><td>University Core</td><td>10</td><td></td></tr>
+<tr><td>PE</td><td>Programme Elective</td><td>6</td><td>3</td></tr>
+</table>
+<table>
+<tr><th>Sl.No.</th><th>Category</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Credits</th></tr>
+<tr><td>1</td><td>UC</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>4</td></tr>
+<tr><td>2</td><td>UC</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>3</td></tr>
+<tr><td>3</td><td>UC</td><td>ENG1001</td><td>Technical English</td><td>TH</td><td>3</td></tr>
+</table>"#;
+
+const ALLOCATION: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Category</th><th>Credits</th><th>Status</th></tr>
+<tr><td>1</td><td>CSE4019</td><td>Image Processing</td><td>ETH</td><td>PE</td><td>3</td><td>Allotted</td></tr>
+</table>"#;
+
+const GRADE_VIEW: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>C</th><th>Grade</th><th>Result</th></tr>
+<tr><td>1</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>4</td><td>A</td><td>PASS</td></tr>
+<tr><td>2</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>3</td><td>F</td><td>FAIL</td></tr>
+</table>"#;
+
+fn slot(course_code: &str) -> TimetableSlot {
+    TimetableSlot {
+        serial: "1".into(),
+        day: "MON".into(),
+        slot: "A1".into(),
+        course_code: course_code.into(),
+        course_type: "TH".into(),
+        room_no: "101".into(),
+        block: "AB1".into(),
+        start_time: "08:00".into(),
+        end_time: "08:50".into(),
+        name: String::new(),
+        is_lab: false,
+        faculty: String::new(),
+    }
+}
+
+#[test]
+fn curriculum_tables_are_told_apart_by_header() {
+    let curriculum = parse_curriculum(CURRICULUM.into());
+    let [core, elective] = curriculum.categories.as_slice() else {
+        panic!("expected two categories, got {:?}", curriculum.categories);
+    };
+    assert_eq!(core.code, "UC");
+    assert_eq!(core.name, "University Core");
+    assert_eq!(core.credits_required, 10.0);
+    assert_eq!(core.credits_earned, None);
+    assert_eq!(elective.credits_earned, Some(3.0));
+    assert_eq!(curriculum.courses.len(), 3);
+    assert_eq!(curriculum.courses[1].course_code, "MAT2002");
+    assert_eq!(curriculum.courses[1].category, "UC");
+    assert_eq!(curriculum.courses[1].credits, 3.0);
+
+    let allocation = parse_allocation(ALLOCATION.into());
+    assert_eq!(allocation.allocations.len(), 1);
+    assert_eq!(allocation.allocations[0].category, "PE");
+    assert_eq!(allocation.allocations[0].status, "Allotted");
+}
+
+#[test]
+fn progress_splits_courses_and_credits_by_category() {
+    let curriculum = parse_curriculum(CURRICULUM.into());
+    let allocation = parse_allocation(ALLOCATION.into());
+    let grades = parse_semester_grades(GRADE_VIEW.into(), "AP2024252".into()).grades;
+    let timetable = TimetableData {
+        // The repeated slot is the same course on another day.
+        slots: vec![slot("MAT2002"), slot("CSE4019"), slot("CSE4019")],
+        semester_id: "AP2025262".into(),
+        update_time: 0,
+    };
+
+    let progress = curriculum_progress(&curriculum, &allocation, &grades, &timetable);
+    let codes = |courses: &[CurriculumCourse]| -> Vec<String> {
+        courses.iter().map(|c| c.course_code.clone()).collect()
+    };
+    assert_eq!(codes(&progress.completed), ["CSE3005"]);
+    assert_eq!(codes(&progress.registered), ["MAT2002", "CSE4019"]);
+    assert_eq!(codes(&progress.pending), ["ENG1001"]);
+
+    let [core, elective] = progress.categories.as_slice() else {
+        panic!("expected two categories, got {:?}", progress.categories);
+    };
+    // Counted from the grades, since VTOP left it blank.
+    assert_eq!(core.credits_earned, 4.0);
+    assert_eq!(core.credits_registered, 3.0);
+    assert_eq!(core.credits_remaining, 3.0);
+    // VTOP's figure; the elective's category comes from the allocation.
+    assert_eq!(elective.credits_earned, 3.0);
+    assert_eq!(elective.credits_registered, 3.0);
+    assert_eq!(elective.credits_remaining, 0.0);
+    assert_eq!(progress.credits_required, 16.0);
+    assert_eq!(progress.credits_earned, 7.0);
+}
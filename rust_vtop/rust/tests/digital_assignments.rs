@@ -0,0 +1,182 @@
+mod common;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::post;
+use axum::{Form, Router};
+use futures::FutureExt;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_digital_assignments, upload_digital_assignment,
+};
+
+use common::logged_in;
+
+const COURSES: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Class Nbr</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Faculty</th><th></th></tr>
+<tr><td>1</td><td>AP2024252000123</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>Dr. A</td>
+<td><button onclick="javascript:myFunction('AP2024252000123');">Process</button></td></tr>
+<tr><td>2</td><td>AP2024252000456</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>Dr. B</td>
+<td><button onclick="javascript:myFunction('AP2024252000456');">Process</button></td></tr>
+</table>"#;
+
+const ASSIGNMENTS: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Title</th><th>Max. Mark</th><th>Weightage %</th><th>Due Date</th><th>QP</th><th>Last Updated</th><th>Upload</th></tr>
+<tr><td>1</td><td>Assignment 1</td><td>10</td><td>10</td><td>15-Mar-2025</td>
+<td><a href="javascript:vtopDownload('examinations/doDownloadQuestion/Am01/AP2024252000123')">Download</a></td>
+<td>10-Mar-2025 11:02</td><td><button onclick="doDAUpload('Am01','AP2024252000123')">Upload</button></td></tr>
+<tr><td>2</td><td>Assignment 2</td><td>10</td><td>10</td><td>01-Feb-2025</td>
+<td><a href="javascript:vtopDownload('examinations/doDownloadQuestion/Am02/AP2024252000123')">Download</a></td>
+<td>-</td><td></td></tr>
+</table>"#;
+
+const OTP_FORM: &str = r#"<span class="text-info">OTP sent to your registered e-mail</span>
+<form><input name="otp"></form>"#;
+
+#[derive(Clone, Default)]
+struct Vtop {
+    uploads: Arc<Mutex<Vec<String>>>,
+}
+
+async fn upload(State(vtop): State<Vtop>, body: Bytes) -> Html<&'static str> {
+    vtop.uploads
+        .lock()
+        .unwrap()
+        .push(String::from_utf8_lossy(&body).into_owned());
+    Html(OTP_FORM)
+}
+
+async fn verify_otp(Form(form): Form<HashMap<String, String>>) -> Html<&'static str> {
+    match form.get("otp").map(String::as_str) {
+        Some("123456") => Html(r#"<div class="alert">File uploaded successfully</div>"#),
+        _ => Html(
+            r#"<span class="text-danger">Invalid OTP. Please try again.</span><input name="otp">"#,
+        ),
+    }
+}
+
+// Only the compiler course's page loads.
+async fn assignments(Form(form): Form<HashMap<String, String>>) -> Response {
+    match form.get("classId").map(String::as_str) {
+        Some("AP2024252000123") => Html(ASSIGNMENTS).into_response(),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "no such class").into_response(),
+    }
+}
+
+async fn spawn_fake_vtop(vtop: Vtop) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route(
+                "/vtop/examinations/doDigitalAssignment",
+                post(|| async { Html(COURSES) }),
+            )
+            .route(
+                "/vtop/examinations/processDigitalAssignment",
+                post(assignments),
+            )
+            .route("/vtop/examinations/doDAUpload", post(upload))
+            .route("/vtop/examinations/doDAUploadOtp", post(verify_otp))
+            .with_state(vtop),
+    )
+    .await
+}
+
+fn answer_file(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("{}-{}", std::process::id(), name));
+    std::fs::write(&path, b"my answers").unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[tokio::test]
+async fn lists_assignments_per_course() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0301").await;
+
+    let report = fetch_digital_assignments(&client, "AP2024252".into(), None)
+        .await
+        .unwrap();
+    let data = &report.assignments;
+    let [course] = data.courses.as_slice() else {
+        panic!("expected one course, got {:?}", data.courses);
+    };
+    assert_eq!(course.class_id, "AP2024252000123");
+    assert_eq!(course.course_code, "CSE3005");
+
+    let [open, closed] = course.assignments.as_slice() else {
+        panic!("expected two assignments, got {:?}", course.assignments);
+    };
+    assert_eq!(open.code, "Am01");
+    assert_eq!(open.due_date, "15-Mar-2025");
+    assert!(open.uploaded && open.can_upload);
+    assert_eq!(
+        open.question_paper.as_ref().unwrap().path,
+        "examinations/doDownloadQuestion/Am01/AP2024252000123"
+    );
+    assert_eq!(closed.code, "Am02");
+    assert!(!closed.uploaded && !closed.can_upload);
+
+    // The other course's page failed without losing this one.
+    let [failure] = report.failures.as_slice() else {
+        panic!("expected one failure, got {:?}", report.failures);
+    };
+    assert_eq!(failure.class_id, "AP2024252000456");
+    assert_eq!(failure.course_code, "MAT2002");
+    assert!(matches!(failure.error, VtopError::VtopServerError(_)));
+}
+
+#[tokio::test]
+async fn upload_asks_for_the_otp_until_vtop_accepts_it() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0301").await;
+    let prompts = Arc::new(Mutex::new(vec![]));
+    let answers = Arc::new(Mutex::new(vec!["123456", "000000"]));
+
+    let seen = prompts.clone();
+    let message = upload_digital_assignment(
+        &client,
+        "AP2024252000123".into(),
+        "Am01".into(),
+        answer_file("da.pdf"),
+        move |prompt| {
+            seen.lock().unwrap().push(prompt);
+            let answer = answers.lock().unwrap().pop().map(str::to_string);
+            async move { answer }.boxed()
+        },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(message, "File uploaded successfully");
+    assert_eq!(
+        *prompts.lock().unwrap(),
+        vec![
+            "OTP sent to your registered e-mail".to_string(),
+            "Invalid OTP. Please try again.".to_string(),
+        ]
+    );
+    let uploads = vtop.uploads.lock().unwrap();
+    assert_eq!(uploads.len(), 1);
+    assert!(uploads[0].contains("filename=\"") && uploads[0].contains("da.pdf"));
+    assert!(uploads[0].contains("my answers"));
+    assert!(uploads[0].contains("Am01"));
+}
+
+#[tokio::test]
+async fn declining_the_otp_cancels_the_upload() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0301").await;
+
+    let result = upload_digital_assignment(
+        &client,
+        "AP2024252000123".into(),
+        "Am01".into(),
+        answer_file("declined.pdf"),
+        |_| async { None }.boxed(),
+    )
+    .await;
+    assert!(matches!(result, Err(VtopError::Cancelled)));
+}
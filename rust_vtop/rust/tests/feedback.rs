@@ -0,0 +1,214 @@
+mod common;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::post;
+use axum::{Form, Router};
+use rust_lib_vitapmate::api::vtop::paraser::parsefeedback::parse_feedback_questions;
+use rust_lib_vitapmate::api::vtop::types::{
+    FeedbackAnswer, FeedbackQuestionKind, FeedbackResponse,
+};
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::vtop_errors::FormError;
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_feedback_forms, fetch_feedback_questions, submit_feedback,
+};
+
+use common::logged_in;
+
+const QUESTIONS: &str = r#"<form>
+<input type="hidden" name="formId" value="FB01">
+<table>
+<tr><td>The faculty explained concepts clearly</td><td>
+<input type="radio" name="q1" value="1" required> <input type="radio" name="q1" value="2">
+<input type="radio" name="q1" value="3"> <input type="radio" name="q1" value="4">
+<input type="radio" name="q1" value="5"></td></tr>
+<tr><td>Pace of the course</td><td><select name="q2" required><option value="">--</option>
+<option value="SLOW">Slow</option><option value="OK">Just right</option><option value="FAST">Fast</option></select></td></tr>
+<tr><td>Other comments</td><td><textarea name="q3"></textarea></td></tr>
+</table>
+</form>"#;
+
+fn answer(question_id: &str, answer: FeedbackAnswer) -> FeedbackResponse {
+    FeedbackResponse {
+        question_id: question_id.into(),
+        answer,
+    }
+}
+
+#[derive(Clone, Default)]
+struct Vtop {
+    submitted: Arc<Mutex<Option<HashMap<String, String>>>>,
+    // The forms page fails once feedback has been saved.
+    forms_fail_after_save: bool,
+}
+
+async fn forms(State(vtop): State<Vtop>) -> Response {
+    let saved = vtop.submitted.lock().unwrap().is_some();
+    if saved && vtop.forms_fail_after_save {
+        return (StatusCode::SERVICE_UNAVAILABLE, "busy").into_response();
+    }
+    let compilers = if saved {
+        r#"<td>Submitted</td><td></td>"#
+    } else {
+        r#"<td>Not Submitted</td><td><button onclick="openFeedback('FB01','AP2024252000123')">Give</button></td>"#
+    };
+    Html(format!(
+        r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Type</th><th>Faculty</th><th>Status</th><th></th></tr>
+<tr><td>1</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>Dr. A</td>{}</tr>
+<tr><td>2</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>Dr. B</td><td>Not Submitted</td>
+<td><button onclick="openFeedback('FB02','AP2024252000124')">Give</button></td></tr>
+</table>"#,
+        compilers
+    ))
+    .into_response()
+}
+
+async fn save(
+    State(vtop): State<Vtop>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Html<&'static str> {
+    *vtop.submitted.lock().unwrap() = Some(form);
+    Html(r#"<div class="alert">Feedback submitted successfully</div>"#)
+}
+
+async fn spawn_fake_vtop(vtop: Vtop) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route("/vtop/academics/common/StudentFeedback", post(forms))
+            .route(
+                "/vtop/academics/common/getFeedbackQuestions",
+                post(|| async { Html(QUESTIONS) }),
+            )
+            .route("/vtop/academics/common/saveFeedback", post(save))
+            .with_state(vtop),
+    )
+    .await
+}
+
+#[test]
+fn answers_are_checked_against_the_questions() {
+    let form = parse_feedback_questions(QUESTIONS.into(), "AP2024252".into(), "FB01".into());
+    let [clarity, pace, comments] = form.questions.as_slice() else {
+        panic!("expected three questions, got {:?}", form.questions);
+    };
+    assert_eq!(clarity.text, "The faculty explained concepts clearly");
+    assert_eq!(
+        clarity.kind,
+        FeedbackQuestionKind::Rating { min: 1, max: 5 }
+    );
+    assert!(clarity.required);
+    assert_eq!(
+        pace.kind,
+        FeedbackQuestionKind::Choice {
+            options: vec!["SLOW".into(), "OK".into(), "FAST".into()]
+        }
+    );
+    assert_eq!(comments.kind, FeedbackQuestionKind::Text);
+    assert!(!comments.required);
+
+    let complete = [
+        answer("q1", FeedbackAnswer::Rating(4)),
+        answer("q2", FeedbackAnswer::Choice("OK".into())),
+    ];
+    assert_eq!(form.validate(&complete), Ok(()));
+    assert_eq!(
+        form.validate(&complete[..1]),
+        Err(FormError::UnansweredQuestion("Pace of the course".into()))
+    );
+    assert_eq!(
+        form.validate(&[answer("q1", FeedbackAnswer::Rating(6))]),
+        Err(FormError::InvalidAnswer(
+            "The faculty explained concepts clearly".into()
+        ))
+    );
+    assert_eq!(
+        form.validate(&[answer("q2", FeedbackAnswer::Text("OK".into()))]),
+        Err(FormError::InvalidAnswer("Pace of the course".into()))
+    );
+    assert_eq!(
+        form.validate(&[answer("q9", FeedbackAnswer::Rating(1))]),
+        Err(FormError::UnknownQuestion("q9".into()))
+    );
+}
+
+#[tokio::test]
+async fn submitting_reports_the_forms_still_outstanding() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE1001").await;
+
+    let forms = fetch_feedback_forms(&client, "AP2024252".into())
+        .await
+        .unwrap();
+    let pending = forms.pending();
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].form_id, "FB01");
+    assert_eq!(pending[0].class_id, "AP2024252000123");
+    assert_eq!(pending[0].course_code, "CSE3005");
+
+    let form = fetch_feedback_questions(&client, "AP2024252".into(), pending[0].clone())
+        .await
+        .unwrap();
+    let invalid = submit_feedback(&client, form.clone(), vec![]).await;
+    assert!(matches!(
+        invalid,
+        Err(VtopError::InvalidForm(FormError::UnansweredQuestion(_)))
+    ));
+    assert!(vtop.submitted.lock().unwrap().is_none());
+
+    let result = submit_feedback(
+        &client,
+        form,
+        vec![
+            answer("q1", FeedbackAnswer::Rating(5)),
+            answer("q2", FeedbackAnswer::Choice("FAST".into())),
+            answer("q3", FeedbackAnswer::Text("  Great labs ".into())),
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.message, "Feedback submitted successfully");
+    let [left] = result.outstanding.as_deref().unwrap_or_default() else {
+        panic!(
+            "expected one outstanding form, got {:?}",
+            result.outstanding
+        );
+    };
+    assert_eq!(left.course_code, "MAT2002");
+
+    let sent = vtop.submitted.lock().unwrap().clone().unwrap();
+    assert_eq!(sent["formId"], "FB01");
+    assert_eq!(sent["q1"], "5");
+    assert_eq!(sent["q2"], "FAST");
+    assert_eq!(sent["q3"], "Great labs");
+}
+
+#[tokio::test]
+async fn saved_feedback_is_reported_even_when_the_refresh_fails() {
+    let vtop = Vtop {
+        forms_fail_after_save: true,
+        ..Vtop::default()
+    };
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE1002").await;
+    let form = parse_feedback_questions(QUESTIONS.into(), "AP2024252".into(), "FB01".into());
+
+    let result = submit_feedback(
+        &client,
+        form,
+        vec![
+            answer("q1", FeedbackAnswer::Rating(3)),
+            answer("q2", FeedbackAnswer::Choice("OK".into())),
+        ],
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.message, "Feedback submitted successfully");
+    assert!(result.outstanding.is_none());
+    assert!(vtop.submitted.lock().unwrap().is_some());
+}
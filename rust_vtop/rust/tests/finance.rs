@@ -0,0 +1,82 @@
+mod common;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::post;
+use axum::{Form, Router};
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_fee_dues, fetch_fee_receipt, fetch_payment_history,
+};
+
+use common::logged_in;
+
+const DUES: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Fee Head</th><th>Semester</th><th>Amount</th><th>Due Date</th><th>Fine</th><th>Status</th></tr>
+<tr><td>1</td><td>Tuition Fee</td><td>Winter Semester 2024-25</td><td>1,25,000.00</td><td>10-Jan-2025</td><td>0.00</td><td>Not Paid</td></tr>
+<tr><td>2</td><td>Hostel Fee</td><td>2024-25</td><td>80,000.00</td><td>10-Jan-2025</td><td>500.00</td><td>Not Paid</td></tr>
+<tr><td colspan="6">Total</td><td>2,05,500.00</td></tr>
+</table>"#;
+
+const HISTORY: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Receipt No</th><th>Date</th><th>Description</th><th>Amount</th><th>Mode</th><th>Status</th><th></th></tr>
+<tr><td>1</td><td>RC1001</td><td>05-Jul-2024</td><td>Tuition Fee</td><td>1,25,000.00</td><td>Online</td><td>Success</td>
+<td><button onclick="doDownloadReceipt('RC1001','2024AP01')">Receipt</button></td></tr>
+<tr><td>2</td><td>-</td><td>06-Jul-2024</td><td>Hostel Fee</td><td>80,000.00</td><td>Online</td><td>Failed</td><td></td></tr>
+</table>"#;
+
+async fn receipt(Form(form): Form<HashMap<String, String>>) -> Response {
+    match form.get("receiptNo").map(String::as_str) {
+        Some("RC1001") => b"%PDF-1.4 receipt".to_vec().into_response(),
+        _ => Html(r#"<span class="text-danger">No receipt found</span>"#).into_response(),
+    }
+}
+
+async fn spawn_fake_vtop() -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route("/vtop/finance/Payments", post(|| async { Html(DUES) }))
+            .route(
+                "/vtop/p2p/getReceiptsApplno",
+                post(|| async { Html(HISTORY) }),
+            )
+            .route("/vtop/finance/getDownloadReceipt", post(receipt)),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn dues_and_payments_parse_into_models() {
+    let client = logged_in(spawn_fake_vtop().await, "21BCE0401").await;
+
+    let dues = fetch_fee_dues(&client).await.unwrap();
+    assert_eq!(dues.dues.len(), 2);
+    assert_eq!(dues.dues[0].fee_head, "Tuition Fee");
+    assert_eq!(dues.dues[1].fine, "500.00");
+    assert_eq!(dues.total_due, "2,05,500.00");
+
+    let history = fetch_payment_history(&client).await.unwrap();
+    let [paid, failed] = history.payments.as_slice() else {
+        panic!("expected two payments, got {:?}", history.payments);
+    };
+    assert_eq!(paid.receipt_no, "RC1001");
+    assert!(paid.has_receipt);
+    assert_eq!(failed.status, "Failed");
+    assert!(!failed.has_receipt);
+}
+
+#[tokio::test]
+async fn receipts_download_as_pdf_bytes() {
+    let client = logged_in(spawn_fake_vtop().await, "21BCE0401").await;
+
+    let pdf = fetch_fee_receipt(&client, "RC1001".into()).await.unwrap();
+    assert_eq!(pdf, b"%PDF-1.4 receipt");
+
+    let missing = fetch_fee_receipt(&client, "RC9999".into()).await;
+    match missing {
+        Err(VtopError::RequestRejected(message)) => assert_eq!(message, "No receipt found"),
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+}
@@ -0,0 +1,56 @@
+use rust_lib_vitapmate::api::vtop::gpa::{cgpa, grade_points, semester_gpa};
+use rust_lib_vitapmate::api::vtop::paraser::parsegrades::{parse_arrears, parse_semester_grades};
+
+const GRADE_VIEW: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>L</th><th>P</th><th>C</th><th>Grading Type</th><th>Grand Total</th><th>Grade</th><th>Result</th></tr>
+<tr><td>1</td><td>CSE3005</td><td>Compiler Design</td><td>ETH</td><td>3</td><td>2</td><td>4</td><td>RG</td><td>81</td><td>A</td><td>PASS</td></tr>
+<tr><td>2</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>3</td><td>0</td><td>3</td><td>AG</td><td>32</td><td>F</td><td>FAIL</td></tr>
+<tr><td>3</td><td>STS2001</td><td>Soft Skills</td><td>SS</td><td>0</td><td>0</td><td>1</td><td>PF</td><td>-</td><td>P</td><td>PASS</td></tr>
+</table>
+<p>CGPA : 8.80 GPA : 5.14</p>"#;
+
+const ARREARS: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Course Code</th><th>Course Title</th><th>Course Type</th><th>Credits</th><th>Exam Month</th><th>Grade</th><th>Grade Point</th><th>Result Status</th></tr>
+<tr><td>1</td><td>MAT2002</td><td>Discrete Mathematics</td><td>TH</td><td>3</td><td>Jun-2025</td><td>C</td><td>7</td><td>PASS</td></tr>
+</table>"#;
+
+#[test]
+fn grade_view_columns_are_found_by_header() {
+    let sheet = parse_semester_grades(GRADE_VIEW.into(), "AP2024252".into());
+    assert_eq!(sheet.grades.len(), 3);
+    let compiler = &sheet.grades[0];
+    assert_eq!(compiler.course_code, "CSE3005");
+    assert_eq!(compiler.course_type, "ETH");
+    assert_eq!(compiler.credits, 4.0);
+    assert_eq!(compiler.grade, "A");
+    assert_eq!(compiler.result_status, "PASS");
+    assert_eq!(sheet.published_gpa, Some(5.14));
+
+    let arrears = parse_arrears(ARREARS.into());
+    let [retake] = arrears.records.as_slice() else {
+        panic!("expected one arrear, got {:?}", arrears.records);
+    };
+    assert_eq!(retake.exam_month, "Jun-2025");
+    assert_eq!(retake.grade_points, Some(7.0));
+}
+
+#[test]
+fn results_feed_straight_into_gpa() {
+    assert_eq!(grade_points("s"), Some(10.0));
+    assert_eq!(grade_points("P"), None);
+
+    let sheet = parse_semester_grades(GRADE_VIEW.into(), "AP2024252".into());
+    // (4 * 9 + 3 * 0) / 7; the pass/fail course carries no points.
+    let semester = semester_gpa(&sheet.grades);
+    assert!((semester.gpa - 36.0 / 7.0).abs() < 1e-4);
+    assert_eq!(semester.credits_counted, 7.0);
+    assert_eq!(semester.credits_earned, 4.0);
+
+    // Before the re-exam only the passed course counts; afterwards the
+    // re-exam grade replaces the F.
+    assert_eq!(cgpa(&sheet.grades).gpa, 9.0);
+    let arrears = parse_arrears(ARREARS.into());
+    let overall = cgpa(sheet.grades.iter().chain(&arrears.records));
+    assert!((overall.gpa - (4.0 * 9.0 + 3.0 * 7.0) / 7.0).abs() < 1e-4);
+    assert_eq!(overall.credits_earned, 7.0);
+}
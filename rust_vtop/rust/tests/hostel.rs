@@ -0,0 +1,181 @@
+mod common;
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::Html;
+use axum::routing::post;
+use axum::{Form, Router};
+use rust_lib_vitapmate::api::vtop::types::{HostelRequest, HostelRequestKind};
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::vtop_errors::FormError;
+use rust_lib_vitapmate::api::vtop_get_client::{
+    cancel_hostel_application, fetch_hostel_applications, submit_hostel_request,
+};
+
+use common::logged_in;
+
+const OUTINGS: &str = r#"<table>
+<tr><th>Sl.No.</th><th>Id</th><th>Place</th><th>Purpose</th><th>From Date</th><th>From Time</th><th>To Date</th><th>To Time</th><th>Approver</th><th>Status</th><th></th></tr>
+<tr><td>1</td><td>OUT2001</td><td>Vijayawada</td><td>Shopping</td><td>08-Mar-2025</td><td>09:00</td><td>08-Mar-2025</td><td>18:00</td><td>Warden A</td><td>Pending</td>
+<td><button onclick="deleteOuting('OUT2001')">Cancel</button></td></tr>
+<tr><td>2</td><td>OUT1990</td><td>Guntur</td><td>Hospital</td><td>01-Mar-2025</td><td>10:00</td><td>01-Mar-2025</td><td>16:00</td><td>Warden A</td><td>Approved</td><td></td></tr>
+</table>"#;
+
+#[derive(Clone, Default)]
+struct Vtop {
+    submissions: Arc<AtomicUsize>,
+}
+
+async fn save_outing(
+    State(vtop): State<Vtop>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Html<&'static str> {
+    vtop.submissions.fetch_add(1, Ordering::SeqCst);
+    match form.get("fromDate").map(String::as_str) {
+        Some("09-Mar-2025") => {
+            Html(r#"<span class="text-danger">Outing already applied for this date</span>"#)
+        }
+        Some("10-Mar-2025") => Html(r#"<div class="alert">Outing request Unsuccessful</div>"#),
+        Some("11-Mar-2025") => {
+            Html(r#"<div class="alert">Outing request not successful, try again</div>"#)
+        }
+        _ => Html(r#"<div class="alert">Outing request saved successfully</div>"#),
+    }
+}
+
+async fn delete_outing(Form(form): Form<HashMap<String, String>>) -> Html<String> {
+    Html(format!(
+        r#"<div class="alert">Outing {} cancelled successfully</div>"#,
+        form["leaveId"]
+    ))
+}
+
+async fn spawn_fake_vtop(vtop: Vtop) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route(
+                "/vtop/hostel/StudentGeneralOuting",
+                post(|| async { Html(OUTINGS) }),
+            )
+            .route("/vtop/hostel/saveGeneralOutingForm", post(save_outing))
+            .route("/vtop/hostel/deleteGeneralOutingInfo", post(delete_outing))
+            .with_state(vtop),
+    )
+    .await
+}
+
+fn outing(date: &str, from: &str, to: &str) -> HostelRequest {
+    HostelRequest {
+        kind: HostelRequestKind::Outing,
+        place: "Vijayawada".into(),
+        purpose: "Shopping".into(),
+        from_date: date.into(),
+        from_time: from.into(),
+        to_date: date.into(),
+        to_time: to.into(),
+        contact_number: "9876543210".into(),
+    }
+}
+
+#[test]
+fn requests_are_checked_before_submission() {
+    assert_eq!(outing("08-Mar-2025", "09:00", "18:00").validate(), Ok(()));
+    assert_eq!(
+        outing("08-Mar-2025", "18:00", "09:00").validate(),
+        Err(FormError::EndsBeforeStart)
+    );
+    assert_eq!(
+        outing("30-Feb-2025", "09:00", "18:00").validate(),
+        Err(FormError::InvalidDate("30-Feb-2025".into()))
+    );
+    assert_eq!(
+        outing("08-Mar-2025", "9am", "18:00").validate(),
+        Err(FormError::InvalidTime("9am".into()))
+    );
+
+    let mut overnight = outing("08-Mar-2025", "09:00", "18:00");
+    overnight.to_date = "09-Mar-2025".into();
+    assert_eq!(overnight.validate(), Err(FormError::OutingSpansDays));
+    overnight.kind = HostelRequestKind::Leave;
+    assert_eq!(overnight.validate(), Ok(()));
+
+    let mut blank = outing("08-Mar-2025", "09:00", "18:00");
+    blank.purpose = "  ".into();
+    assert_eq!(
+        blank.validate(),
+        Err(FormError::MissingField("purpose".into()))
+    );
+    blank.purpose = "Shopping".into();
+    blank.contact_number = "12345".into();
+    assert_eq!(
+        blank.validate(),
+        Err(FormError::InvalidPhone("12345".into()))
+    );
+}
+
+#[tokio::test]
+async fn lists_outings_with_status_and_approver() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0501").await;
+
+    let data = fetch_hostel_applications(&client, HostelRequestKind::Outing)
+        .await
+        .unwrap();
+    let [pending, approved] = data.applications.as_slice() else {
+        panic!("expected two outings, got {:?}", data.applications);
+    };
+    assert_eq!(pending.id, "OUT2001");
+    assert_eq!(pending.approver, "Warden A");
+    assert!(pending.can_cancel);
+    assert_eq!(approved.status, "Approved");
+    assert!(!approved.can_cancel);
+
+    let message = cancel_hostel_application(&client, HostelRequestKind::Outing, "OUT2001".into())
+        .await
+        .unwrap();
+    assert_eq!(message, "Outing OUT2001 cancelled successfully");
+}
+
+#[tokio::test]
+async fn submissions_report_what_went_wrong() {
+    let vtop = Vtop::default();
+    let client = logged_in(spawn_fake_vtop(vtop.clone()).await, "21BCE0501").await;
+
+    let invalid = submit_hostel_request(&client, outing("08-Mar-2025", "18:00", "09:00")).await;
+    assert!(matches!(
+        invalid,
+        Err(VtopError::InvalidForm(FormError::EndsBeforeStart))
+    ));
+    assert_eq!(vtop.submissions.load(Ordering::SeqCst), 0);
+
+    let saved = submit_hostel_request(&client, outing("08-Mar-2025", "09:00", "18:00"))
+        .await
+        .unwrap();
+    assert_eq!(saved, "Outing request saved successfully");
+
+    match submit_hostel_request(&client, outing("09-Mar-2025", "09:00", "18:00")).await {
+        Err(VtopError::RequestRejected(message)) => {
+            assert_eq!(message, "Outing already applied for this date")
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+    assert_eq!(vtop.submissions.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn unsuccessful_notices_are_rejections() {
+    let client = logged_in(spawn_fake_vtop(Vtop::default()).await, "21BCE0501").await;
+
+    for (date, expected) in [
+        ("10-Mar-2025", "Outing request Unsuccessful"),
+        ("11-Mar-2025", "Outing request not successful, try again"),
+    ] {
+        match submit_hostel_request(&client, outing(date, "09:00", "18:00")).await {
+            Err(VtopError::RequestRejected(message)) => assert_eq!(message, expected),
+            other => panic!("expected a rejection, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use rust_lib_vitapmate::api::live_events::{
+    AccessDenied, AccessTokens, EventHub, Subscription, SubscriptionItem, Topic,
+};
+use serde_json::json;
+
+async fn next_event_id(subscription: &mut Subscription) -> u64 {
+    match tokio::time::timeout(Duration::from_secs(1), subscription.next()).await {
+        Ok(Some(SubscriptionItem::Event(event))) => event.id,
+        Ok(Some(SubscriptionItem::Resync)) => panic!("unexpected resync"),
+        _ => panic!("no event"),
+    }
+}
+
+#[test]
+fn topics_parse_with_normalised_owner() {
+    assert_eq!(Topic::parse(" faculty-cabin "), Some(Topic::FacultyCabin));
+    assert_eq!(
+        Topic::parse("marks: 21bce0001"),
+        Some(Topic::Marks("21BCE0001".into()))
+    );
+    assert_eq!(
+        Topic::parse("announcements:21BCE0001"),
+        Some(Topic::Announcements("21BCE0001".into()))
+    );
+    assert_eq!(Topic::parse("marks:"), None);
+    assert_eq!(Topic::parse("grades:21BCE0001"), None);
+    assert_eq!(Topic::parse("faculty-cabin:21BCE0001"), None);
+
+    let topic = Topic::parse("attendance:21bce0001").unwrap();
+    assert_eq!(topic.to_string(), "attendance:21BCE0001");
+    assert_eq!(topic.owner(), Some("21BCE0001"));
+    assert_eq!(Topic::FacultyCabin.owner(), None);
+}
+
+#[tokio::test]
+async fn replays_buffered_events_after_last_event_id() {
+    let hub = EventHub::new(8);
+    let marks = Topic::Marks("21BCE0001".into());
+    let other = Topic::Marks("21BCE0002".into());
+    let first = hub.publish(&marks, "mark", json!(1));
+    hub.publish(&other, "mark", json!(2));
+    let third = hub.publish(&marks, "mark", json!(3));
+
+    let mut subscription = hub.subscribe(vec![marks.clone()], Some(first));
+    assert_eq!(next_event_id(&mut subscription).await, third);
+
+    // Live events follow the replay.
+    let fourth = hub.publish(&marks, "mark", json!(4));
+    assert_eq!(next_event_id(&mut subscription).await, fourth);
+}
+
+#[tokio::test]
+async fn resyncs_when_last_event_id_is_gone_or_unknown() {
+    let hub = EventHub::new(2);
+    let marks = Topic::Marks("21BCE0001".into());
+    let first = hub.publish(&marks, "mark", json!(1));
+    for n in 2..=4 {
+        hub.publish(&marks, "mark", json!(n));
+    }
+
+    // Events 2 and 3 are no longer buffered.
+    let mut subscription = hub.subscribe(vec![marks.clone()], Some(first));
+    assert!(matches!(
+        subscription.next().await,
+        Some(SubscriptionItem::Resync)
+    ));
+
+    // An id from before a restart.
+    let mut subscription = hub.subscribe(vec![marks.clone()], Some(100));
+    assert!(matches!(
+        subscription.next().await,
+        Some(SubscriptionItem::Resync)
+    ));
+
+    // The oldest buffered event minus one is still fine.
+    let mut subscription = hub.subscribe(vec![marks], Some(2));
+    assert_eq!(next_event_id(&mut subscription).await, 3);
+}
+
+#[test]
+fn publishes_only_changed_payloads() {
+    let hub = EventHub::new(8);
+    let marks = Topic::Marks("21BCE0001".into());
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "CSE1001", json!({"score": 10}))
+        .is_some());
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "CSE1001", json!({"score": 10}))
+        .is_none());
+    // Another key, kind or topic is tracked separately.
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "CSE1002", json!({"score": 10}))
+        .is_some());
+    assert!(hub
+        .publish_if_changed(&marks, "grade", "CSE1001", json!({"score": 10}))
+        .is_some());
+    assert!(hub
+        .publish_if_changed(
+            &Topic::Marks("21BCE0002".into()),
+            "mark",
+            "CSE1001",
+            json!({"score": 10})
+        )
+        .is_some());
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "CSE1001", json!({"score": 12}))
+        .is_some());
+}
+
+#[test]
+fn forgets_least_recently_used_payloads() {
+    let hub = EventHub::with_dedup_limit(8, 2);
+    let marks = Topic::Marks("21BCE0001".into());
+    hub.publish_if_changed(&marks, "mark", "a", json!(1));
+    hub.publish_if_changed(&marks, "mark", "b", json!(1));
+    // Touch `a` so `b` is the least recently used.
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "a", json!(1))
+        .is_none());
+    hub.publish_if_changed(&marks, "mark", "c", json!(1));
+    assert_eq!(hub.remembered_payloads(), 2);
+
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "a", json!(1))
+        .is_none());
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "b", json!(1))
+        .is_some());
+}
+
+#[test]
+fn keeps_only_the_newest_payloads_under_churn() {
+    let hub = EventHub::with_dedup_limit(8, 3);
+    let marks = Topic::Marks("21BCE0001".into());
+    for n in 0..100 {
+        hub.publish_if_changed(&marks, "mark", &n.to_string(), json!(n));
+    }
+    assert_eq!(hub.remembered_payloads(), 3);
+    for n in 97..100 {
+        assert!(hub
+            .publish_if_changed(&marks, "mark", &n.to_string(), json!(n))
+            .is_none());
+    }
+    assert!(hub
+        .publish_if_changed(&marks, "mark", "96", json!(96))
+        .is_some());
+    assert_eq!(hub.remembered_payloads(), 3);
+}
+
+#[test]
+fn replace_last_stores_without_publishing() {
+    let hub = EventHub::new(8);
+    let topic = Topic::Announcements("21BCE0001".into());
+    assert_eq!(hub.replace_last(&topic, "baseline", "", json!([1])), None);
+    assert_eq!(
+        hub.replace_last(&topic, "baseline", "", json!([1, 2])),
+        Some(json!([1]))
+    );
+    // Nothing was published before this.
+    assert_eq!(hub.publish(&topic, "announcement", json!(2)), 1);
+}
+
+#[test]
+fn tokens_only_open_their_owners_topics() {
+    let tokens = AccessTokens::new(Duration::from_secs(60));
+    let token = tokens.issue("21bce0001");
+    assert_eq!(tokens.owner(&token).as_deref(), Some("21BCE0001"));
+
+    let own = [
+        Topic::Marks("21BCE0001".into()),
+        Topic::Announcements("21BCE0001".into()),
+        Topic::FacultyCabin,
+    ];
+    assert_eq!(tokens.authorize(Some(&token), &own), Ok(()));
+    assert_eq!(
+        tokens.authorize(
+            Some(&token),
+            &[
+                Topic::Sync("21BCE0001".into()),
+                Topic::Marks("21BCE0002".into())
+            ]
+        ),
+        Err(AccessDenied::Forbidden)
+    );
+    assert_eq!(
+        tokens.authorize(None, &[Topic::FacultyCabin]),
+        Err(AccessDenied::Unauthenticated)
+    );
+    assert_eq!(
+        tokens.authorize(Some("made-up"), &own),
+        Err(AccessDenied::Unauthenticated)
+    );
+    assert_ne!(tokens.issue("21BCE0001"), token);
+}
+
+#[test]
+fn tokens_expire() {
+    let tokens = AccessTokens::new(Duration::ZERO);
+    let token = tokens.issue("21BCE0001");
+    assert_eq!(tokens.owner(&token), None);
+    assert_eq!(
+        tokens.authorize(Some(&token), &[Topic::FacultyCabin]),
+        Err(AccessDenied::Unauthenticated)
+    );
+}
@@ -0,0 +1,106 @@
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::wifi::{NetworkProfile, NetworkProfiles, PortalKind};
+
+const EXAMPLE: &str = include_str!("../network_profiles.example.toml");
+
+#[test]
+fn example_file_matches_the_defaults() {
+    let example = NetworkProfiles::from_toml_str(EXAMPLE).unwrap();
+    let defaults = NetworkProfiles::default();
+    assert_eq!(example.connectivity_checks, defaults.connectivity_checks);
+    assert_eq!(example.internet_check_url, defaults.internet_check_url);
+    assert_eq!(example.profiles.len(), defaults.profiles.len());
+    for (example, default) in example.profiles.iter().zip(&defaults.profiles) {
+        assert_eq!(example, default, "{} differs", default.name);
+    }
+    assert_eq!(example, defaults);
+}
+
+#[test]
+fn example_tls_lines_pin_a_profile_when_uncommented() {
+    let uncommented = EXAMPLE
+        .replace("# [profiles.tls]", "[profiles.tls]")
+        .replace("# mode = \"pinned_spki\"", "mode = \"pinned_spki\"")
+        .replace("# pins = [\"...\"]", "pins = [\"abc=\"]");
+    let profiles = NetworkProfiles::from_toml_str(&uncommented).unwrap();
+    let hostel = profiles.get("vitap-hostel").unwrap();
+    assert_eq!(hostel.tls, Some(TlsPolicy::pinned(["abc="])));
+    assert_eq!(
+        hostel.tls(&TlsPolicy::default()),
+        &TlsPolicy::pinned(["abc="])
+    );
+    assert_eq!(profiles.get("vitap-academic").unwrap().tls, None);
+}
+
+#[test]
+fn an_empty_profile_list_is_rejected() {
+    match NetworkProfiles::from_toml_str("profiles = []") {
+        Err(VtopError::ConfigurationError(message)) => {
+            assert!(message.contains("network profile"), "{}", message)
+        }
+        other => panic!("expected a configuration error, got {:?}", other),
+    }
+    assert!(matches!(
+        NetworkProfiles::from_toml_str("[[profiles]]\nname = \"missing-fields\""),
+        Err(VtopError::ConfigurationError(_))
+    ));
+    // Leaving `profiles` out keeps the built-in list.
+    let unset = NetworkProfiles::from_toml_str("internet_check_url = \"https://example.com\"");
+    assert_eq!(unset.unwrap().profiles, NetworkProfiles::default().profiles);
+}
+
+#[test]
+fn profiles_replace_the_built_in_list_and_keep_default_checks() {
+    let profiles = NetworkProfiles::from_toml_str(
+        r#"
+        [[profiles]]
+        name = "library"
+        kind = "sophos"
+        base_url = "https://10.0.0.1:8090/"
+        login_path = "/login.xml"
+        logout_path = "logout.xml"
+        hosts = ["10.0.0.1"]
+
+        [profiles.fields]
+        username = "user"
+        password = "pass"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(profiles.profiles.len(), 1);
+    assert_eq!(
+        profiles.connectivity_checks,
+        NetworkProfiles::default().connectivity_checks
+    );
+    let library = profiles.first_of(PortalKind::Sophos).unwrap();
+    assert_eq!(library.url("/login.xml"), "https://10.0.0.1:8090/login.xml");
+    assert_eq!(library.logout_url(), "https://10.0.0.1:8090/logout.xml");
+    assert!(profiles.first_of(PortalKind::Fortinet).is_none());
+    assert!(profiles.get("vitap-hostel").is_none());
+}
+
+#[test]
+fn portal_urls_are_matched_by_their_host() {
+    let profiles = NetworkProfiles::default();
+    let name = |url: &str| profiles.for_portal_url(url).map(|p| p.name.as_str());
+    assert_eq!(
+        name("http://172.18.10.10:1000/fgtauth?0123456789abcdef"),
+        Some("vitap-academic")
+    );
+    assert_eq!(
+        name("https://HFW.vitap.ac.in:8090/httpclient.html"),
+        Some("vitap-hostel")
+    );
+    assert_eq!(name("https://172.18.8.14:8090/"), Some("vitap-hostel"));
+
+    assert_eq!(name("http://172.18.10.100:1000/fgtauth"), None);
+    assert_eq!(name("https://hfw.vitap.ac.in.example.com/"), None);
+    assert_eq!(name("https://example.com/?next=172.18.10.10"), None);
+    assert_eq!(name("not a url"), None);
+
+    assert_eq!(
+        NetworkProfile::vitap_academic().logout_url(),
+        "https://172.18.10.10:1000/logout?"
+    );
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use std::net::SocketAddr;
+
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::{get, post};
+use axum::Router;
+use rust_lib_vitapmate::api::vtop_get_client::{fetch_proctor_contacts, fetch_proctor_details};
+
+use common::logged_in;
+
+// "JPEG" in base64.
+const PROCTOR_PAGE: &str = r#"<h5>Proctor Details</h5>
+<table>
+<tr><td>Faculty ID</td><td>70123</td><td rowspan="7"><img src="data:image/jpeg;base64,SlBFRw=="></td></tr>
+<tr><td>Faculty Name</td><td>Dr. Lakshmi Rao</td></tr>
+<tr><td>Faculty Designation</td><td>Associate Professor</td></tr>
+<tr><td>School</td><td>SCOPE</td></tr>
+<tr><td>Cabin</td><td>AB1 412</td></tr>
+<tr><td>Faculty Email</td><td>lakshmi.rao@vitap.ac.in</td></tr>
+<tr><td>Faculty Mobile Number</td><td>9876500000</td></tr>
+</table>
+<h5>Class Advisor</h5>
+<table>
+<tr><td>Name</td><td>Dr. Suresh Kumar</td><td><img src="/vtop/users/image/70456"></td></tr>
+<tr><td>Cabin</td><td>AB2 108</td></tr>
+</table>"#;
+
+async fn spawn_fake_vtop(photo_status: StatusCode) -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route(
+                "/vtop/proctor/viewProctorDetails",
+                post(|| async { Html(PROCTOR_PAGE) }),
+            )
+            .route(
+                "/vtop/users/image/70456",
+                get(move || async move { (photo_status, b"PNG".to_vec()) }),
+            ),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn proctor_and_advisor_come_with_photos() {
+    let client = logged_in(spawn_fake_vtop(StatusCode::OK).await, "21BCE0801").await;
+
+    let details = fetch_proctor_details(&client).await.unwrap();
+    let proctor = details.proctor.unwrap();
+    assert_eq!(proctor.name, "Dr. Lakshmi Rao");
+    assert_eq!(proctor.emp_id, "70123");
+    assert_eq!(proctor.designation, "Associate Professor");
+    assert_eq!(proctor.school, "SCOPE");
+    assert_eq!(proctor.cabin, "AB1 412");
+    assert_eq!(proctor.email, "lakshmi.rao@vitap.ac.in");
+    assert_eq!(proctor.phone, "9876500000");
+    assert_eq!(proctor.photo.as_deref(), Some(&b"JPEG"[..]));
+
+    let advisor = details.class_advisor.unwrap();
+    assert_eq!(advisor.name, "Dr. Suresh Kumar");
+    assert_eq!(advisor.cabin, "AB2 108");
+    assert_eq!(advisor.photo.as_deref(), Some(&b"PNG"[..]));
+}
+
+#[tokio::test]
+async fn a_failed_photo_download_keeps_the_details() {
+    let client = logged_in(
+        spawn_fake_vtop(StatusCode::INTERNAL_SERVER_ERROR).await,
+        "21BCE0801",
+    )
+    .await;
+
+    let details = fetch_proctor_details(&client).await.unwrap();
+    let advisor = details.class_advisor.unwrap();
+    assert_eq!(advisor.name, "Dr. Suresh Kumar");
+    assert_eq!(advisor.photo, None);
+    assert!(details.proctor.unwrap().photo.is_some());
+}
+
+#[tokio::test]
+async fn contacts_skip_photo_downloads() {
+    let client = logged_in(spawn_fake_vtop(StatusCode::OK).await, "21BCE0801").await;
+
+    let details = fetch_proctor_contacts(&client).await.unwrap();
+    assert_eq!(details.class_advisor.unwrap().photo, None);
+    // Inlined photos cost no request and are kept.
+    let proctor = details.proctor.unwrap();
+    assert_eq!(proctor.name, "Dr. Lakshmi Rao");
+    assert_eq!(proctor.photo.as_deref(), Some(&b"JPEG"[..]));
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use std::path::PathBuf;
+
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::debug_capture::DebugCaptureConfig;
+use rust_lib_vitapmate::api::vtop::redact::{scrub_page, Redacted};
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop_get_client::fetch_timetable;
+
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vitapmate-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn redacted_values_hide_secrets_and_most_of_reg_numbers() {
+    assert_eq!(Redacted::secret("hunter2").to_string(), "[redacted]");
+    assert_eq!(format!("{:?}", Redacted::secret("hunter2")), "[redacted]");
+    assert_eq!(Redacted::secret("").to_string(), "[empty]");
+    assert_eq!(Redacted::reg_no("21BCE0001").to_string(), "21*****01");
+    assert_eq!(Redacted::reg_no("21BC").to_string(), "****");
+    assert_eq!(Redacted::reg_no("").to_string(), "");
+}
+
+#[test]
+fn scrubs_every_secret_whatever_its_length() {
+    let page = "<p>User 21BCE0001 tried pw and pw2; 21BCE0001 again</p>";
+    assert_eq!(
+        scrub_page(page, &["pw", "pw2", "21BCE0001", ""]),
+        "<p>User [redacted] tried [redacted] and [redacted]; [redacted] again</p>"
+    );
+}
+
+#[test]
+fn scrubs_csrf_values_however_they_are_quoted() {
+    let page = r#"<input type="hidden" name="_csrf" value="abc-123">
+<INPUT VALUE='abc-123' NAME='_csrf'>
+<input name=_csrf value=abc-123>
+<meta name="_csrf" content="abc-123"/>
+<input name="username" value="kept">
+<input name="_csrf" data-value="x" value = "abc-123">"#;
+    let scrubbed = scrub_page(page, &[]);
+    assert!(!scrubbed.contains("abc-123"), "{}", scrubbed);
+    assert!(scrubbed.contains(r#"name="_csrf" value="[redacted]">"#));
+    assert!(scrubbed.contains("VALUE='[redacted]'"));
+    assert!(scrubbed.contains("value=[redacted]>"));
+    assert!(scrubbed.contains(r#"content="[redacted]"/>"#));
+    assert!(scrubbed.contains(r#"value="kept""#));
+    assert!(scrubbed.contains(r#"data-value="x""#));
+}
+
+#[test]
+fn captures_keep_the_newest_and_leave_other_files_alone() {
+    let dir = scratch_dir("captures");
+    std::fs::create_dir_all(&dir).unwrap();
+    let foreign = ["vtop-notes.html", "vtop-000000000000001.html", "keep.txt"];
+    for name in foreign {
+        std::fs::write(dir.join(name), "mine").unwrap();
+    }
+
+    let sink = DebugCaptureConfig::new(dir.to_string_lossy(), 2);
+    let mut written = vec![];
+    for label in ["first", "second", "third"] {
+        written.push(sink.capture(label, "<p>page</p>").unwrap());
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    assert!(!written[0].exists());
+    assert!(written[1].exists() && written[2].exists());
+    for name in foreign {
+        assert!(dir.join(name).exists(), "{} was removed", name);
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn failed_pages_are_captured_scrubbed() {
+    let dir = scratch_dir("failed-pages");
+    let addr = common::spawn_fake_vtop(Router::new().route(
+        "/vtop/processViewTimeTable",
+        post(|| async {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                r#"<input name="_csrf" value="t0k3n"><p>21BCE0002 secret</p>"#,
+            )
+        }),
+    ))
+    .await;
+    let config = VtopConfig {
+        base_url: format!("http://{}", addr),
+        debug_capture: Some(DebugCaptureConfig::new(dir.to_string_lossy(), 5)),
+        ..VtopConfig::default()
+    };
+    let client = common::logged_in_with(config, "21BCE0002").await;
+    assert!(fetch_timetable(&client, "AP2024252".into()).await.is_err());
+
+    let captures: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    let [capture] = captures.as_slice() else {
+        panic!("expected one capture, got {:?}", captures);
+    };
+    let name = capture.file_name().unwrap().to_string_lossy().into_owned();
+    assert!(name.ends_with("-http500_vtop_processViewTimeTable.html"));
+    assert_eq!(
+        std::fs::read_to_string(capture).unwrap(),
+        r#"<input name="_csrf" value="[redacted]"><p>[redacted] [redacted]</p>"#
+    );
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use rust_lib_vitapmate::api::vtop::request_limiter::{
+    BreakerState, LimiterConfig, RequestLimiter, Upstream,
+};
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use tokio::time::Instant;
+
+fn limiter() -> RequestLimiter {
+    RequestLimiter::new(LimiterConfig {
+        max_concurrent: 4,
+        per_account_interval: Duration::from_millis(250),
+        queue_timeout: Duration::from_secs(5),
+        breaker_threshold: 2,
+        breaker_cooldown: Duration::from_secs(30),
+    })
+}
+
+fn open_vtop_breaker(limiter: &RequestLimiter) {
+    limiter.record_failure(Upstream::Vtop);
+    limiter.record_failure(Upstream::Vtop);
+    assert_eq!(limiter.metrics().breaker_state, BreakerState::Open);
+}
+
+#[tokio::test(start_paused = true)]
+async fn breaker_opens_half_opens_and_closes() {
+    let limiter = limiter();
+    limiter.record_failure(Upstream::Vtop);
+    assert_eq!(limiter.metrics().breaker_state, BreakerState::Closed);
+    open_vtop_breaker(&limiter);
+    assert!(matches!(
+        limiter.acquire(Upstream::Vtop, "A").await,
+        Err(VtopError::CircuitOpen)
+    ));
+
+    tokio::time::advance(Duration::from_secs(30)).await;
+    let trial = limiter.acquire(Upstream::Vtop, "A").await.unwrap();
+    assert_eq!(limiter.metrics().breaker_state, BreakerState::HalfOpen);
+    // Only one trial at a time.
+    assert!(matches!(
+        limiter.acquire(Upstream::Vtop, "B").await,
+        Err(VtopError::CircuitOpen)
+    ));
+
+    // A failed trial opens the breaker again for a full cooldown.
+    limiter.record_failure(Upstream::Vtop);
+    drop(trial);
+    assert_eq!(limiter.metrics().breaker_state, BreakerState::Open);
+    tokio::time::advance(Duration::from_secs(29)).await;
+    assert!(limiter.acquire(Upstream::Vtop, "A").await.is_err());
+
+    tokio::time::advance(Duration::from_secs(1)).await;
+    let trial = limiter.acquire(Upstream::Vtop, "A").await.unwrap();
+    limiter.record_success(Upstream::Vtop);
+    drop(trial);
+    let metrics = limiter.metrics();
+    assert_eq!(metrics.breaker_state, BreakerState::Closed);
+    assert_eq!(metrics.consecutive_failures, 0);
+    assert_eq!(metrics.rejected_circuit_open, 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn dropped_trial_permit_lets_the_next_trial_through() {
+    let limiter = limiter();
+    open_vtop_breaker(&limiter);
+    tokio::time::advance(Duration::from_secs(30)).await;
+
+    // The request owning the trial is cancelled before reporting back.
+    let trial = limiter.acquire(Upstream::Vtop, "A").await.unwrap();
+    drop(trial);
+
+    assert_eq!(limiter.metrics().breaker_state, BreakerState::HalfOpen);
+    assert!(limiter.acquire(Upstream::Vtop, "B").await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_acquire_releases_the_trial() {
+    let limiter = RequestLimiter::new(LimiterConfig {
+        max_concurrent: 1,
+        breaker_threshold: 1,
+        ..LimiterConfig::default()
+    });
+    let busy = limiter.acquire(Upstream::Vtop, "B").await.unwrap();
+    limiter.record_failure(Upstream::Vtop);
+    tokio::time::advance(Duration::from_secs(30)).await;
+
+    // Takes the trial, then waits for the only permit until cancelled.
+    let waiting = tokio::time::timeout(
+        Duration::from_millis(100),
+        limiter.acquire(Upstream::Vtop, "A"),
+    )
+    .await;
+    assert!(waiting.is_err());
+
+    drop(busy);
+    assert!(limiter.acquire(Upstream::Vtop, "C").await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn captcha_solver_failures_leave_vtop_alone() {
+    let limiter = limiter();
+    limiter.record_failure(Upstream::CaptchaSolver);
+    limiter.record_failure(Upstream::CaptchaSolver);
+    let metrics = limiter.metrics();
+    assert_eq!(metrics.solver_breaker_state, BreakerState::Open);
+    assert_eq!(metrics.breaker_state, BreakerState::Closed);
+
+    assert!(matches!(
+        limiter.acquire(Upstream::CaptchaSolver, "A").await,
+        Err(VtopError::CircuitOpen)
+    ));
+    assert!(limiter.acquire(Upstream::Vtop, "A").await.is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn requests_of_one_account_are_spaced() {
+    let limiter = limiter();
+    let start = Instant::now();
+    let mut granted = vec![];
+    for _ in 0..3 {
+        let _permit = limiter.acquire(Upstream::Vtop, "A").await.unwrap();
+        granted.push(start.elapsed());
+    }
+    assert_eq!(
+        granted,
+        [
+            Duration::ZERO,
+            Duration::from_millis(250),
+            Duration::from_millis(500)
+        ]
+    );
+
+    // Other accounts are not held back by "A".
+    let _permit = limiter.acquire(Upstream::Vtop, "B").await.unwrap();
+    assert_eq!(start.elapsed(), Duration::from_millis(500));
+}
+
+#[tokio::test(start_paused = true)]
+async fn gives_up_when_the_account_queue_is_too_long() {
+    let limiter = RequestLimiter::new(LimiterConfig {
+        per_account_interval: Duration::from_secs(3),
+        queue_timeout: Duration::from_secs(5),
+        ..LimiterConfig::default()
+    });
+    let (first, second, third) = tokio::join!(
+        limiter.acquire(Upstream::Vtop, "A"),
+        limiter.acquire(Upstream::Vtop, "A"),
+        limiter.acquire(Upstream::Vtop, "A"),
+    );
+    assert!(first.is_ok() && second.is_ok());
+    // The third slot is 6s away, beyond the queue timeout.
+    assert!(matches!(third, Err(VtopError::QueueTimeout)));
+    assert_eq!(limiter.metrics().rejected_queue_timeout, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cancelled_waits_leave_the_queue() {
+    let limiter = RequestLimiter::new(LimiterConfig {
+        max_concurrent: 1,
+        ..LimiterConfig::default()
+    });
+    let busy = limiter.acquire(Upstream::Vtop, "A").await.unwrap();
+    for account in ["B", "C", "D"] {
+        let waiting = tokio::time::timeout(
+            Duration::from_millis(100),
+            limiter.acquire(Upstream::Vtop, account),
+        )
+        .await;
+        assert!(waiting.is_err());
+    }
+    assert_eq!(limiter.metrics().queue_depth, 0);
+    drop(busy);
+    assert_eq!(limiter.metrics().in_flight, 0);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::post;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::progress::FetchKind;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop_get_client::fetch_semester_bundle;
+
+use common::logged_in;
+
+const ATTENDANCE: &str = r#"<table>
+<tr><th>Sl.No.</th></tr>
+<tr>
+<td>1</td><td>Program Core</td><td>Compiler Design</td><td>CSE3005</td>
+<td>Dr. A (SCOPE)</td><td>18</td><td>20</td><td>90</td><td>-</td><td>Permitted</td>
+<td><a href="javascript:processViewAttendanceDetail('AP2024252','CSE3005','AP2024252000123','ETH')">View</a></td>
+</tr>
+</table>"#;
+
+const FULL_ATTENDANCE: &str = r#"<table>
+<tr><th>a</th></tr><tr><th>b</th></tr><tr><th>c</th></tr>
+<tr><td>1</td><td>02-Jan-2025</td><td>A1</td><td>THU 09:00</td><td>Present</td><td>-</td></tr>
+</table>"#;
+
+const PAGE_DELAY: Duration = Duration::from_secs(1);
+
+// Every semester page takes a second to answer and marks always fail.
+async fn spawn_fake_vtop() -> SocketAddr {
+    common::spawn_fake_vtop(
+        Router::new()
+            .route(
+                "/vtop/academics/common/StudentTimeTable",
+                post(|| async { Html("") }),
+            )
+            .route(
+                "/vtop/processViewTimeTable",
+                post(|| async {
+                    tokio::time::sleep(PAGE_DELAY).await;
+                    Html("")
+                }),
+            )
+            .route(
+                "/vtop/processViewStudentAttendance",
+                post(|| async {
+                    tokio::time::sleep(PAGE_DELAY).await;
+                    Html(ATTENDANCE)
+                }),
+            )
+            .route(
+                "/vtop/processViewAttendanceDetail",
+                post(|| async { Html(FULL_ATTENDANCE) }),
+            )
+            .route(
+                "/vtop/examinations/doStudentMarkView",
+                post(|| async {
+                    tokio::time::sleep(PAGE_DELAY).await;
+                    (StatusCode::INTERNAL_SERVER_ERROR, "marks are down")
+                }),
+            )
+            .route(
+                "/vtop/examinations/doSearchExamScheduleForStudent",
+                post(|| async {
+                    tokio::time::sleep(PAGE_DELAY).await;
+                    Html("")
+                }),
+            ),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn bundle_fetches_parts_concurrently_and_keeps_what_succeeded() {
+    let client = logged_in(spawn_fake_vtop().await, "21BCE0101").await;
+
+    let started = Instant::now();
+    let bundle = fetch_semester_bundle(&client, "AP2024252".into(), true, None).await;
+    // Four one-second pages one after another would take at least four.
+    assert!(started.elapsed() < PAGE_DELAY * 3);
+
+    assert_eq!(bundle.semester_id, "AP2024252");
+    assert!(bundle.timetable.is_some());
+    assert!(bundle.exam_schedule.is_some());
+    assert!(bundle.marks.is_none());
+    assert_eq!(bundle.attendance.as_ref().unwrap().records.len(), 1);
+    assert_eq!(bundle.full_attendance.len(), 1);
+    assert_eq!(bundle.full_attendance[0].course_id, "AP2024252000123");
+
+    assert!(!bundle.is_complete());
+    let [failure] = bundle.failures.as_slice() else {
+        panic!("expected one failure, got {:?}", bundle.failures);
+    };
+    assert_eq!(failure.part, FetchKind::Marks);
+    assert!(matches!(failure.error, VtopError::VtopServerError(_)));
+}
+
+#[tokio::test]
+async fn bundle_skips_attendance_details_unless_asked() {
+    let client = logged_in(spawn_fake_vtop().await, "21BCE0102").await;
+
+    let bundle = fetch_semester_bundle(&client, "AP2024252".into(), false, None).await;
+    assert!(bundle.attendance.is_some());
+    assert!(bundle.full_attendance.is_empty());
+}
@@ -0,0 +1,195 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair};
+use rust_lib_vitapmate::api::vtop::tls_policy::{spki_pin, TlsPolicy};
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::wifi::{
+    NetworkProfile, NetworkProfiles, SophosPortal, WifiOutcome, WifiPortal,
+};
+use rustls::pki_types::{PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+
+struct StandIn {
+    addr: SocketAddr,
+    ca_pem: String,
+    leaf_pin: String,
+    ca_pin: String,
+    leaf_public_key_der: Vec<u8>,
+}
+
+// Serves a fixed HTTP response over TLS with a leaf certificate for
+// `localhost` issued by a freshly generated CA.
+async fn spawn_stand_in() -> StandIn {
+    let ca_key = KeyPair::generate().unwrap();
+    let mut ca_params = CertificateParams::new(vec![]).unwrap();
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "FacultyHub Test CA");
+    let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+    let leaf_key = KeyPair::generate().unwrap();
+    let leaf_params = CertificateParams::new(vec!["localhost".to_string()]).unwrap();
+    let leaf_cert = leaf_params.signed_by(&leaf_key, &ca_cert, &ca_key).unwrap();
+
+    let server_config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_no_client_auth()
+    .with_single_cert(
+        vec![leaf_cert.der().clone(), ca_cert.der().clone()],
+        PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(leaf_key.serialize_der())),
+    )
+    .unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(server_config));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                return;
+            };
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                let Ok(mut tls) = acceptor.accept(stream).await else {
+                    return;
+                };
+                let mut buf = [0u8; 1024];
+                let _ = tls.read(&mut buf).await;
+                let _ = tls
+                    .write_all(
+                        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    )
+                    .await;
+                let _ = tls.shutdown().await;
+            });
+        }
+    });
+
+    StandIn {
+        addr,
+        ca_pem: ca_cert.pem(),
+        leaf_pin: spki_pin(leaf_cert.der()).unwrap(),
+        ca_pin: spki_pin(ca_cert.der()).unwrap(),
+        leaf_public_key_der: leaf_key.public_key_der(),
+    }
+}
+
+async fn fetch(stand_in: &StandIn, policy: &TlsPolicy) -> Result<String, String> {
+    let builder = reqwest::Client::builder().resolve("localhost", stand_in.addr);
+    let client = policy
+        .configure(builder)
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())?;
+    let url = format!("https://localhost:{}/", stand_in.addr.port());
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    response.text().await.map_err(|e| e.to_string())
+}
+
+#[tokio::test]
+async fn spki_pin_hashes_the_subject_public_key_info() {
+    let stand_in = spawn_stand_in().await;
+    let digest = ring::digest::digest(&ring::digest::SHA256, &stand_in.leaf_public_key_der);
+    assert_eq!(stand_in.leaf_pin, STANDARD.encode(digest.as_ref()));
+}
+
+#[tokio::test]
+async fn pinned_policy_accepts_matching_leaf_key() {
+    let stand_in = spawn_stand_in().await;
+    let policy = TlsPolicy::pinned([format!("sha256/{}", stand_in.leaf_pin)]);
+    assert_eq!(fetch(&stand_in, &policy).await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn pinned_policy_rejects_other_keys() {
+    let stand_in = spawn_stand_in().await;
+    let wrong = STANDARD.encode([7u8; 32]);
+    assert!(fetch(&stand_in, &TlsPolicy::pinned([wrong])).await.is_err());
+    // A pinned CA key is not enough: intermediates sent by the server are
+    // not authenticated by the pinning verifier.
+    let ca_pin = stand_in.ca_pin.clone();
+    assert!(fetch(&stand_in, &TlsPolicy::pinned([ca_pin]))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn default_policy_rejects_untrusted_certificate() {
+    let stand_in = spawn_stand_in().await;
+    assert!(fetch(&stand_in, &TlsPolicy::default()).await.is_err());
+}
+
+#[tokio::test]
+async fn trusted_ca_policy_accepts_bundled_ca() {
+    let stand_in = spawn_stand_in().await;
+    let policy = TlsPolicy::TrustedCa {
+        certificates: vec![stand_in.ca_pem.clone()],
+    };
+    assert_eq!(fetch(&stand_in, &policy).await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn invalid_pins_are_a_configuration_error() {
+    let policy = TlsPolicy::pinned(["not-base64!"]);
+    assert!(policy.configure(reqwest::Client::builder()).is_err());
+    let empty = TlsPolicy::PinnedSpki { pins: vec![] };
+    assert!(empty.configure(reqwest::Client::builder()).is_err());
+}
+
+#[tokio::test]
+async fn portals_report_a_policy_they_cannot_apply() {
+    let mut profile = NetworkProfile::vitap_hostel();
+    profile.tls = Some(TlsPolicy::pinned(["not-base64!"]));
+    let portal =
+        SophosPortal::from_profile(profile, &NetworkProfiles::default(), &TlsPolicy::default());
+    assert!(matches!(portal, Err(VtopError::ConfigurationError(_))));
+}
+
+// Answers every plain HTTP request with 200, like the internet does once we
+// are past a captive portal.
+async fn spawn_online() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+            });
+        }
+    });
+    format!("http://{}/", addr)
+}
+
+#[tokio::test]
+async fn portals_report_a_rejected_certificate_rather_than_connectivity() {
+    let stand_in = spawn_stand_in().await;
+    let profiles = NetworkProfiles {
+        internet_check_url: spawn_online().await,
+        ..NetworkProfiles::default()
+    };
+    let mut profile = NetworkProfile::vitap_hostel();
+    profile.base_url = format!("https://localhost:{}", stand_in.addr.port());
+    profile.tls = Some(TlsPolicy::pinned([stand_in.ca_pin.clone()]));
+    let portal = SophosPortal::from_profile(profile, &profiles, &TlsPolicy::default()).unwrap();
+
+    assert_eq!(
+        portal.login("21BCE0201", "secret").await,
+        WifiOutcome::CertificateRejected
+    );
+    assert_eq!(
+        portal.logout("21BCE0201").await,
+        WifiOutcome::CertificateRejected
+    );
+}
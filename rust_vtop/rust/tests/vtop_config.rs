@@ -0,0 +1,165 @@
+mod common;
+
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::debug_capture::DebugCaptureConfig;
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop_get_client::get_vtop_client;
+
+const VARS: [&str; 10] = [
+    "VTOP_BASE_URL",
+    "VTOP_TIMEOUT_SECONDS",
+    "VTOP_CONNECT_TIMEOUT_SECONDS",
+    "VTOP_READ_TIMEOUT_SECONDS",
+    "VTOP_USER_AGENT",
+    "VTOP_PROXY",
+    "VTOP_TLS_PINS",
+    "VTOP_DANGER_ACCEPT_INVALID_CERTS",
+    "VTOP_DEBUG_CAPTURE_DIR",
+    "VTOP_DEBUG_CAPTURE_MAX_FILES",
+];
+
+fn clear_env() {
+    for name in VARS {
+        std::env::remove_var(name);
+    }
+}
+
+#[test]
+fn example_config_matches_the_defaults() {
+    let example =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/vtop.example.toml")).unwrap();
+    let parsed = VtopConfig::from_toml_str(&example).unwrap();
+    let defaults = VtopConfig::default();
+    assert_eq!(
+        toml::to_string(&parsed).unwrap(),
+        toml::to_string(&defaults).unwrap()
+    );
+}
+
+#[test]
+fn reads_toml_with_defaults_for_missing_keys() {
+    let config = VtopConfig::from_toml_str(
+        r#"
+        base_url = "http://127.0.0.1:8080/"
+        timeout_seconds = 5
+
+        [tls]
+        mode = "pinned_spki"
+        pins = ["abc="]
+
+        [debug_capture]
+        directory = "pages"
+        max_files = 3
+
+        [extra_headers]
+        "X-Test" = "1"
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.base_url, "http://127.0.0.1:8080/");
+    assert_eq!(
+        config.clone().normalized().base_url,
+        "http://127.0.0.1:8080"
+    );
+    assert_eq!(config.timeout_seconds, 5);
+    assert_eq!(config.connect_timeout_seconds, Some(10));
+    assert_eq!(config.user_agent, VtopConfig::default().user_agent);
+    assert_eq!(config.tls, TlsPolicy::pinned(["abc="]));
+    assert_eq!(
+        config.debug_capture,
+        Some(DebugCaptureConfig::new("pages", 3))
+    );
+    assert_eq!(config.extra_headers["X-Test"], "1");
+
+    assert_eq!(
+        VtopConfig::from_toml_str("").unwrap().base_url,
+        "https://vtop.vitap.ac.in"
+    );
+    for bad in ["timeout_seconds = \"soon\"", "[tls]\nmode = \"trust_me\""] {
+        assert!(matches!(
+            VtopConfig::from_toml_str(bad),
+            Err(VtopError::ConfigurationError(_))
+        ));
+    }
+}
+
+// The only test here touching the environment, so nothing races it.
+#[test]
+fn environment_overrides_the_file() {
+    clear_env();
+    let mut config = VtopConfig::default();
+    std::env::set_var("VTOP_BASE_URL", " http://localhost:9000/ ");
+    std::env::set_var("VTOP_TIMEOUT_SECONDS", " 12 ");
+    std::env::set_var("VTOP_READ_TIMEOUT_SECONDS", "7");
+    std::env::set_var("VTOP_USER_AGENT", "tests");
+    std::env::set_var("VTOP_PROXY", " ");
+    std::env::set_var("VTOP_TLS_PINS", "one=, ,two=");
+    std::env::set_var("VTOP_DEBUG_CAPTURE_DIR", "captures");
+    std::env::set_var("VTOP_DEBUG_CAPTURE_MAX_FILES", "4");
+    config.apply_env().unwrap();
+    assert_eq!(
+        config.clone().normalized().base_url,
+        "http://localhost:9000"
+    );
+    assert_eq!(config.timeout_seconds, 12);
+    assert_eq!(config.connect_timeout_seconds, Some(10));
+    assert_eq!(config.read_timeout_seconds, Some(7));
+    assert_eq!(config.user_agent, "tests");
+    assert_eq!(config.proxy, None);
+    assert_eq!(config.tls, TlsPolicy::pinned(["one=", "two="]));
+    assert_eq!(
+        config.debug_capture,
+        Some(DebugCaptureConfig::new("captures", 4))
+    );
+
+    std::env::set_var("VTOP_DANGER_ACCEPT_INVALID_CERTS", "yes");
+    config.apply_env().unwrap();
+    assert_eq!(config.tls, TlsPolicy::DangerAcceptInvalidCerts);
+
+    std::env::set_var("VTOP_CONNECT_TIMEOUT_SECONDS", "ten");
+    assert!(matches!(
+        config.apply_env(),
+        Err(VtopError::ConfigurationError(message)) if message.contains("VTOP_CONNECT_TIMEOUT_SECONDS")
+    ));
+    clear_env();
+}
+
+#[test]
+fn default_client_is_built_without_panicking() {
+    assert!(get_vtop_client("21BCE0003".into(), "secret".into(), None).is_ok());
+}
+
+#[tokio::test]
+async fn clients_ignore_a_trailing_slash_however_configured() {
+    let addr = common::spawn_fake_vtop(Router::new()).await;
+    let config = VtopConfig {
+        base_url: format!("http://{}//", addr),
+        ..VtopConfig::default()
+    };
+    // The fake VTOP has no `//vtop/...` routes, so login only works if
+    // the slashes were dropped.
+    let client = common::logged_in_with(config, "21BCE0004").await;
+    assert!(client.is_authenticated());
+}
+
+#[test]
+fn other_hosts_keep_only_host_independent_tls_settings() {
+    let verify = TlsPolicy::Verify {
+        extra_roots: vec!["corporate.pem".into()],
+    };
+    assert_eq!(verify.for_other_hosts(), verify);
+    assert_eq!(
+        TlsPolicy::DangerAcceptInvalidCerts.for_other_hosts(),
+        TlsPolicy::DangerAcceptInvalidCerts
+    );
+    assert_eq!(
+        TlsPolicy::pinned(["abc="]).for_other_hosts(),
+        TlsPolicy::default()
+    );
+    let trusted = TlsPolicy::TrustedCa {
+        certificates: vec!["vtop-ca.pem".into()],
+    };
+    assert_eq!(trusted.for_other_hosts(), TlsPolicy::default());
+}
@@ -0,0 +1,113 @@
+use reqwest::{StatusCode, Url};
+use rust_lib_vitapmate::api::vtop::vtop_errors::{ErrorContext, FormError, VtopError};
+
+fn network(timeout: bool) -> VtopError {
+    VtopError::NetworkError(Box::new(ErrorContext {
+        timeout,
+        ..ErrorContext::default()
+    }))
+}
+
+fn server(status: u16) -> VtopError {
+    let url = Url::parse("https://vtop.example/vtop/examinations/doStudentMarkView").unwrap();
+    VtopError::server(&url, StatusCode::from_u16(status).unwrap(), "")
+}
+
+#[test]
+fn only_passing_problems_are_retryable() {
+    assert!(network(false).is_retryable());
+    assert!(network(true).is_retryable());
+    assert!(server(500).is_retryable());
+    assert!(server(503).is_retryable());
+    assert!(server(429).is_retryable());
+    assert!(VtopError::QueueTimeout.is_retryable());
+    assert!(VtopError::CaptchaRequired.is_retryable());
+    assert!(VtopError::AuthenticationFailed("Invalid Captcha".into()).is_retryable());
+
+    assert!(!server(404).is_retryable());
+    assert!(!VtopError::AuthenticationFailed("Locked".into()).is_retryable());
+    assert!(!VtopError::InvalidCredentials.is_retryable());
+    assert!(!VtopError::SessionExpired.is_retryable());
+    assert!(!VtopError::ParseError("no table".into()).is_retryable());
+    assert!(!VtopError::InvalidForm(FormError::EndsBeforeStart).is_retryable());
+    assert!(!VtopError::Cancelled.is_retryable());
+}
+
+#[test]
+fn auth_problems_need_the_user() {
+    assert!(VtopError::AuthenticationFailed("Locked".into()).is_auth_problem());
+    assert!(VtopError::InvalidCredentials.is_auth_problem());
+    assert!(VtopError::SessionExpired.is_auth_problem());
+    assert!(VtopError::RegistrationParsingError.is_auth_problem());
+
+    assert!(!network(false).is_auth_problem());
+    assert!(!server(500).is_auth_problem());
+    assert!(!VtopError::CaptchaRequired.is_auth_problem());
+    assert!(!VtopError::RequestRejected("closed".into()).is_auth_problem());
+}
+
+#[test]
+fn user_messages_say_what_to_do_without_internals() {
+    assert_eq!(
+        network(true).user_message(),
+        "VTOP took too long to respond. Please try again."
+    );
+    assert_eq!(
+        network(false).user_message(),
+        "Could not reach VTOP. Check your internet connection."
+    );
+    assert_eq!(
+        VtopError::AuthenticationFailed("Invalid Captcha".into()).user_message(),
+        "Captcha could not be solved. Please try again."
+    );
+    assert_eq!(
+        VtopError::ParseError("td[3] missing".into()).user_message(),
+        "VTOP returned a page we could not read."
+    );
+    assert_eq!(
+        VtopError::ConfigurationError("bad base_url".into()).user_message(),
+        "The app is misconfigured."
+    );
+    // VTOP's and the form's own explanations are meant for the student.
+    assert_eq!(
+        VtopError::RequestRejected("Outing closed".into()).user_message(),
+        "Outing closed"
+    );
+    assert_eq!(
+        VtopError::InvalidForm(FormError::MissingField("reason".into())).user_message(),
+        "Please fill in the reason."
+    );
+
+    let page = r#"<input name="_csrf" value="t0k3n"> Internal error"#;
+    let url = Url::parse("https://vtop.example/vtop/doLogin?authorizedID=21BCE0001").unwrap();
+    let error = VtopError::server(&url, StatusCode::INTERNAL_SERVER_ERROR, page);
+    assert_eq!(
+        error.user_message(),
+        "VTOP is having problems right now. Please try again later."
+    );
+    assert!(!error.user_message().contains("doLogin"));
+}
+
+#[test]
+fn error_context_leaves_out_tokens_and_the_page() {
+    let page = r#"<input name="_csrf" value="t0k3n"> Internal error"#;
+    let url = Url::parse("https://vtop.example/vtop/doLogin?authorizedID=21BCE0001#top").unwrap();
+    let error = VtopError::server(&url, StatusCode::INTERNAL_SERVER_ERROR, page);
+
+    let context = error.context().unwrap();
+    assert_eq!(context.endpoint.as_deref(), Some("doLogin"));
+    assert_eq!(
+        context.url.as_deref(),
+        Some("https://vtop.example/vtop/doLogin")
+    );
+
+    let logged = error.to_string();
+    assert!(logged.starts_with("VTOP server error at doLogin (HTTP 500)"));
+    assert!(logged.contains("Internal error"));
+    assert!(!logged.contains("t0k3n"));
+
+    let serialized = serde_json::to_string(&error).unwrap();
+    assert!(!serialized.contains("t0k3n"));
+    assert!(!serialized.contains("Internal error"));
+    assert!(!serialized.contains("21BCE0001"));
+}
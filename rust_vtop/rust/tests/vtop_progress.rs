@@ -0,0 +1,268 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::response::Html;
+use axum::routing::{get, post};
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::progress::{
+    with_progress, CancelToken, FetchKind, ProgressFn, VtopEvent, VtopProgress,
+};
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::vtop_client::{VtopClient, VtopError};
+use rust_lib_vitapmate::api::vtop::vtop_config::VtopConfig;
+use rust_lib_vitapmate::api::vtop::wifi::{NetworkProfiles, WifiAction, WifiOutcome};
+use rust_lib_vitapmate::api::vtop_get_client::{
+    fetch_full_attendance, fetch_semester_bundle, fetch_semesters, fetch_semesters_stream,
+    fetch_timetable, fetch_timetable_stream, fetch_wifi, get_vtop_client_with_config,
+    vtop_client_login, vtop_client_login_stream,
+};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+const SEMESTERS: &str = r#"<select name="semesterSubId">
+<option value="">-- Choose Semester --</option>
+<option value="AP2024252">Winter Semester 2024-25</option>
+</select>"#;
+
+// Enough of VTOP to resume a saved session and fetch semesters. The
+// timetable never answers in time, so calls to it can be cancelled.
+async fn spawn_fake_vtop() -> SocketAddr {
+    let app = Router::new()
+        .route(
+            "/vtop/open/page",
+            get(|| async { Html(r#"<input name="_csrf" value="t0k3n">"#) }),
+        )
+        .route(
+            "/vtop/academics/common/StudentTimeTable",
+            post(|| async { Html(SEMESTERS) }),
+        )
+        .route(
+            "/vtop/processViewTimeTable",
+            post(|| async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Html("")
+            }),
+        );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+fn client(addr: SocketAddr) -> VtopClient {
+    let config = VtopConfig {
+        base_url: format!("http://{}", addr),
+        ..VtopConfig::default()
+    };
+    get_vtop_client_with_config(
+        config,
+        "21BCE0001".into(),
+        "secret".into(),
+        Some("SESSION=abc".into()),
+    )
+    .unwrap()
+}
+
+fn drain(events: &mut UnboundedReceiver<VtopEvent>) -> Vec<VtopEvent> {
+    let mut drained = vec![];
+    while let Ok(event) = events.try_recv() {
+        drained.push(event);
+    }
+    drained
+}
+
+fn progress(events: &[VtopEvent]) -> Vec<VtopProgress> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            VtopEvent::Progress(step) => Some(step.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn login_and_fetch_report_each_step_then_the_data() {
+    let mut client = client(spawn_fake_vtop().await);
+
+    let (sink, mut events) = unbounded_channel();
+    vtop_client_login_stream(&mut client, sink, CancelToken::new())
+        .await
+        .unwrap();
+    assert_eq!(
+        progress(&drain(&mut events)),
+        vec![VtopProgress::RestoringSession, VtopProgress::LoggedIn]
+    );
+
+    let (sink, mut events) = unbounded_channel();
+    fetch_semesters_stream(&client, sink, CancelToken::new())
+        .await
+        .unwrap();
+    let events = drain(&mut events);
+    assert_eq!(
+        progress(&events),
+        vec![
+            VtopProgress::Fetching(FetchKind::Semesters),
+            VtopProgress::Parsing(FetchKind::Semesters),
+        ]
+    );
+    match events.last() {
+        Some(VtopEvent::Semesters(data)) => {
+            assert_eq!(data.semesters.len(), 1);
+            assert_eq!(data.semesters[0].id, "AP2024252");
+        }
+        other => panic!("expected semesters last, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn cancelling_stops_a_slow_fetch() {
+    let mut client = client(spawn_fake_vtop().await);
+    let (sink, _events) = unbounded_channel();
+    vtop_client_login_stream(&mut client, sink, CancelToken::new())
+        .await
+        .unwrap();
+
+    let cancel = CancelToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+
+    let (sink, mut events) = unbounded_channel();
+    let started = Instant::now();
+    let result = fetch_timetable_stream(&client, "AP2024252".into(), sink, cancel).await;
+    assert!(matches!(result, Err(VtopError::Cancelled)));
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(
+        progress(&drain(&mut events)),
+        vec![
+            VtopProgress::Fetching(FetchKind::Timetable),
+            VtopProgress::Cancelled,
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failures_end_the_stream_with_a_message() {
+    let client = client(spawn_fake_vtop().await);
+    let (sink, mut events) = unbounded_channel();
+
+    let result = fetch_semesters_stream(&client, sink, CancelToken::new()).await;
+    assert!(matches!(result, Err(VtopError::SessionExpired)));
+    assert_eq!(
+        progress(&drain(&mut events)),
+        vec![VtopProgress::Failed {
+            message: VtopError::SessionExpired.user_message(),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn wifi_calls_honour_a_cancelled_token() {
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let outcome = fetch_wifi(
+        "21BCE0001".into(),
+        "secret".into(),
+        WifiAction::Login,
+        NetworkProfiles::default(),
+        TlsPolicy::default(),
+        Some(cancel),
+    )
+    .await;
+    assert_eq!(outcome, WifiOutcome::Cancelled);
+}
+
+fn cancel_after(delay: Duration) -> CancelToken {
+    let cancel = CancelToken::new();
+    let canceller = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        canceller.cancel();
+    });
+    cancel
+}
+
+fn recorder() -> (ProgressFn, Arc<Mutex<Vec<VtopProgress>>>) {
+    let steps = Arc::new(Mutex::new(vec![]));
+    let sink = steps.clone();
+    (Arc::new(move |step| sink.lock().unwrap().push(step)), steps)
+}
+
+#[tokio::test]
+async fn concurrent_calls_keep_their_own_progress() {
+    let mut client = client(spawn_fake_vtop().await);
+    vtop_client_login(&mut client).await.unwrap();
+
+    let (semesters_progress, semesters_steps) = recorder();
+    let (timetable_progress, timetable_steps) = recorder();
+    let cancel = cancel_after(Duration::from_millis(200));
+    let (semesters, timetable) = tokio::join!(
+        with_progress(semesters_progress, fetch_semesters(&client)),
+        with_progress(
+            timetable_progress,
+            cancel.run(fetch_timetable(&client, "AP2024252".into()))
+        ),
+    );
+    assert!(semesters.is_ok());
+    assert!(matches!(timetable, Err(VtopError::Cancelled)));
+    assert_eq!(
+        *semesters_steps.lock().unwrap(),
+        vec![
+            VtopProgress::Fetching(FetchKind::Semesters),
+            VtopProgress::Parsing(FetchKind::Semesters),
+        ]
+    );
+    assert_eq!(
+        *timetable_steps.lock().unwrap(),
+        vec![VtopProgress::Fetching(FetchKind::Timetable)]
+    );
+}
+
+#[tokio::test]
+async fn cancelled_bundle_keeps_the_parts_that_finished() {
+    let mut client = client(spawn_fake_vtop().await);
+    vtop_client_login(&mut client).await.unwrap();
+
+    let started = Instant::now();
+    let bundle = fetch_semester_bundle(
+        &client,
+        "AP2024252".into(),
+        false,
+        Some(cancel_after(Duration::from_millis(200))),
+    )
+    .await;
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(bundle.timetable.is_none());
+    // Parts still waiting for their turn are cancelled with the slow one.
+    assert!(!bundle.failures.is_empty());
+    assert!(bundle
+        .failures
+        .iter()
+        .all(|failure| matches!(failure.error, VtopError::Cancelled)));
+    assert!(bundle
+        .failures
+        .iter()
+        .any(|failure| failure.part == FetchKind::Timetable));
+}
+
+#[tokio::test]
+async fn single_fetches_honour_a_cancelled_token() {
+    let mut client = client(spawn_fake_vtop().await);
+    vtop_client_login(&mut client).await.unwrap();
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let result = fetch_full_attendance(
+        &client,
+        "AP2024252".into(),
+        "AP2024252000123".into(),
+        "ETH".into(),
+        Some(cancel),
+    )
+    .await;
+    assert!(matches!(result, Err(VtopError::Cancelled)));
+}
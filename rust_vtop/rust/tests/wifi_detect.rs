@@ -0,0 +1,215 @@
+use std::net::SocketAddr;
+
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+use reqwest::Url;
+use rust_lib_vitapmate::api::vtop::paraser::wifi_portal::{fingerprint, next_hop};
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::vtop_client::VtopError;
+use rust_lib_vitapmate::api::vtop::wifi::profile::ConnectivityCheck;
+use rust_lib_vitapmate::api::vtop::wifi::{
+    detect_portal, Connectivity, NetworkProfile, NetworkProfiles, PortalKind, PortalVendor,
+};
+use tokio::net::TcpListener;
+
+fn url(text: &str) -> Url {
+    Url::parse(text).unwrap()
+}
+
+#[test]
+fn fingerprints_portals_by_url_or_page() {
+    let plain = url("http://10.0.0.1/");
+    assert_eq!(
+        fingerprint(&url("http://10.0.0.1:1000/fgtauth?0123"), ""),
+        PortalVendor::Fortinet
+    );
+    assert_eq!(
+        fingerprint(&plain, r#"<input type="hidden" NAME="magic" value="x">"#),
+        PortalVendor::Fortinet
+    );
+    assert_eq!(
+        fingerprint(&url("https://10.0.0.1:8090/"), ""),
+        PortalVendor::Sophos
+    );
+    assert_eq!(
+        fingerprint(&plain, "<title>Sophos Captive Portal</title>"),
+        PortalVendor::Sophos
+    );
+    assert_eq!(
+        fingerprint(&plain, "<title>Welcome</title>"),
+        PortalVendor::Unknown
+    );
+}
+
+#[test]
+fn follows_meta_refresh() {
+    let base = url("http://10.0.0.1/generate_204");
+    assert_eq!(
+        next_hop(
+            r#"<meta http-equiv="Refresh" content="0; URL='/fgtauth?abc'">"#,
+            &base
+        ),
+        Some(url("http://10.0.0.1/fgtauth?abc"))
+    );
+    assert_eq!(
+        next_hop(
+            r#"<meta http-equiv="refresh" content="1;url=https://portal:8090/httpclient.html">"#,
+            &base
+        ),
+        Some(url("https://portal:8090/httpclient.html"))
+    );
+    // Not a refresh.
+    assert_eq!(
+        next_hop(
+            r#"<meta http-equiv="content-type" content="text/html">"#,
+            &base
+        ),
+        None
+    );
+}
+
+#[test]
+fn follows_script_redirects() {
+    let base = url("http://10.0.0.1/hotspot-detect.html");
+    let hop = |code: &str| next_hop(&format!("<script>{}</script>", code), &base);
+    assert_eq!(
+        hop(r#"window.location = "http://10.0.0.1:1000/fgtauth?1";"#),
+        Some(url("http://10.0.0.1:1000/fgtauth?1"))
+    );
+    assert_eq!(
+        hop("window.location.href='/login?2'"),
+        Some(url("http://10.0.0.1/login?2"))
+    );
+    assert_eq!(
+        hop(r#"top.location.replace( "login.html" )"#),
+        Some(url("http://10.0.0.1/login.html"))
+    );
+    assert_eq!(
+        hop("document.location.assign('/portal')"),
+        Some(url("http://10.0.0.1/portal"))
+    );
+    // Mentions of `location` that are not redirects are skipped.
+    assert_eq!(
+        hop("var here = window.location; location = '/next';"),
+        Some(url("http://10.0.0.1/next"))
+    );
+    assert_eq!(hop("console.log(location.href)"), None);
+}
+
+#[test]
+fn follows_the_fortigate_portal_form() {
+    let page =
+        r#"<form id="portal_url" action="http://10.0.0.1:1000/fgtauth?3" method="get"></form>"#;
+    assert_eq!(
+        next_hop(page, &url("http://example.com/")),
+        Some(url("http://10.0.0.1:1000/fgtauth?3"))
+    );
+    assert_eq!(next_hop("<p>Hello</p>", &url("http://example.com/")), None);
+}
+
+// A network where `/generate_204` is intercepted with a meta refresh to a
+// FortiGate login page, `/hotspot-detect.html` with a script redirect to a
+// Sophos one, and `/online` answers as expected.
+async fn spawn_fake_network() -> SocketAddr {
+    let app = Router::new()
+        .route(
+            "/generate_204",
+            get(|| async { Html(r#"<meta http-equiv="refresh" content="0;url=/fgtauth?77">"#) }),
+        )
+        .route(
+            "/fgtauth",
+            get(|| async { Html(r#"<form><input type="hidden" name="magic" value="77"></form>"#) }),
+        )
+        .route(
+            "/hotspot-detect.html",
+            get(|| async { Html(r#"<script>window.location.href = "/redirect";</script>"#) }),
+        )
+        .route(
+            "/redirect",
+            get(|| async {
+                (StatusCode::FOUND, [(header::LOCATION, "/httpclient.html")]).into_response()
+            }),
+        )
+        .route("/httpclient.html", get(|| async { Html("<p>Sign in</p>") }))
+        .route("/online", get(|| async { StatusCode::NO_CONTENT }));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+fn network(addr: SocketAddr, check: &str, kind: PortalKind) -> NetworkProfiles {
+    let profile = match kind {
+        PortalKind::Fortinet => NetworkProfile::vitap_academic(),
+        PortalKind::Sophos => NetworkProfile::vitap_hostel(),
+    };
+    NetworkProfiles {
+        connectivity_checks: vec![ConnectivityCheck::new(
+            format!("http://{}{}", addr, check),
+            204,
+            None,
+        )],
+        profiles: vec![NetworkProfile {
+            base_url: format!("http://{}", addr),
+            hosts: vec![addr.to_string()],
+            ..profile
+        }],
+        ..NetworkProfiles::default()
+    }
+}
+
+#[tokio::test]
+async fn detects_a_portal_behind_a_meta_refresh() {
+    let addr = spawn_fake_network().await;
+    let profiles = network(addr, "/generate_204", PortalKind::Fortinet);
+    let detection = detect_portal(&profiles, &TlsPolicy::default())
+        .await
+        .unwrap();
+    assert_eq!(detection.connectivity, Connectivity::CaptivePortal);
+    assert_eq!(detection.vendor, PortalVendor::Fortinet);
+    assert_eq!(
+        detection.login_url,
+        Some(format!("http://{}/fgtauth?77", addr))
+    );
+    assert_eq!(detection.profile.as_deref(), Some("vitap-academic"));
+}
+
+#[tokio::test]
+async fn detects_a_portal_behind_a_script_redirect() {
+    let addr = spawn_fake_network().await;
+    let profiles = network(addr, "/hotspot-detect.html", PortalKind::Sophos);
+    let detection = detect_portal(&profiles, &TlsPolicy::default())
+        .await
+        .unwrap();
+    assert_eq!(detection.connectivity, Connectivity::CaptivePortal);
+    assert_eq!(detection.vendor, PortalVendor::Sophos);
+    assert_eq!(
+        detection.login_url,
+        Some(format!("http://{}/httpclient.html", addr))
+    );
+    assert_eq!(detection.profile.as_deref(), Some("vitap-hostel"));
+}
+
+#[tokio::test]
+async fn expected_answer_means_online() {
+    let addr = spawn_fake_network().await;
+    let profiles = network(addr, "/online", PortalKind::Sophos);
+    let detection = detect_portal(&profiles, &TlsPolicy::default())
+        .await
+        .unwrap();
+    assert_eq!(detection.connectivity, Connectivity::Online);
+    assert_eq!(detection.login_url, None);
+    // The portal host answers, so we know which network this is.
+    assert_eq!(detection.vendor, PortalVendor::Sophos);
+    assert_eq!(detection.profile.as_deref(), Some("vitap-hostel"));
+}
+
+#[tokio::test]
+async fn a_policy_that_cannot_be_applied_is_an_error() {
+    let addr = spawn_fake_network().await;
+    let profiles = network(addr, "/online", PortalKind::Sophos);
+    let detection = detect_portal(&profiles, &TlsPolicy::pinned(["not-base64!"])).await;
+    assert!(matches!(detection, Err(VtopError::ConfigurationError(_))));
+}
@@ -0,0 +1,171 @@
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{RawQuery, State};
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::wifi::{
+    spawn_keepalive, FortinetPortal, KeepaliveOptions, KeepaliveState, WifiOutcome,
+};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+const LOGIN_PAGE: &str = r#"<form><input type="hidden" name="magic" value="m4g1c"></form>"#;
+
+#[derive(Default)]
+struct FakePortal {
+    logins: AtomicUsize,
+    keepalives: AtomicUsize,
+    session: AtomicUsize,
+    active: AtomicBool,
+}
+
+// Mimics the FortiGate flow: a login form with a `magic` token, a script
+// redirect to `keepalive?<token>` after login, and a keepalive page that
+// refreshes itself after one second.
+async fn spawn_fake_portal() -> (SocketAddr, Arc<FakePortal>) {
+    let portal = Arc::new(FakePortal::default());
+    let app = Router::new()
+        .route("/login", get(|| async { Html(LOGIN_PAGE) }))
+        .route(
+            "/",
+            axum::routing::post(|State(portal): State<Arc<FakePortal>>, body: String| async move {
+                if !body.contains("password=secret") {
+                    return Html("<p>Authentication failed</p>".to_string());
+                }
+                let session = portal.logins.fetch_add(1, Ordering::SeqCst) + 1;
+                portal.session.store(session, Ordering::SeqCst);
+                portal.active.store(true, Ordering::SeqCst);
+                Html(format!(
+                    r#"<script>window.location="/keepalive?token{}";</script>"#,
+                    session
+                ))
+            }),
+        )
+        .route(
+            "/keepalive",
+            get(|State(portal): State<Arc<FakePortal>>, RawQuery(query): RawQuery| async move {
+                let expected = format!("token{}", portal.session.load(Ordering::SeqCst));
+                if !portal.active.load(Ordering::SeqCst) || query.as_deref() != Some(&expected) {
+                    return Html(LOGIN_PAGE.to_string());
+                }
+                portal.keepalives.fetch_add(1, Ordering::SeqCst);
+                Html(format!(
+                    r#"<script>var countDownTime=1; window.location.href="/keepalive?{}";</script>"#,
+                    expected
+                ))
+            }),
+        )
+        .with_state(portal.clone());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (addr, portal)
+}
+
+fn options() -> KeepaliveOptions {
+    KeepaliveOptions {
+        fallback_interval: Duration::from_secs(30),
+        min_interval: Duration::from_millis(100),
+        retry_delay: Duration::from_millis(100),
+    }
+}
+
+async fn next_state(states: &mut UnboundedReceiver<KeepaliveState>) -> KeepaliveState {
+    tokio::time::timeout(Duration::from_secs(5), states.recv())
+        .await
+        .expect("keepalive state in time")
+        .expect("keepalive channel open")
+}
+
+#[tokio::test]
+async fn logs_in_and_follows_the_keepalive_link() {
+    let (addr, fake) = spawn_fake_portal().await;
+    let portal = FortinetPortal::with_base_url(&TlsPolicy::default(), format!("http://{}", addr)).unwrap();
+    let (handle, mut states) =
+        spawn_keepalive(portal, "21BCE0001".into(), "secret".into(), options());
+
+    assert_eq!(next_state(&mut states).await, KeepaliveState::LoggingIn);
+    match next_state(&mut states).await {
+        KeepaliveState::Online {
+            keepalive_url,
+            refresh_in_seconds,
+        } => {
+            assert!(keepalive_url.ends_with("/keepalive?token1"));
+            assert_eq!(refresh_in_seconds, 1);
+        }
+        other => panic!("unexpected state {:?}", other),
+    }
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert!(fake.keepalives.load(Ordering::SeqCst) >= 3);
+    assert_eq!(fake.logins.load(Ordering::SeqCst), 1);
+
+    handle.stop().await;
+    assert_eq!(next_state(&mut states).await, KeepaliveState::Stopped);
+}
+
+#[tokio::test]
+async fn logs_in_again_when_the_session_is_dropped() {
+    let (addr, fake) = spawn_fake_portal().await;
+    let portal = FortinetPortal::with_base_url(&TlsPolicy::default(), format!("http://{}", addr)).unwrap();
+    let (handle, mut states) =
+        spawn_keepalive(portal, "21BCE0001".into(), "secret".into(), options());
+
+    assert_eq!(next_state(&mut states).await, KeepaliveState::LoggingIn);
+    assert!(matches!(
+        next_state(&mut states).await,
+        KeepaliveState::Online { .. }
+    ));
+
+    fake.active.store(false, Ordering::SeqCst);
+    assert_eq!(next_state(&mut states).await, KeepaliveState::SessionLost);
+    assert_eq!(next_state(&mut states).await, KeepaliveState::LoggingIn);
+    match next_state(&mut states).await {
+        KeepaliveState::Online { keepalive_url, .. } => {
+            assert!(keepalive_url.ends_with("/keepalive?token2"))
+        }
+        other => panic!("unexpected state {:?}", other),
+    }
+    assert_eq!(fake.logins.load(Ordering::SeqCst), 2);
+
+    handle.stop().await;
+}
+
+#[tokio::test]
+async fn gives_up_on_rejected_credentials() {
+    let (addr, fake) = spawn_fake_portal().await;
+    let portal = FortinetPortal::with_base_url(&TlsPolicy::default(), format!("http://{}", addr)).unwrap();
+    let (handle, mut states) =
+        spawn_keepalive(portal, "21BCE0001".into(), "wrong".into(), options());
+
+    assert_eq!(next_state(&mut states).await, KeepaliveState::LoggingIn);
+    assert_eq!(
+        next_state(&mut states).await,
+        KeepaliveState::Failed(WifiOutcome::InvalidCredentials)
+    );
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!handle.is_running());
+    assert_eq!(fake.logins.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn dropping_the_handle_stops_the_task() {
+    let (addr, _fake) = spawn_fake_portal().await;
+    let portal = FortinetPortal::with_base_url(&TlsPolicy::default(), format!("http://{}", addr)).unwrap();
+    let (handle, mut states) =
+        spawn_keepalive(portal, "21BCE0001".into(), "secret".into(), options());
+
+    assert_eq!(next_state(&mut states).await, KeepaliveState::LoggingIn);
+    assert!(matches!(
+        next_state(&mut states).await,
+        KeepaliveState::Online { .. }
+    ));
+    drop(handle);
+    assert_eq!(next_state(&mut states).await, KeepaliveState::Stopped);
+}
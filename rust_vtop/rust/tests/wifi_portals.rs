@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse};
+use axum::routing::{get, post};
+use axum::Router;
+use rust_lib_vitapmate::api::vtop::paraser::wifi_status::xml_text;
+use rust_lib_vitapmate::api::vtop::tls_policy::TlsPolicy;
+use rust_lib_vitapmate::api::vtop::wifi::{
+    perform_on_any, CampusPortal, FortinetPortal, NetworkProfile, NetworkProfiles, SophosPortal,
+    WifiAction, WifiOutcome, WifiPortal,
+};
+use tokio::net::TcpListener;
+
+const LOGIN_PAGE: &str = r#"<form><input type="hidden" name="magic" value="m4g1c"></form>"#;
+
+async fn serve(app: Router) -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr
+}
+
+// Nothing answers the internet check, so an unreachable portal means there
+// is no network.
+fn offline() -> NetworkProfiles {
+    NetworkProfiles {
+        internet_check_url: "http://127.0.0.1:9/".into(),
+        ..NetworkProfiles::default()
+    }
+}
+
+// A FortiGate whose login form is answered with `reply` and whose login
+// page is `status_page`.
+async fn fortinet(
+    reply: &'static str,
+    status: StatusCode,
+    status_page: &'static str,
+) -> FortinetPortal {
+    let app = Router::new()
+        .route(
+            "/login",
+            get(move || async move { (status, Html(status_page)) }),
+        )
+        .route("/", post(move || async move { Html(reply) }));
+    let addr = serve(app).await;
+    FortinetPortal::with_base_url(&TlsPolicy::default(), format!("http://{}", addr)).unwrap()
+}
+
+#[tokio::test]
+async fn fortinet_rejections_are_classified() {
+    let login = |reply| async move {
+        fortinet(reply, StatusCode::OK, LOGIN_PAGE)
+            .await
+            .login("21BCE0001", "secret")
+            .await
+    };
+    assert_eq!(
+        login("<p>Authentication failed</p>").await,
+        WifiOutcome::InvalidCredentials
+    );
+    assert_eq!(
+        login("<p>Sorry, user limit exceeded. Authentication failed</p>").await,
+        WifiOutcome::LimitReached
+    );
+    assert_eq!(
+        login("<p>Please try again later</p>").await,
+        WifiOutcome::Unknown("Please try again later".into())
+    );
+}
+
+#[tokio::test]
+async fn fortinet_status_needs_a_portal_answer() {
+    let status =
+        |code, page| async move { fortinet("", code, page).await.status("21BCE0001").await };
+    assert_eq!(
+        status(StatusCode::OK, LOGIN_PAGE).await,
+        WifiOutcome::LoggedOut
+    );
+    assert_eq!(
+        status(
+            StatusCode::OK,
+            r#"<script>window.location="/keepalive?0011";</script>"#
+        )
+        .await,
+        WifiOutcome::LoggedIn
+    );
+    // Neither a login form nor a keepalive page.
+    assert!(matches!(
+        status(StatusCode::OK, "<h1>Welcome</h1>").await,
+        WifiOutcome::Unknown(_)
+    ));
+    assert_eq!(
+        status(StatusCode::SERVICE_UNAVAILABLE, "<h1>Busy</h1>").await,
+        WifiOutcome::Unknown("The portal answered with HTTP 503.".into())
+    );
+}
+
+#[derive(Default)]
+struct Hostel {
+    logouts: AtomicUsize,
+}
+
+// A Sophos portal that answers login.xml with `reply` and reports `live`
+// for the status check.
+async fn sophos(
+    reply: &'static str,
+    live: &'static str,
+    delay: Duration,
+) -> (SophosPortal, Arc<Hostel>) {
+    let hostel = Arc::new(Hostel::default());
+    let app = Router::new()
+        .route("/login.xml", post(move || async move { Html(reply) }))
+        .route(
+            "/logout.xml",
+            post(move |State(hostel): State<Arc<Hostel>>| async move {
+                tokio::time::sleep(delay).await;
+                hostel.logouts.fetch_add(1, Ordering::SeqCst);
+                Html("<requestresponse><message>You&#39;ve signed out</message></requestresponse>")
+            }),
+        )
+        .route(
+            "/live",
+            get(
+                move |Query(query): Query<HashMap<String, String>>| async move {
+                    tokio::time::sleep(delay).await;
+                    match query.get("mode").map(String::as_str) {
+                        Some("192") => Html(live).into_response(),
+                        _ => StatusCode::BAD_REQUEST.into_response(),
+                    }
+                },
+            ),
+        )
+        .with_state(hostel.clone());
+    let addr = serve(app).await;
+    let profile = NetworkProfile {
+        base_url: format!("http://{}", addr),
+        ..NetworkProfile::vitap_hostel()
+    };
+    let portal = SophosPortal::from_profile(profile, &offline(), &TlsPolicy::default()).unwrap();
+    (portal, hostel)
+}
+
+async fn sophos_login(reply: &'static str) -> WifiOutcome {
+    let (portal, _) = sophos(reply, "", Duration::ZERO).await;
+    portal.login("21BCE0001", "secret").await
+}
+
+#[tokio::test]
+async fn sophos_replies_map_to_outcomes() {
+    assert_eq!(
+        sophos_login(
+            "<requestresponse><message><![CDATA[You are signed in as 21BCE0001]]></message></requestresponse>"
+        )
+        .await,
+        WifiOutcome::LoggedIn
+    );
+    assert_eq!(
+        sophos_login(
+            "<requestresponse><message>Your data transfer limit reached</message></requestresponse>"
+        )
+        .await,
+        WifiOutcome::LimitReached
+    );
+    assert_eq!(
+        sophos_login(
+            "<requestresponse><message>Invalid user name/password</message></requestresponse>"
+        )
+        .await,
+        WifiOutcome::InvalidCredentials
+    );
+    assert_eq!(
+        sophos_login("<requestresponse><message>Try later</message></requestresponse>").await,
+        WifiOutcome::Unknown("Try later".into())
+    );
+}
+
+#[tokio::test]
+async fn unreachable_portals_without_internet_mean_no_network() {
+    let profile = NetworkProfile {
+        base_url: "http://127.0.0.1:9".into(),
+        ..NetworkProfile::vitap_hostel()
+    };
+    let portal = SophosPortal::from_profile(profile, &offline(), &TlsPolicy::default()).unwrap();
+    assert_eq!(
+        portal.login("21BCE0001", "secret").await,
+        WifiOutcome::NoNetwork
+    );
+    assert_eq!(portal.status("21BCE0001").await, WifiOutcome::NoNetwork);
+}
+
+#[test]
+fn xml_text_reads_the_first_element() {
+    let xml = "<Response><Message><![CDATA[ Signed in ]]></Message>\
+               <message>second</message><empty></empty></Response>";
+    assert_eq!(xml_text(xml, "message").as_deref(), Some("Signed in"));
+    assert_eq!(xml_text(xml, "empty").as_deref(), Some(""));
+    assert_eq!(xml_text(xml, "status"), None);
+    // An element that is never closed is ignored.
+    assert_eq!(xml_text("<status>LIVE", "status"), None);
+}
+
+#[tokio::test]
+async fn status_prefers_the_portal_holding_the_session() {
+    // The academic portal answers at once that we are not logged in there;
+    // the hostel portal takes longer to report the live session.
+    let academic = fortinet("", StatusCode::OK, LOGIN_PAGE).await;
+    let (hostel, _) = sophos(
+        "",
+        "<requestresponse><status>LIVE</status></requestresponse>",
+        Duration::from_millis(300),
+    )
+    .await;
+    let portals = [
+        CampusPortal::Fortinet(academic),
+        CampusPortal::Sophos(hostel),
+    ];
+    assert_eq!(
+        perform_on_any(&portals, WifiAction::Status, "21BCE0001", "").await,
+        WifiOutcome::LoggedIn
+    );
+}
+
+#[tokio::test]
+async fn logout_reaches_every_portal() {
+    let (fast, fast_hostel) = sophos("", "", Duration::ZERO).await;
+    let (slow, slow_hostel) = sophos("", "", Duration::from_millis(300)).await;
+    let portals = [CampusPortal::Sophos(fast), CampusPortal::Sophos(slow)];
+    assert_eq!(
+        perform_on_any(&portals, WifiAction::Logout, "21BCE0001", "").await,
+        WifiOutcome::LoggedOut
+    );
+    assert_eq!(fast_hostel.logouts.load(Ordering::SeqCst), 1);
+    assert_eq!(slow_hostel.logouts.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn failures_are_reported_when_nothing_succeeds() {
+    let academic = fortinet("", StatusCode::BAD_GATEWAY, "").await;
+    let portals = [CampusPortal::Fortinet(academic)];
+    assert_eq!(
+        perform_on_any(&portals, WifiAction::Status, "21BCE0001", "").await,
+        WifiOutcome::Unknown("The portal answered with HTTP 502.".into())
+    );
+    assert_eq!(
+        perform_on_any(&[], WifiAction::Login, "21BCE0001", "secret").await,
+        WifiOutcome::Unknown(String::new())
+    );
+}
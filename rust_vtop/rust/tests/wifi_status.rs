@@ -0,0 +1,104 @@
+use rust_lib_vitapmate::api::vtop::paraser::wifi_status::parse_session_status;
+use rust_lib_vitapmate::api::vtop::wifi::ActiveSession;
+
+#[test]
+fn live_status_reads_user_quota_time_and_devices() {
+    let status = parse_session_status(
+        "<requestresponse><status><![CDATA[LIVE]]></status>\
+         <username>21BCE0001</username><remainingquota>1.5 GB</remainingquota>\
+         <sessiontime>01:02:03</sessiontime><loggedinusers>2</loggedinusers>\
+         </requestresponse>",
+    );
+    assert!(status.logged_in);
+    assert_eq!(status.username.as_deref(), Some("21BCE0001"));
+    assert_eq!(status.remaining_quota.as_deref(), Some("1.5 GB"));
+    assert_eq!(status.remaining_quota_bytes, Some(1_610_612_736));
+    assert_eq!(status.session_seconds, Some(3723));
+    assert_eq!(status.active_devices, Some(2));
+    assert!(!status.limit_reached);
+    assert_eq!(status.message, None);
+}
+
+#[test]
+fn keepalive_ack_and_signed_in_message_count_as_logged_in() {
+    let ack = parse_session_status("<requestresponse><ack><![CDATA[ack]]></ack></requestresponse>");
+    assert!(ack.logged_in);
+
+    let status = parse_session_status(
+        "<requestresponse><message><![CDATA[You are signed in as 21BCE0002]]></message>\
+         </requestresponse>",
+    );
+    assert!(status.logged_in);
+    assert_eq!(status.username.as_deref(), Some("21BCE0002"));
+}
+
+#[test]
+fn quota_units() {
+    let bytes = |quota: &str| {
+        parse_session_status(&format!("<remainingquota>{}</remainingquota>", quota))
+            .remaining_quota_bytes
+    };
+    assert_eq!(bytes("512MB"), Some(512 * 1024 * 1024));
+    assert_eq!(bytes("2048 KB"), Some(2048 * 1024));
+    assert_eq!(bytes("100 B"), Some(100));
+    assert_eq!(bytes("2g"), Some(2 * 1024 * 1024 * 1024));
+    // The portal leaves out the unit for megabytes.
+    assert_eq!(bytes("300"), Some(300 * 1024 * 1024));
+    assert_eq!(bytes("lots"), None);
+    assert_eq!(bytes("5 TB"), None);
+}
+
+#[test]
+fn session_time_formats() {
+    let seconds = |time: &str| {
+        parse_session_status(&format!("<sessiontime>{}</sessiontime>", time)).session_seconds
+    };
+    assert_eq!(seconds("01:02:03"), Some(3723));
+    assert_eq!(seconds("02:03"), Some(123));
+    assert_eq!(seconds(" 45 "), Some(45));
+    assert_eq!(seconds("1h"), None);
+    assert_eq!(seconds("01::03"), None);
+    // Too large to count, rather than a panic.
+    assert_eq!(seconds(&format!("{}:00", u64::MAX)), None);
+    assert_eq!(seconds(&format!("1:{}", u64::MAX)), None);
+}
+
+#[test]
+fn device_limit_lists_the_sessions_holding_the_account() {
+    let status = parse_session_status(
+        "<requestresponse><status>LOGIN</status>\
+         <message>Maximum login limit reached for this user</message>\
+         <session><ip>10.1.2.3</ip><mac>AA:BB</mac><logintime>10:00</logintime></session>\
+         <session><ipaddress>10.1.2.4</ipaddress></session>\
+         </requestresponse>",
+    );
+    assert!(!status.logged_in);
+    assert!(status.limit_reached);
+    assert_eq!(status.active_devices, Some(2));
+    assert_eq!(
+        status.active_sessions,
+        vec![
+            ActiveSession {
+                ip: Some("10.1.2.3".into()),
+                mac: Some("AA:BB".into()),
+                login_time: Some("10:00".into()),
+            },
+            ActiveSession {
+                ip: Some("10.1.2.4".into()),
+                ..ActiveSession::default()
+            },
+        ]
+    );
+}
+
+#[test]
+fn limit_wording() {
+    let limited = |message: &str| {
+        parse_session_status(&format!("<message>{}</message>", message)).limit_reached
+    };
+    assert!(limited("Login limit reached"));
+    assert!(limited("You have reached the maximum device limit"));
+    assert!(!limited("Your data limit is 2 GB"));
+    assert!(!limited("Maximum session time is 8 hours"));
+    assert!(!limited(""));
+}
@@ -1,7 +1,115 @@
 
 import { NextResponse } from 'next/server';
-import { execFile } from 'child_process';
+import { createHash } from 'crypto';
+import { createRequire } from 'module';
 import path from 'path';
+import type * as Native from '../../../../rust_vtop/rust/bindings/node';
+
+type NativeBinding = typeof Native;
+type VtopSession = Native.VtopSession;
+
+// Loaded at runtime so the bundler leaves the .node addon alone. Build it with
+// `npm run build` in rust_vtop/rust/bindings/node.
+let native: NativeBinding | undefined;
+function loadNative(): NativeBinding {
+    if (!native) {
+        const bindingPath = process.env.VTOP_NATIVE_PATH
+            || path.resolve(process.cwd(), 'rust_vtop/rust/bindings/node');
+        native = createRequire(path.join(process.cwd(), 'package.json'))(bindingPath) as NativeBinding;
+    }
+    return native;
+}
+
+// Logged-in sessions, kept between requests. Keyed by a hash of the
+// credentials so a wrong password never reuses someone's session. A session
+// idle for SESSION_IDLE_MS is dropped, and once MAX_SESSIONS are cached the
+// least recently used one makes room.
+const SESSION_IDLE_MS = 30 * 60 * 1000;
+const MAX_SESSIONS = 200;
+
+interface CachedSession {
+    session: Promise<VtopSession>;
+    lastUsed: number;
+}
+
+// Map order is insertion order and entries are re-inserted when used, so
+// the first entry is always the least recently used.
+const sessions = new Map<string, CachedSession>();
+
+function sessionKey(username: string, password: string): string {
+    return createHash('sha256').update(`${username.toUpperCase()}\0${password}`).digest('hex');
+}
+
+function evictSessions(now: number) {
+    for (const [key, cached] of sessions) {
+        if (now - cached.lastUsed <= SESSION_IDLE_MS) {
+            break;
+        }
+        sessions.delete(key);
+    }
+    while (sessions.size >= MAX_SESSIONS) {
+        sessions.delete(sessions.keys().next().value as string);
+    }
+}
+
+function forgetSession(key: string, session: Promise<VtopSession>) {
+    if (sessions.get(key)?.session === session) {
+        sessions.delete(key);
+    }
+}
+
+function sessionFor(binding: NativeBinding, key: string, username: string, password: string): Promise<VtopSession> {
+    const now = Date.now();
+    const cached = sessions.get(key);
+    sessions.delete(key);
+    if (cached && now - cached.lastUsed <= SESSION_IDLE_MS) {
+        sessions.set(key, { session: cached.session, lastUsed: now });
+        return cached.session;
+    }
+    evictSessions(now);
+    const session = binding.VtopSession.login(username, password);
+    sessions.set(key, { session, lastUsed: now });
+    session.catch(() => forgetSession(key, session));
+    return session;
+}
+
+// Re-logins in progress. Requests that find the same session expired wait
+// for the one login instead of each starting their own.
+const relogins = new WeakMap<VtopSession, Promise<void>>();
+
+function relogin(session: VtopSession): Promise<void> {
+    let pending = relogins.get(session);
+    if (!pending) {
+        pending = session.login().finally(() => relogins.delete(session));
+        relogins.set(session, pending);
+    }
+    return pending;
+}
+
+// VTOP drops idle sessions; log in again once and retry.
+async function withRelogin<T>(session: VtopSession, call: () => Promise<T>): Promise<T> {
+    try {
+        return await call();
+    } catch (error) {
+        if (!(error as Native.VtopError).authProblem) {
+            throw error;
+        }
+        await relogin(session);
+        return call();
+    }
+}
+
+function vtopErrorResponse(binding: NativeBinding, error: unknown, fallback: string) {
+    if (error instanceof binding.VtopError) {
+        console.error(`${fallback}:`, error.message, error.status ?? '');
+        return NextResponse.json(
+            { error: error.message },
+            { status: error.authProblem ? 401 : 502 },
+        );
+    }
+    console.error(`${fallback}:`, error);
+    return NextResponse.json({ error: fallback }, { status: 500 });
+}
 
 export async function POST(req: Request) {
     try {
@@ -24,78 +132,55 @@ export async function POST(req: Request) {
             return NextResponse.json(data, { status: response.status });
         }
 
-        // LOCAL MODE: Use the Windows .exe (for development)
-        const rustBinaryPath = path.resolve(
-            process.cwd(),
-            'rust_vtop/rust/target/debug/debug_timetable.exe'
-        );
-
-        const args = [username, password];
-        if (semesterId) args.push(semesterId);
-
-        return new Promise((resolve) => {
-            execFile(rustBinaryPath, args, (error, stdout, stderr) => {
-                if (error) {
-                    console.error('Rust execution error:', error);
-                    return resolve(NextResponse.json({
-                        error: 'Failed to execute VTOP fetcher',
-                        details: stderr || error.message
-                    }, { status: 500 }));
-                }
-
-                try {
-                    const jsonStartIndex = stdout.indexOf('{');
-                    const jsonEndIndex = stdout.lastIndexOf('}');
+        // LOCAL MODE: Call the Rust client in-process through the N-API binding
+        const native = loadNative();
+        const key = sessionKey(username, password);
+        const pending = sessionFor(native, key, username, password);
+        let session: VtopSession;
+        try {
+            session = await pending;
+        } catch (error) {
+            return vtopErrorResponse(native, error, 'Login failed');
+        }
 
-                    if (jsonStartIndex === -1 || jsonEndIndex === -1) {
-                        throw new Error("No JSON found in output");
+        try {
+            const semesters = (await withRelogin(session, () => session.semesters())).semesters;
+            if (semesters.length === 0) {
+                return NextResponse.json({ error: 'No semesters found' }, { status: 502 });
+            }
+
+            const timetable = await withRelogin(session, () =>
+                session.timetable(semesterId || semesters[0].id),
+            );
+
+            // Extract faculty from timetable slots
+            const facultyMap = new Map();
+            timetable.slots.forEach((slot) => {
+                if (slot.faculty && slot.faculty.trim() !== "") {
+                    const cabinId = slot.room_no || `UNKNOWN-${slot.faculty.replace(/\s+/g, '-')}`;
+                    if (!facultyMap.has(cabinId)) {
+                        facultyMap.set(cabinId, {
+                            cabinId,
+                            name: slot.faculty,
+                        });
                     }
+                }
+            });
 
-                    const jsonString = stdout.substring(jsonStartIndex, jsonEndIndex + 1);
-                    const responseData = JSON.parse(jsonString);
-
-                    if (responseData.error) {
-                        return resolve(NextResponse.json({
-                            error: responseData.error,
-                            details: responseData.details
-                        }, { status: 401 }));
-                    }
+            const facultyList = Array.from(facultyMap.values());
 
-                    const timetable = responseData.timetable || {};
-                    const semesters = responseData.semesters?.semesters || [];
-
-                    // Extract faculty from timetable slots
-                    const slots = timetable.slots || [];
-                    const facultyMap = new Map();
-
-                    slots.forEach((slot: any) => {
-                        if (slot.faculty && slot.faculty.trim() !== "") {
-                            const cabinId = slot.room_no || `UNKNOWN-${slot.faculty.replace(/\s+/g, '-')}`;
-                            if (!facultyMap.has(cabinId)) {
-                                facultyMap.set(cabinId, {
-                                    cabinId,
-                                    name: slot.faculty,
-                                });
-                            }
-                        }
-                    });
-
-                    const facultyList = Array.from(facultyMap.values());
-
-                    return resolve(NextResponse.json({
-                        success: true,
-                        faculty: facultyList,
-                        semesters: semesters
-                    }));
-                } catch (parseError) {
-                    console.error('JSON Parse Error:', parseError);
-                    return resolve(NextResponse.json({
-                        error: 'Failed to parse VTOP data',
-                        details: stdout
-                    }, { status: 500 }));
-                }
+            return NextResponse.json({
+                success: true,
+                faculty: facultyList,
+                semesters: semesters
             });
-        });
+        } catch (error) {
+            // Even a fresh login was refused, e.g. after a password change.
+            if ((error as Native.VtopError).authProblem) {
+                forgetSession(key, pending);
+            }
+            return vtopErrorResponse(native, error, 'Failed to fetch VTOP data');
+        }
 
     } catch (error) {
         console.error('API Error:', error);